@@ -18,14 +18,14 @@ impl ActivityApi {
 
     /// Fetch the activity feed
     ///
-    /// This method fetches the activity feed using multipart/form-data encoding,
-    /// encoding each struct field as a separate form part.
+    /// Sent as multipart/form-data, with each struct field as a separate form
+    /// part (see [`BodyEncoding::for_method`](crate::middleware::BodyEncoding::for_method)).
     ///
     /// # Arguments
     ///
     /// * `params` - Request parameters that will be encoded as multipart form data
     pub async fn feed(&self, params: ActivityFeedRequest) -> Result<ActivityFeedResponse> {
-        self.client.post_multipart("activity.feed", &params).await
+        self.client.post("activity.feed", &params).await
     }
 }
 
@@ -4,6 +4,7 @@
 
 use crate::client::SlackClient;
 use crate::error::Result;
+use crate::pagination::{impl_paginated, Paginator};
 use crate::types::ResponseMetadata;
 use serde::{Deserialize, Serialize};
 
@@ -167,6 +168,14 @@ impl AdminTeamsApi {
 
         self.client.post("admin.teams.list", &params).await
     }
+
+    /// Stream every workspace in an Enterprise Grid, following pagination cursors
+    pub fn list_stream(
+        &self,
+        params: AdminTeamsListRequest,
+    ) -> Paginator<AdminTeamsListRequest, AdminTeamsListResponse> {
+        Paginator::new(self.client.clone(), "admin.teams.list", params)
+    }
 }
 
 /// Admin Conversations API
@@ -273,7 +282,7 @@ pub struct AdminTeamsCreateResponse {
     pub team: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct AdminTeamsListRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
@@ -288,6 +297,8 @@ pub struct AdminTeamsListResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(AdminTeamsListRequest, AdminTeamsListResponse, teams: AdminTeam);
+
 #[derive(Debug, Deserialize)]
 pub struct AdminTeam {
     pub id: String,
@@ -4,6 +4,7 @@
 
 use crate::client::SlackClient;
 use crate::error::Result;
+use crate::pagination::impl_paginated;
 use serde::{Deserialize, Serialize};
 
 /// Apps API client
@@ -160,6 +161,12 @@ pub struct AppsEventAuthorizationsListResponse {
     pub response_metadata: Option<crate::types::ResponseMetadata>,
 }
 
+impl_paginated!(
+    AppsEventAuthorizationsListRequest,
+    AppsEventAuthorizationsListResponse,
+    authorizations: AppAuthorization
+);
+
 #[derive(Debug, Deserialize)]
 pub struct AppAuthorization {
     pub enterprise_id: Option<String>,
@@ -251,6 +258,12 @@ pub struct AppsPermissionsResourcesListResponse {
     pub response_metadata: Option<crate::types::ResponseMetadata>,
 }
 
+impl_paginated!(
+    AppsPermissionsResourcesListRequest,
+    AppsPermissionsResourcesListResponse,
+    resources: AppResource
+);
+
 #[derive(Debug, Deserialize)]
 pub struct AppResource {
     #[serde(rename = "type")]
@@ -4,6 +4,7 @@
 
 use crate::client::SlackClient;
 use crate::error::Result;
+use crate::pagination::impl_paginated;
 use serde::{Deserialize, Serialize};
 
 /// Auth API client
@@ -86,6 +87,8 @@ pub struct AuthTeamsListResponse {
     pub response_metadata: Option<crate::types::ResponseMetadata>,
 }
 
+impl_paginated!(AuthTeamsListRequest, AuthTeamsListResponse, teams: AuthTeam);
+
 #[derive(Debug, Deserialize)]
 pub struct AuthTeam {
     pub id: String,
@@ -4,6 +4,7 @@
 
 use crate::client::SlackClient;
 use crate::error::Result;
+use crate::pagination::impl_paginated;
 use crate::types::Message;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -639,6 +640,12 @@ pub struct ScheduledMessagesListResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(
+    ScheduledMessagesListRequest,
+    ScheduledMessagesListResponse,
+    scheduled_messages: ScheduledMessage
+);
+
 #[derive(Debug, Deserialize)]
 pub struct ScheduledMessage {
     pub id: String,
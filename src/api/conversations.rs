@@ -4,6 +4,8 @@
 
 use crate::client::SlackClient;
 use crate::error::Result;
+use crate::middleware::BodyEncoding;
+use crate::pagination::{impl_paginated, Paginator};
 use crate::types::{Channel, Message, ResponseMetadata};
 use serde::{Deserialize, Serialize};
 
@@ -48,6 +50,14 @@ impl ConversationsApi {
         self.client.post("conversations.list", &params).await
     }
 
+    /// Stream every channel, following pagination cursors
+    pub fn list_stream(
+        &self,
+        params: ListConversationsRequest,
+    ) -> Paginator<ListConversationsRequest, ListConversationsResponse> {
+        Paginator::new(self.client.clone(), "conversations.list", params)
+    }
+
     /// List direct message conversations
     ///
     /// Returns DMs (im) for the authenticated user.
@@ -166,6 +176,14 @@ impl ConversationsApi {
         self.client.post("conversations.history", &params).await
     }
 
+    /// Stream every message in a conversation, following pagination cursors
+    pub fn history_stream(
+        &self,
+        params: ConversationHistoryRequest,
+    ) -> Paginator<ConversationHistoryRequest, ConversationHistoryResponse> {
+        Paginator::new(self.client.clone(), "conversations.history", params)
+    }
+
     /// Get replies to a thread
     ///
     /// # Arguments
@@ -193,6 +211,20 @@ impl ConversationsApi {
         self.client.post("conversations.replies", &params).await
     }
 
+    /// Stream the parent message and every reply in a thread, following
+    /// pagination cursors
+    ///
+    /// Slack repeats the parent message at the start of every page; it is
+    /// only yielded once.
+    pub fn replies_stream(
+        &self,
+        params: ConversationRepliesRequest,
+    ) -> Paginator<ConversationRepliesRequest, ConversationRepliesResponse> {
+        Paginator::new(self.client.clone(), "conversations.replies", params).map_later_pages(
+            |request, page| page.messages.retain(|message| message.ts != request.ts),
+        )
+    }
+
     /// Get replies to a thread using multipart/form-data encoding
     ///
     /// This method sends the request using multipart/form-data with WebKit-style boundaries,
@@ -206,7 +238,7 @@ impl ConversationsApi {
         params: ConversationRepliesRequest,
     ) -> Result<ConversationRepliesResponse> {
         self.client
-            .post_multipart("conversations.replies", &params)
+            .post_with_encoding("conversations.replies", &params, BodyEncoding::Multipart)
             .await
     }
 
@@ -259,6 +291,14 @@ impl ConversationsApi {
         self.client.post("conversations.members", &params).await
     }
 
+    /// Stream every member of a conversation, following pagination cursors
+    pub fn members_stream(
+        &self,
+        params: ConversationMembersRequest,
+    ) -> Paginator<ConversationMembersRequest, ConversationMembersResponse> {
+        Paginator::new(self.client.clone(), "conversations.members", params)
+    }
+
     /// Open or resume a direct message or multi-person DM
     ///
     /// # Arguments
@@ -625,7 +665,7 @@ pub struct OpenConversationResponse {
     pub channel: Channel,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct ListConversationsRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exclude_archived: Option<bool>,
@@ -644,6 +684,12 @@ pub struct ListConversationsResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(
+    ListConversationsRequest,
+    ListConversationsResponse,
+    channels: Channel
+);
+
 #[derive(Debug, Deserialize)]
 pub struct ConversationInfoResponse {
     pub channel: Channel,
@@ -718,6 +764,12 @@ pub struct ConversationHistoryResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(
+    ConversationHistoryRequest,
+    ConversationHistoryResponse,
+    messages: Message
+);
+
 #[derive(Debug, Serialize)]
 pub struct ConversationRepliesRequest {
     pub channel: String,
@@ -742,6 +794,12 @@ pub struct ConversationRepliesResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(
+    ConversationRepliesRequest,
+    ConversationRepliesResponse,
+    messages: Message
+);
+
 #[derive(Debug, Serialize)]
 pub struct InviteConversationRequest {
     pub channel: String,
@@ -778,6 +836,12 @@ pub struct ConversationMembersResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(
+    ConversationMembersRequest,
+    ConversationMembersResponse,
+    members: String
+);
+
 #[derive(Debug, Serialize)]
 pub struct RenameConversationRequest {
     pub channel: String,
@@ -1026,6 +1090,12 @@ pub struct RequestSharedInviteListResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(
+    RequestSharedInviteListRequest,
+    RequestSharedInviteListResponse,
+    invites: SharedInviteRequest
+);
+
 #[derive(Debug, Deserialize)]
 pub struct SharedInviteRequest {
     #[serde(default)]
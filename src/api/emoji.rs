@@ -4,6 +4,7 @@
 
 use crate::client::SlackClient;
 use crate::error::Result;
+use crate::pagination::impl_paginated;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -159,6 +160,8 @@ pub struct EmojiAdminListResponse {
     pub response_metadata: Option<crate::types::ResponseMetadata>,
 }
 
+impl_paginated!(EmojiAdminListRequest, EmojiAdminListResponse, emoji: AdminEmoji);
+
 #[derive(Debug, Deserialize)]
 pub struct AdminEmoji {
     pub name: String,
@@ -3,9 +3,10 @@
 //! Methods for uploading and managing files.
 
 use crate::client::SlackClient;
-use crate::error::{Result, SlackError};
-use crate::types::{File, ResponseMetadata, SlackResponse};
-use reqwest::multipart::{Form, Part};
+use crate::error::Result;
+use crate::middleware::{FilePart, MultipartBody, RequestBody};
+use crate::pagination::{impl_paginated, Paginator};
+use crate::types::{File, ResponseMetadata};
 use serde::{Deserialize, Serialize};
 
 /// Files API client
@@ -31,54 +32,14 @@ impl FilesApi {
         content: Vec<u8>,
         filename: &str,
     ) -> Result<FileUploadResponse> {
-        let url = format!("{}/files.upload", self.client.base_url);
-        let headers = self.client.auth.build_headers();
-
-        // Build multipart form
-        let file_part = Part::bytes(content)
-            .file_name(filename.to_string())
-            .mime_str("application/octet-stream")
-            .map_err(|e| SlackError::config_error(format!("Invalid mime type: {}", e)))?;
-
-        let mut form = Form::new()
+        let body = MultipartBody::new()
             .text("channels", channels.join(","))
-            .part("file", file_part);
-
-        // Add filename as a form field too
-        form = form.text("filename", filename.to_string());
-
-        let response = self
-            .client
-            .http
-            .post(&url)
-            .headers(headers)
-            .multipart(form)
-            .send()
-            .await?;
-
-        // Check for rate limiting
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
-            return Err(SlackError::RateLimitExceeded { retry_after });
-        }
-
-        let slack_response: SlackResponse<FileUploadResponse> = response.json().await?;
-
-        if !slack_response.ok {
-            let error_msg = slack_response
-                .error
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(SlackError::api_error("files.upload", error_msg));
-        }
+            .text("filename", filename)
+            .file(FilePart::new("file", filename, content).mime_type("application/octet-stream"));
 
-        slack_response
-            .data
-            .ok_or_else(|| SlackError::api_error("files.upload", "No data in response"))
+        self.client
+            .send("files.upload", RequestBody::Multipart(body))
+            .await
     }
 
     /// Upload a file with custom parameters (for text content)
@@ -123,63 +84,27 @@ impl FilesApi {
         filename: &str,
         options: FileUploadOptions,
     ) -> Result<FileUploadResponse> {
-        let url = format!("{}/files.upload", self.client.base_url);
-        let headers = self.client.auth.build_headers();
-
-        let file_part = Part::bytes(content)
-            .file_name(filename.to_string())
-            .mime_str("application/octet-stream")
-            .map_err(|e| SlackError::config_error(format!("Invalid mime type: {}", e)))?;
-
-        let mut form = Form::new()
+        let mut body = MultipartBody::new()
             .text("channels", channels.join(","))
-            .text("filename", filename.to_string())
-            .part("file", file_part);
+            .text("filename", filename)
+            .file(FilePart::new("file", filename, content).mime_type("application/octet-stream"));
 
         if let Some(title) = options.title {
-            form = form.text("title", title);
+            body = body.text("title", title);
         }
         if let Some(initial_comment) = options.initial_comment {
-            form = form.text("initial_comment", initial_comment);
+            body = body.text("initial_comment", initial_comment);
         }
         if let Some(thread_ts) = options.thread_ts {
-            form = form.text("thread_ts", thread_ts);
+            body = body.text("thread_ts", thread_ts);
         }
         if let Some(filetype) = options.filetype {
-            form = form.text("filetype", filetype);
-        }
-
-        let response = self
-            .client
-            .http
-            .post(&url)
-            .headers(headers)
-            .multipart(form)
-            .send()
-            .await?;
-
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
-            return Err(SlackError::RateLimitExceeded { retry_after });
+            body = body.text("filetype", filetype);
         }
 
-        let slack_response: SlackResponse<FileUploadResponse> = response.json().await?;
-
-        if !slack_response.ok {
-            let error_msg = slack_response
-                .error
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(SlackError::api_error("files.upload", error_msg));
-        }
-
-        slack_response
-            .data
-            .ok_or_else(|| SlackError::api_error("files.upload", "No data in response"))
+        self.client
+            .send("files.upload", RequestBody::Multipart(body))
+            .await
     }
 
     /// Get information about a file
@@ -333,6 +258,14 @@ impl FilesApi {
         self.client.post("files.remote.list", &params).await
     }
 
+    /// Stream every remote file, following pagination cursors
+    pub fn remote_list_stream(
+        &self,
+        params: RemoteListRequest,
+    ) -> Paginator<RemoteListRequest, RemoteListResponse> {
+        Paginator::new(self.client.clone(), "files.remote.list", params)
+    }
+
     /// Remove a remote file
     ///
     /// # Arguments
@@ -627,7 +560,7 @@ pub struct RemoteInfoRequest {
     pub file: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct RemoteListRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub channel: Option<String>,
@@ -688,6 +621,8 @@ pub struct RemoteListResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(RemoteListRequest, RemoteListResponse, files: File);
+
 #[derive(Debug, Deserialize)]
 pub struct RemoteRemoveResponse {}
 
@@ -5,6 +5,7 @@
 
 use crate::client::SlackClient;
 use crate::error::Result;
+use crate::pagination::{impl_paginated, Paginator};
 use crate::types::ResponseMetadata;
 use serde::{Deserialize, Serialize};
 
@@ -242,6 +243,14 @@ impl ListsApi {
         self.client.post("lists.items.list", &params).await
     }
 
+    /// Stream every item in a list, following pagination cursors
+    pub fn items_list_stream(
+        &self,
+        params: ItemsListRequest,
+    ) -> Paginator<ItemsListRequest, ItemsListResponse> {
+        Paginator::new(self.client.clone(), "lists.items.list", params)
+    }
+
     // ========== Download/Export ==========
 
     /// Start a list export
@@ -427,6 +436,8 @@ pub struct ItemsListResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(ItemsListRequest, ItemsListResponse, items: ListItem);
+
 #[derive(Debug, Deserialize)]
 pub struct ListItem {
     pub id: String,
@@ -4,9 +4,9 @@
 
 use std::collections::HashMap;
 
+use crate::client::SlackClient;
 use crate::error::Result;
-use crate::types::Message;
-use crate::{client::SlackClient, types::SlackResponse};
+use crate::types::{Message, SlackResponse};
 use serde::{Deserialize, Serialize};
 
 /// Messages API client
@@ -21,19 +21,14 @@ impl MessagesApi {
 
     /// List messages by channel and timestamp
     ///
-    /// This method lists messages using multipart/form-data encoding,
-    /// encoding each struct field as a separate form part.
+    /// Sent as multipart/form-data, with each struct field as a separate form
+    /// part (see [`BodyEncoding::for_method`](crate::middleware::BodyEncoding::for_method)).
     ///
     /// # Arguments
     ///
     /// * `params` - Request parameters that will be encoded as multipart form data
     pub async fn list(&self, params: MessagesListRequest) -> Result<MessagesListResponse> {
-        let x = self
-            .client
-            .post_multipart_direct("messages.list", &params)
-            .await?;
-
-        Ok(x)
+        self.client.post("messages.list", &params).await
     }
 }
 
@@ -54,14 +49,28 @@ pub struct ChanTimestamps {
     pub timestamps: Vec<String>,
 }
 
+#[allow(deprecated)]
 #[derive(Debug, Deserialize)]
 pub struct MessagesListResponse {
     #[serde(default)]
     pub messages_data: HashMap<String, MessagesData>,
-    #[serde(flatten)]
+    /// Always an `ok` response: errors are returned as [`SlackError`](crate::SlackError)
+    #[deprecated(note = "API errors are returned as `SlackError`; this is always `ok: true`")]
+    #[serde(skip, default = "ok_response")]
     pub base: SlackResponse<()>,
 }
 
+fn ok_response() -> SlackResponse<()> {
+    SlackResponse {
+        ok: true,
+        error: None,
+        warning: None,
+        needed: None,
+        provided: None,
+        data: None,
+    }
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub struct MessagesData {
     pub messages: Vec<Message>,
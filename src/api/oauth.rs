@@ -26,31 +26,55 @@ pub struct OAuthAccessRequest {
     pub redirect_uri: Option<String>,
 }
 
+/// Request for oauth.v2.access with a refresh token
+#[derive(Debug, Serialize)]
+pub struct OAuthRefreshRequest {
+    /// Always `refresh_token`
+    pub grant_type: String,
+    /// The refresh token from the previous token exchange
+    pub refresh_token: String,
+    /// Your app's client ID
+    pub client_id: String,
+    /// Your app's client secret
+    pub client_secret: String,
+}
+
 /// Response from oauth.v2.access
 #[derive(Debug, Deserialize)]
 pub struct OAuthAccessResponse {
+    #[serde(default = "crate::types::default_true")]
     pub ok: bool,
+    /// Bot token; empty when only user scopes were requested
+    #[serde(default)]
     pub access_token: String,
+    #[serde(default)]
     pub token_type: String,
+    #[serde(default)]
     pub scope: String,
     pub bot_user_id: Option<String>,
     pub app_id: String,
+    /// Installed team; empty for org-wide Enterprise Grid installs
+    #[serde(default, deserialize_with = "crate::types::null_as_default")]
     pub team: TeamInfo,
     pub enterprise: Option<EnterpriseInfo>,
     pub authed_user: Option<AuthedUser>,
     pub incoming_webhook: Option<IncomingWebhook>,
     pub is_enterprise_install: Option<bool>,
+    /// Refresh token, when token rotation is enabled
+    pub refresh_token: Option<String>,
+    /// Seconds until `access_token` expires, when token rotation is enabled
+    pub expires_in: Option<u64>,
 }
 
 /// Team information in OAuth response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct TeamInfo {
     pub id: String,
     pub name: String,
 }
 
 /// Enterprise information in OAuth response
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct EnterpriseInfo {
     pub id: String,
     pub name: String,
@@ -63,6 +87,10 @@ pub struct AuthedUser {
     pub scope: Option<String>,
     pub access_token: Option<String>,
     pub token_type: Option<String>,
+    /// Refresh token for the user token, when token rotation is enabled
+    pub refresh_token: Option<String>,
+    /// Seconds until the user token expires, when token rotation is enabled
+    pub expires_in: Option<u64>,
 }
 
 /// Incoming webhook configuration
@@ -88,6 +116,7 @@ pub struct OAuthExchangeRequest {
 /// Response from oauth.v2.exchange
 #[derive(Debug, Deserialize)]
 pub struct OAuthExchangeResponse {
+    #[serde(default = "crate::types::default_true")]
     pub ok: bool,
     pub access_token: String,
     pub token_type: String,
@@ -151,6 +180,51 @@ impl OAuthApi {
         self.client.post("oauth.v2.access", &request).await
     }
 
+    /// Exchange a refresh token for a new access token
+    ///
+    /// Apps with token rotation enabled get short-lived access tokens
+    /// (`xoxe.xoxb-...`, `xoxe.xoxp-...`) together with a refresh token. Each
+    /// refresh returns a new access token and a new refresh token; the old
+    /// refresh token stops working. To have the client rotate tokens
+    /// automatically, see [`TokenRotation`](crate::rotation::TokenRotation).
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_token` - The refresh token from the previous exchange
+    /// * `client_id` - Your app's client ID
+    /// * `client_secret` - Your app's client secret
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use slacko::{SlackClient, AuthConfig};
+    /// # let client = SlackClient::new(AuthConfig::bot("xoxe.xoxb-token"))?;
+    /// let response = client
+    ///     .oauth()
+    ///     .refresh("xoxe-1-refresh", "your_client_id", "your_client_secret")
+    ///     .await?;
+    ///
+    /// println!("New access token: {}", response.access_token);
+    /// println!("Expires in: {:?}s", response.expires_in);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn refresh(
+        &self,
+        refresh_token: &str,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<OAuthAccessResponse> {
+        let request = OAuthRefreshRequest {
+            grant_type: "refresh_token".to_string(),
+            refresh_token: refresh_token.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+        };
+        self.client.post("oauth.v2.access", &request).await
+    }
+
     /// Exchange a legacy token for a new workspace token
     ///
     /// This method exchanges a legacy Slack API token (xoxp-, xoxb-, or xoxa-)
@@ -35,9 +35,11 @@ pub struct OpenIDTokenRequest {
 /// Response from openid.connect.token
 #[derive(Debug, Deserialize)]
 pub struct OpenIDTokenResponse {
+    #[serde(default = "crate::types::default_true")]
     pub ok: bool,
     pub access_token: String,
     pub token_type: String,
+    /// Signed JWT with the user's identity; see [`IdTokenVerifier`](crate::oidc::IdTokenVerifier)
     pub id_token: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refresh_token: Option<String>,
@@ -47,6 +49,7 @@ pub struct OpenIDTokenResponse {
 /// Response from openid.connect.userInfo
 #[derive(Debug, Deserialize)]
 pub struct UserInfoResponse {
+    #[serde(default = "crate::types::default_true")]
     pub ok: bool,
     pub sub: String, // User ID
     #[serde(rename = "https://slack.com/user_id")]
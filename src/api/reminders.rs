@@ -118,103 +118,26 @@ impl RemindersApi {
         filter: Option<&str>,
         cursor: Option<&str>,
     ) -> Result<SavedListResponse> {
-        let url = format!("{}/saved.list", self.client.base_url);
-        let headers = self.client.auth.build_headers();
-
         // Form-urlencoded without token (auth header provides it)
         // Note: limit must be <= 50
-        let mut params = vec![
-            ("limit", "50".to_string()),
-            ("include_tombstones", "true".to_string()),
-        ];
+        let mut params = vec![("limit", "50"), ("include_tombstones", "true")];
 
         if let Some(f) = filter {
-            params.push(("filter", f.to_string()));
+            params.push(("filter", f));
         }
 
         if let Some(c) = cursor {
-            params.push(("cursor", c.to_string()));
-        }
-
-        let response = self
-            .client
-            .http
-            .post(&url)
-            .headers(headers)
-            .form(&params)
-            .send()
-            .await?;
-
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
-            return Err(crate::error::SlackError::RateLimitExceeded { retry_after });
-        }
-
-        let text = response.text().await?;
-
-        let slack_response: crate::types::SlackResponse<SavedListResponse> =
-            serde_json::from_str(&text).map_err(|e| {
-                crate::error::SlackError::api_error(
-                    "saved.list",
-                    format!("Parse error: {} - {}", e, &text[..text.len().min(200)]),
-                )
-            })?;
-
-        if !slack_response.ok {
-            let error_msg = slack_response
-                .error
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(crate::error::SlackError::api_error("saved.list", error_msg));
+            params.push(("cursor", c));
         }
 
-        slack_response
-            .data
-            .ok_or_else(|| crate::error::SlackError::api_error("saved.list", "No data in response"))
+        self.client.post_form("saved.list", &params).await
     }
 
     /// Delete a saved item by ID
     pub async fn delete_saved(&self, item_id: &str) -> Result<SavedDeleteResponse> {
-        let url = format!("{}/saved.delete", self.client.base_url);
-        let headers = self.client.auth.build_headers();
-
         let params = [("item_type", "reminder"), ("item_id", item_id)];
 
-        let response = self
-            .client
-            .http
-            .post(&url)
-            .headers(headers)
-            .form(&params)
-            .send()
-            .await?;
-
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
-            return Err(crate::error::SlackError::RateLimitExceeded { retry_after });
-        }
-
-        let slack_response: crate::types::SlackResponse<SavedDeleteResponse> =
-            response.json().await?;
-
-        if !slack_response.ok {
-            let error_msg = slack_response
-                .error
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(crate::error::SlackError::api_error(
-                "saved.delete",
-                error_msg,
-            ));
-        }
+        let _: serde_json::Value = self.client.post_form("saved.delete", &params).await?;
 
         Ok(SavedDeleteResponse {})
     }
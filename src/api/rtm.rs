@@ -4,15 +4,29 @@
 
 use crate::client::SlackClient;
 use crate::error::{Result, SlackError};
+use crate::events::Event;
 use crate::types::RtmConnectResponse;
-use futures_util::{SinkExt, StreamExt};
+use crate::websocket;
+use futures_util::future::BoxFuture;
+use futures_util::{FutureExt, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, Semaphore};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 /// RTM API client
 pub struct RtmApi {
     client: SlackClient,
+    sender: RtmSender,
+    max_concurrency: usize,
+    shutdown: CancellationToken,
 }
 
 /// RTM event types
@@ -40,9 +54,162 @@ pub struct RtmMessageEvent {
 /// Callback type for RTM message handlers
 pub type MessageHandler = Box<dyn Fn(RtmMessageEvent) + Send + Sync>;
 
+/// Sends frames over the RTM connection of the [`RtmApi`] it came from
+///
+/// Clones share the connection. While the API is reconnecting, sends fail
+/// with [`SlackError::WebSocketError`].
+#[derive(Clone, Default)]
+pub struct RtmSender {
+    inner: Arc<SenderInner>,
+}
+
+#[derive(Default)]
+struct SenderInner {
+    /// Outgoing frames for the open connection, if any
+    connection: Mutex<Option<mpsc::UnboundedSender<String>>>,
+    next_id: AtomicU64,
+    /// Frames waiting for a reply, by `id`
+    replies: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
+}
+
+impl RtmSender {
+    /// Ping the server and wait for the matching pong
+    ///
+    /// Returns the round trip time. Fails if the connection closes first.
+    pub async fn ping(&self) -> Result<Duration> {
+        let started = Instant::now();
+        let (id, reply) = self.request();
+        if let Err(e) = self.send(json!({ "id": id, "type": "ping" })) {
+            self.inner.replies.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+        reply.await.map_err(|_| {
+            SlackError::websocket_error("RTM connection closed before the pong arrived")
+        })?;
+        Ok(started.elapsed())
+    }
+
+    /// Show the user as typing in `channel`
+    ///
+    /// The indicator disappears after a few seconds or when the user posts.
+    pub fn typing(&self, channel: &str) -> Result<()> {
+        let id = self.next_id();
+        self.send(json!({ "id": id, "type": "typing", "channel": channel }))
+    }
+
+    /// Receive `presence_change` events for these users only
+    ///
+    /// Replaces the previous subscription; an empty list unsubscribes.
+    pub fn presence_sub(&self, user_ids: &[&str]) -> Result<()> {
+        self.send(json!({ "type": "presence_sub", "ids": user_ids }))
+    }
+
+    fn next_id(&self) -> u64 {
+        self.inner.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Reserve an `id` whose reply is delivered to the receiver
+    fn request(&self) -> (u64, oneshot::Receiver<Value>) {
+        let id = self.next_id();
+        let (tx, rx) = oneshot::channel();
+        self.inner.replies.lock().unwrap().insert(id, tx);
+        (id, rx)
+    }
+
+    fn send(&self, frame: Value) -> Result<()> {
+        let connection = self.inner.connection.lock().unwrap().clone();
+        connection
+            .and_then(|connection| connection.send(frame.to_string()).ok())
+            .ok_or_else(|| SlackError::websocket_error("RTM is not connected"))
+    }
+
+    /// Route a reply to its request, returning `false` if nobody waits for it
+    fn deliver_reply(&self, value: &Value) -> bool {
+        let Some(id) = value.get("reply_to").and_then(Value::as_u64) else {
+            return false;
+        };
+        match self.inner.replies.lock().unwrap().remove(&id) {
+            Some(reply) => {
+                let _ = reply.send(value.clone());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Route frames to a new connection
+    fn attach(&self, connection: mpsc::UnboundedSender<String>) {
+        *self.inner.connection.lock().unwrap() = Some(connection);
+    }
+
+    /// Stop sending and fail the requests still waiting for a reply
+    fn detach(&self) {
+        *self.inner.connection.lock().unwrap() = None;
+        self.inner.replies.lock().unwrap().clear();
+    }
+}
+
+/// Default limit on async handlers running at once
+const DEFAULT_MAX_CONCURRENCY: usize = 32;
+
+type SyncHandler = Arc<dyn Fn(Value) + Send + Sync>;
+type AsyncHandler = Arc<dyn Fn(Event) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// A handler and how to run it
+enum Handler {
+    /// Called inline in the read loop with the raw event
+    Sync(SyncHandler),
+    /// Spawned on its own task, at most `permits` at a time
+    Async {
+        handler: AsyncHandler,
+        permits: Arc<Semaphore>,
+    },
+}
+
+/// How an RTM connection ended
+enum Ended {
+    /// Closed by the server or dropped without an error
+    Closed,
+    /// The server said `goodbye`; a new connection should be opened
+    Goodbye,
+    /// The shutdown token was cancelled
+    Stopped,
+}
+
 impl RtmApi {
     pub(crate) fn new(client: SlackClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            sender: RtmSender::default(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Limit how many async handlers run at once (default: 32)
+    ///
+    /// When the limit is reached the connection stops reading until a
+    /// handler finishes.
+    pub fn max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max.max(1);
+        self
+    }
+
+    /// Stop listening when `token` is cancelled
+    ///
+    /// The connection is closed, handlers already running are left to
+    /// finish, and the `start*` methods return `Ok(())`.
+    pub fn shutdown(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
+    /// Send pings, typing indicators and presence subscriptions
+    ///
+    /// The sender works while one of this API's `start*` methods is
+    /// connected, and keeps working across reconnects.
+    pub fn sender(&self) -> RtmSender {
+        self.sender.clone()
     }
 
     /// Connect to the RTM API
@@ -56,6 +223,9 @@ impl RtmApi {
 
     /// Start an RTM connection and listen for events
     ///
+    /// Only plain messages from others reach the handler; use
+    /// [`start_async`](Self::start_async) to receive every event.
+    ///
     /// # Arguments
     ///
     /// * `on_message` - Callback function for message events
@@ -77,41 +247,232 @@ impl RtmApi {
         F: Fn(RtmMessageEvent) + Send + Sync + 'static,
     {
         let rtm_info = self.connect().await?;
-        let ws_url = &rtm_info.url;
         let bot_id = rtm_info.self_info.id.clone();
 
-        info!("Connecting to RTM WebSocket: {}", ws_url);
+        let handler = Handler::Sync(Arc::new(move |value| {
+            if let Ok(event) = serde_json::from_value::<RtmMessageEvent>(value) {
+                // Only process message events
+                if event.event_type == "message" {
+                    // Skip messages from ourselves
+                    if event.bot_id.as_ref() == Some(&bot_id) {
+                        return;
+                    }
+
+                    // Call the message handler
+                    on_message(event);
+                }
+            }
+        }));
+        self.run(&rtm_info.url, &handler).await.map(|_| ())
+    }
+
+    /// Start an RTM connection and receive every event, typed
+    ///
+    /// Unlike [`start`](Self::start), this passes all events, including the
+    /// app's own messages, as [`Event`]s.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use slacko::{SlackClient, AuthConfig};
+    /// use slacko::events::Event;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SlackClient::new(AuthConfig::oauth("token"))?;
+    /// client.rtm().start_events(|event| match event {
+    ///     Event::Message(message) => println!("Message: {:?}", message.text),
+    ///     Event::UserTyping(typing) => println!("{} is typing", typing.user),
+    ///     _ => {}
+    /// }).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_events<F>(&self, handler: F) -> Result<()>
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        let rtm_info = self.connect().await?;
+        let handler = Handler::Sync(Arc::new(move |value| handler(Event::from_value(value))));
+        self.run(&rtm_info.url, &handler).await.map(|_| ())
+    }
 
-        let (ws_stream, _) = connect_async(ws_url)
+    /// Start an RTM connection with an async handler for every event
+    ///
+    /// Each event is handled on its own task; at most
+    /// [`max_concurrency`](Self::max_concurrency) handlers run at once. Use
+    /// [`sender`](Self::sender) to reply with typing indicators or pings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use slacko::{SlackClient, AuthConfig};
+    /// use slacko::events::Event;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SlackClient::new(AuthConfig::oauth("token"))?;
+    /// let rtm = client.rtm();
+    /// let sender = rtm.sender();
+    /// rtm.start_async(move |event| {
+    ///     let sender = sender.clone();
+    ///     async move {
+    ///         if let Event::Message(message) = event {
+    ///             if let Some(channel) = &message.channel {
+    ///                 sender.typing(channel).ok();
+    ///             }
+    ///         }
+    ///     }
+    /// })
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_async<F, Fut>(&self, handler: F) -> Result<()>
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let rtm_info = self.connect().await?;
+        self.run(&rtm_info.url, &self.async_handler(handler))
             .await
-            .map_err(|e| SlackError::websocket_error(format!("Failed to connect: {}", e)))?;
+            .map(|_| ())
+    }
+
+    /// Start RTM with an async handler and automatic reconnection
+    ///
+    /// Like [`start_async`](Self::start_async), but reconnects with
+    /// exponential backoff if the connection drops or is closed, and right
+    /// away after a `goodbye` event. Runs until the
+    /// [`shutdown`](Self::shutdown) token is cancelled.
+    pub async fn start_async_with_reconnect<F, Fut>(&self, handler: F) -> Result<()>
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler = self.async_handler(handler);
+        let mut backoff = Duration::from_secs(1);
+        let max_backoff = Duration::from_secs(60);
+
+        loop {
+            let connected = tokio::select! {
+                connected = self.connect() => connected,
+                _ = self.shutdown.cancelled() => break Ok(()),
+            };
+            let rtm_info = match connected {
+                Ok(info) => {
+                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
+                    info
+                }
+                Err(e) => {
+                    error!("Failed to open RTM connection: {}", e);
+                    if !self.wait(&mut backoff, max_backoff).await {
+                        break Ok(());
+                    }
+                    continue;
+                }
+            };
+
+            match self.run(&rtm_info.url, &handler).await {
+                Ok(Ended::Goodbye) => info!("RTM server said goodbye, reconnecting..."),
+                Ok(Ended::Stopped) => break Ok(()),
+                Ok(Ended::Closed) => {
+                    warn!("RTM connection closed, reconnecting...");
+                    if !self.wait(&mut backoff, max_backoff).await {
+                        break Ok(());
+                    }
+                }
+                Err(e) => {
+                    warn!("RTM connection error: {}, reconnecting...", e);
+                    if !self.wait(&mut backoff, max_backoff).await {
+                        break Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sleep for `backoff`, then double it up to `max`
+    ///
+    /// Returns `false` if the shutdown token was cancelled meanwhile.
+    async fn wait(&self, backoff: &mut Duration, max: Duration) -> bool {
+        tokio::select! {
+            _ = tokio::time::sleep(*backoff) => {
+                *backoff = (*backoff * 2).min(max);
+                true
+            }
+            _ = self.shutdown.cancelled() => false,
+        }
+    }
+
+    fn async_handler<F, Fut>(&self, handler: F) -> Handler
+    where
+        F: Fn(Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Handler::Async {
+            handler: Arc::new(move |event| handler(event).boxed()),
+            permits: Arc::new(Semaphore::new(self.max_concurrency)),
+        }
+    }
+
+    /// Read events from an RTM WebSocket until it closes or shutdown
+    async fn run(&self, ws_url: &str, handler: &Handler) -> Result<Ended> {
+        info!("Connecting to RTM WebSocket: {}", ws_url);
+
+        let ws_stream = tokio::select! {
+            ws_stream = websocket::connect(&self.client, ws_url) => ws_stream
+                .map_err(|e| SlackError::websocket_error(format!("Failed to connect: {}", e)))?,
+            _ = self.shutdown.cancelled() => return Ok(Ended::Stopped),
+        };
 
         info!("RTM WebSocket connected");
 
         let (mut write, mut read) = ws_stream.split();
+        let (frames_tx, mut frames_rx) = mpsc::unbounded_channel();
+        self.sender.attach(frames_tx);
+        let result = loop {
+            let msg = tokio::select! {
+                msg = read.next() => match msg {
+                    Some(msg) => msg,
+                    None => break Ok(Ended::Closed),
+                },
+                Some(frame) = frames_rx.recv() => {
+                    debug!("RTM sending: {}", frame);
+                    if let Err(e) = write.send(WsMessage::Text(frame)).await {
+                        error!("Failed to send RTM frame: {}", e);
+                    }
+                    continue;
+                }
+                _ = self.shutdown.cancelled() => {
+                    info!("RTM shutting down");
+                    let _ = write.send(WsMessage::Close(None)).await;
+                    break Ok(Ended::Stopped);
+                }
+            };
 
-        // Message processing loop
-        while let Some(msg) = read.next().await {
             match msg {
                 Ok(WsMessage::Text(text)) => {
                     debug!("RTM received: {}", text);
 
-                    if let Ok(event) = serde_json::from_str::<RtmMessageEvent>(&text) {
-                        // Only process message events
-                        if event.event_type == "message" {
-                            // Skip messages from ourselves
-                            if event.bot_id.as_ref() == Some(&bot_id) {
-                                continue;
-                            }
-
-                            // Call the message handler
-                            on_message(event);
+                    let value: Value = match serde_json::from_str(&text) {
+                        Ok(value) => value,
+                        Err(e) => {
+                            warn!("Failed to parse RTM event: {}", e);
+                            continue;
                         }
+                    };
+                    if self.sender.deliver_reply(&value) {
+                        continue;
+                    }
+                    let goodbye = value["type"] == "goodbye";
+                    tokio::select! {
+                        _ = Self::dispatch(handler, value) => {}
+                        _ = self.shutdown.cancelled() => break Ok(Ended::Stopped),
+                    }
+                    if goodbye {
+                        break Ok(Ended::Goodbye);
                     }
                 }
                 Ok(WsMessage::Close(_)) => {
                     warn!("RTM WebSocket closed");
-                    break;
+                    break Ok(Ended::Closed);
                 }
                 Ok(WsMessage::Ping(data)) => {
                     debug!("RTM received ping");
@@ -124,16 +485,37 @@ impl RtmApi {
                 }
                 Err(e) => {
                     error!("RTM WebSocket error: {}", e);
-                    return Err(SlackError::websocket_error(format!(
+                    break Err(SlackError::websocket_error(format!(
                         "WebSocket error: {}",
                         e
                     )));
                 }
             }
-        }
+        };
 
+        self.sender.detach();
         warn!("RTM connection closed");
-        Ok(())
+        result
+    }
+
+    /// Hand an event to the handler
+    async fn dispatch(handler: &Handler, value: Value) {
+        match handler {
+            Handler::Sync(handler) => handler(value),
+            Handler::Async { handler, permits } => {
+                // Waiting here stops the read loop until a handler finishes
+                let permit = permits
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("handler semaphore is never closed");
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    handler(Event::from_value(value)).await;
+                    drop(permit);
+                });
+            }
+        }
     }
 
     /// Start RTM and filter messages by channel
@@ -156,3 +538,15 @@ impl RtmApi {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ping_without_connection_drops_reply_slot() {
+        let sender = RtmSender::default();
+        assert!(sender.ping().await.is_err());
+        assert!(sender.inner.replies.lock().unwrap().is_empty());
+    }
+}
@@ -5,18 +5,97 @@
 
 use crate::client::SlackClient;
 use crate::error::{Result, SlackError};
-use futures_util::{SinkExt, StreamExt};
+use crate::events::Event;
+pub use crate::interactions::{InteractiveChannel, InteractivePayload, InteractiveUser};
+use crate::websocket;
+use chrono::{DateTime, Utc};
+use futures_util::future::{self, BoxFuture};
+use futures_util::stream::FuturesUnordered;
+use futures_util::{FutureExt, SinkExt, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::Arc;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 use std::time::Duration;
-use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
+use tokio::sync::{broadcast, mpsc, watch, Semaphore};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+pub use tokio_util::sync::CancellationToken;
+use tokio_util::sync::DropGuard;
 use tracing::{debug, error, info, warn};
 
 /// Socket Mode API client
+#[derive(Clone)]
 pub struct SocketModeApi {
     client: SlackClient,
+    ack_mode: AckMode,
+    max_concurrency: usize,
+    connections: usize,
+    shutdown: CancellationToken,
+    ping_interval: Duration,
+    ping_timeout: Duration,
+    stale_timeout: Duration,
+    health: Arc<watch::Sender<ConnectionHealth>>,
+}
+
+/// Health of a [`SocketModeApi`]'s connections, from
+/// [`SocketModeApi::health`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionHealth {
+    /// Open connections
+    pub connected: usize,
+    /// Connections waiting to reopen after an error
+    pub reconnecting: usize,
+    /// When the last envelope other than `hello`/`disconnect` arrived
+    pub last_event_at: Option<DateTime<Utc>>,
+    /// Connections reopened after an error or replaced after a disconnect
+    /// warning
+    pub reconnects: u64,
+}
+
+impl ConnectionHealth {
+    /// Overall state of the connections
+    pub fn state(&self) -> ConnectionState {
+        if self.connected > 0 {
+            ConnectionState::Connected
+        } else if self.reconnecting > 0 {
+            ConnectionState::Reconnecting
+        } else {
+            ConnectionState::Disconnected
+        }
+    }
+}
+
+/// Overall state of a [`SocketModeApi`]'s connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// At least one connection is open
+    Connected,
+    /// No connection is open and one is about to reopen after an error
+    Reconnecting,
+    /// No connection is open, e.g. before the first one or after shutdown
+    Disconnected,
+}
+
+/// When async handlers' envelopes are acknowledged
+///
+/// Slack expects an ack within 3 seconds and redelivers the envelope
+/// otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AckMode {
+    /// Ack as soon as the envelope arrives, then run the handler; its return
+    /// value is ignored
+    #[default]
+    Immediate,
+    /// For envelopes that accept a response payload (interactions and slash
+    /// commands), wait for the handler and ack with its return value, e.g.
+    /// `{"response_action": "errors", ...}`. Other envelopes are acked at once.
+    AfterHandler,
 }
 
 // ============================================
@@ -29,6 +108,9 @@ struct ConnectionOpenRequest {}
 /// Response from apps.connections.open
 #[derive(Debug, Deserialize)]
 pub struct ConnectionOpenResponse {
+    /// Always `true` on success; the envelope's `ok` is consumed before this
+    /// struct is parsed
+    #[serde(default = "crate::types::default_true")]
     pub ok: bool,
     pub url: String,
 }
@@ -40,7 +122,8 @@ pub struct ConnectionOpenResponse {
 /// A Socket Mode envelope wrapping an event
 #[derive(Debug, Clone, Deserialize)]
 pub struct SocketModeEnvelope {
-    /// Unique ID for this envelope - must be acknowledged
+    /// Unique ID for this envelope - must be acknowledged (empty for hello/disconnect)
+    #[serde(default)]
     pub envelope_id: String,
 
     /// Type of payload: "events_api", "interactive", "slash_commands", "hello", "disconnect"
@@ -62,6 +145,11 @@ pub struct SocketModeEnvelope {
     /// Reason for retry
     #[serde(default)]
     pub retry_reason: Option<String>,
+
+    /// Why Slack is closing the connection, for `disconnect` envelopes:
+    /// `warning`, `refresh_requested` or `link_disabled`
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 /// Parsed Socket Mode event with typed payload
@@ -78,6 +166,93 @@ pub struct SocketModeEvent {
 
     /// The parsed payload
     pub payload: SocketModePayload,
+
+    /// Retry attempt number, if Slack is redelivering this event
+    pub retry_attempt: Option<u32>,
+
+    /// Why Slack is retrying, e.g. `timeout`
+    pub retry_reason: Option<String>,
+
+    /// Acknowledges this envelope
+    pub acker: Acker,
+}
+
+/// Acknowledges one envelope on the connection it arrived on
+///
+/// Clones share their state, so only the first ack is sent. Envelopes
+/// without an ID (`hello`, `disconnect`) and events from the HTTP
+/// [`receiver`](crate::receiver) get a detached acker whose acks do nothing.
+///
+/// A connection that is shutting down stays open until its envelopes are
+/// acked or their ackers dropped, for up to 3 seconds.
+#[derive(Clone)]
+pub struct Acker {
+    envelope_id: String,
+    /// Taken by the first ack
+    acks: Arc<Mutex<Option<mpsc::Sender<SocketModeAck>>>>,
+}
+
+impl Acker {
+    fn new(envelope_id: &str, acks: &mpsc::Sender<SocketModeAck>) -> Self {
+        Self {
+            envelope_id: envelope_id.to_string(),
+            acks: Arc::new(Mutex::new((!envelope_id.is_empty()).then(|| acks.clone()))),
+        }
+    }
+
+    /// An acker with no connection behind it
+    #[cfg(feature = "receiver")]
+    pub(crate) fn detached() -> Self {
+        Self {
+            envelope_id: String::new(),
+            acks: Arc::default(),
+        }
+    }
+
+    /// Acknowledge the envelope without a payload
+    pub async fn ack(&self) -> Result<()> {
+        self.send(None).await
+    }
+
+    /// Acknowledge the envelope with a response payload
+    ///
+    /// Only envelopes with `accepts_response_payload` take one, e.g.
+    /// `{"response_action": "errors", ...}` for a view submission.
+    pub async fn ack_with(&self, payload: Value) -> Result<()> {
+        self.send(Some(payload)).await
+    }
+
+    /// Whether the envelope was acknowledged already, or needs no ack
+    pub fn is_acked(&self) -> bool {
+        self.acks.lock().unwrap().is_none()
+    }
+
+    /// Queue the ack on the connection, unless it was sent already
+    async fn send(&self, payload: Option<Value>) -> Result<()> {
+        let Some(acks) = self.acks.lock().unwrap().take() else {
+            return Ok(());
+        };
+
+        let ack = SocketModeAck {
+            envelope_id: self.envelope_id.clone(),
+            payload,
+        };
+        acks.send(ack).await.map_err(|_| {
+            SlackError::websocket_error(format!(
+                "Connection closed before envelope {} was acked",
+                self.envelope_id
+            ))
+        })
+    }
+}
+
+impl fmt::Debug for Acker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Acker")
+            .field("envelope_id", &self.envelope_id)
+            .field("acked", &self.is_acked())
+            .finish()
+    }
 }
 
 /// Types of Socket Mode events
@@ -141,7 +316,7 @@ pub struct EventsApiPayload {
     pub api_app_id: Option<String>,
 
     /// The actual event
-    pub event: Option<Value>,
+    pub event: Option<Event>,
 
     /// Event type (e.g., "app_mention", "message")
     #[serde(rename = "type")]
@@ -158,66 +333,6 @@ pub struct EventsApiPayload {
     pub authorizations: Vec<Value>,
 }
 
-/// Interactive component payload
-#[derive(Debug, Clone, Deserialize)]
-pub struct InteractivePayload {
-    /// Type of interaction
-    #[serde(rename = "type")]
-    pub interaction_type: String,
-
-    /// User who triggered the interaction
-    #[serde(default)]
-    pub user: Option<InteractiveUser>,
-
-    /// Channel where interaction occurred
-    #[serde(default)]
-    pub channel: Option<InteractiveChannel>,
-
-    /// Trigger ID for opening modals
-    #[serde(default)]
-    pub trigger_id: Option<String>,
-
-    /// Response URL for async responses
-    #[serde(default)]
-    pub response_url: Option<String>,
-
-    /// Action details
-    #[serde(default)]
-    pub actions: Vec<Value>,
-
-    /// View details (for view_submission, view_closed)
-    #[serde(default)]
-    pub view: Option<Value>,
-
-    /// Message that was interacted with
-    #[serde(default)]
-    pub message: Option<Value>,
-
-    /// Full raw payload
-    #[serde(flatten)]
-    pub extra: Value,
-}
-
-/// User in interactive payload
-#[derive(Debug, Clone, Deserialize)]
-pub struct InteractiveUser {
-    pub id: String,
-    #[serde(default)]
-    pub username: Option<String>,
-    #[serde(default)]
-    pub name: Option<String>,
-    #[serde(default)]
-    pub team_id: Option<String>,
-}
-
-/// Channel in interactive payload
-#[derive(Debug, Clone, Deserialize)]
-pub struct InteractiveChannel {
-    pub id: String,
-    #[serde(default)]
-    pub name: Option<String>,
-}
-
 /// Slash command payload
 #[derive(Debug, Clone, Deserialize)]
 pub struct SlashCommandPayload {
@@ -270,29 +385,252 @@ struct SocketModeAck {
     payload: Option<Value>,
 }
 
+/// Default limit on async handlers running at once
+const DEFAULT_MAX_CONCURRENCY: usize = 32;
+
+/// Slack's limit on concurrent Socket Mode connections per app
+const MAX_CONNECTIONS: usize = 10;
+
+/// How many recent envelope and event IDs a pool remembers
+const DEDUPE_CAPACITY: usize = 1000;
+
+/// How long a connection waits for pending acks after shutdown, matching
+/// Slack's ack deadline
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+
+/// Default time between ping probes
+const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default time to wait for a pong
+const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time without any frame before a connection counts as stale
+const DEFAULT_STALE_TIMEOUT: Duration = Duration::from_secs(120);
+
+type SyncHandler = Arc<dyn Fn(SocketModeEvent) -> Option<Value> + Send + Sync>;
+type AsyncHandler = Arc<dyn Fn(SocketModeEvent) -> BoxFuture<'static, Option<Value>> + Send + Sync>;
+
+/// A handler and how to run it
+#[derive(Clone)]
+enum Handler {
+    /// Called inline in the read loop; the ack waits for it
+    Sync(SyncHandler),
+    /// Spawned on its own task, at most `permits` at a time
+    Async {
+        handler: AsyncHandler,
+        ack_mode: AckMode,
+        permits: Arc<Semaphore>,
+    },
+    /// Sent to a [`SocketModeStream`], which acks through the event's acker
+    Stream(mpsc::Sender<SocketModeEvent>),
+}
+
+/// Recently delivered envelope and event IDs, shared by a pool's connections
+#[derive(Default)]
+struct Dedupe {
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl Dedupe {
+    /// Record the event's IDs, returning `false` if any was seen before
+    fn first_delivery(&mut self, event: &SocketModeEvent) -> bool {
+        let mut ids = Vec::new();
+        if !event.envelope_id.is_empty() {
+            ids.push(event.envelope_id.as_str());
+        }
+        if let SocketModePayload::EventsApi(payload) = &event.payload {
+            ids.extend(payload.event_id.as_deref());
+        }
+        if ids.iter().any(|id| self.seen.contains(*id)) {
+            return false;
+        }
+
+        for id in ids {
+            self.seen.insert(id.to_string());
+            self.order.push_back(id.to_string());
+            if self.order.len() > DEDUPE_CAPACITY {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.seen.remove(&oldest);
+                }
+            }
+        }
+        true
+    }
+}
+
+/// What a pooled connection shares with the rest of its pool
+struct PoolConnection {
+    dedupe: Arc<Mutex<Dedupe>>,
+    /// Asks the pool slot to open a replacement connection
+    refresh: mpsc::UnboundedSender<()>,
+    /// Set once a replacement was requested
+    replaced: AtomicBool,
+}
+
+/// How a pooled connection ended
+enum Ended {
+    /// Closed after a replacement was opened
+    Replaced,
+    /// Closed normally
+    Closed,
+    /// Failed to open or dropped with an error
+    Failed { error: SlackError, opened: bool },
+}
+
 // ============================================
 // Socket Mode Client Implementation
 // ============================================
 
 impl SocketModeApi {
     pub(crate) fn new(client: SlackClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            ack_mode: AckMode::default(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            connections: 1,
+            shutdown: CancellationToken::new(),
+            ping_interval: DEFAULT_PING_INTERVAL,
+            ping_timeout: DEFAULT_PING_TIMEOUT,
+            stale_timeout: DEFAULT_STALE_TIMEOUT,
+            health: Arc::new(watch::channel(ConnectionHealth::default()).0),
+        }
+    }
+
+    /// Send a ping this long after the last pong (default: 30 seconds)
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
+    /// Reconnect when a ping is not answered within `timeout` (default: 10
+    /// seconds)
+    pub fn ping_timeout(mut self, timeout: Duration) -> Self {
+        self.ping_timeout = timeout;
+        self
+    }
+
+    /// Reconnect when no frame at all arrives for `timeout` (default: 2
+    /// minutes)
+    ///
+    /// Pongs count, so this should be longer than the ping interval.
+    pub fn stale_timeout(mut self, timeout: Duration) -> Self {
+        self.stale_timeout = timeout;
+        self
+    }
+
+    /// Watch the health of this client's connections
+    ///
+    /// The receiver always holds the latest [`ConnectionHealth`];
+    /// `changed()` wakes on every connect, disconnect, reconnect and event.
+    pub fn health(&self) -> watch::Receiver<ConnectionHealth> {
+        self.health.subscribe()
+    }
+
+    /// Stop listening when `token` is cancelled
+    ///
+    /// Connections stop reading new envelopes, acks for envelopes already
+    /// handed out get a few seconds to go through, and the `start*` methods
+    /// return `Ok(())`.
+    pub fn shutdown(mut self, token: CancellationToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
+    /// Keep several connections open (default: 1, at most 10)
+    ///
+    /// Used by [`start_with_reconnect`](Self::start_with_reconnect) and
+    /// [`start_async_with_reconnect`](Self::start_async_with_reconnect).
+    /// Slack delivers each envelope on one of the app's connections, so more
+    /// connections keep events flowing while one reconnects. Deliveries are
+    /// deduplicated across the pool by envelope and event ID.
+    pub fn connections(mut self, count: usize) -> Self {
+        self.connections = count.clamp(1, MAX_CONNECTIONS);
+        self
+    }
+
+    /// Set when envelopes handled by [`start_async`](Self::start_async) are
+    /// acknowledged (default: [`AckMode::Immediate`])
+    pub fn ack_mode(mut self, mode: AckMode) -> Self {
+        self.ack_mode = mode;
+        self
+    }
+
+    /// Limit how many async handlers run at once (default: 32)
+    ///
+    /// When the limit is reached the connection stops reading until a
+    /// handler finishes, so a backlog stays on Slack's side instead of
+    /// piling up in memory.
+    pub fn max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max.max(1);
+        self
     }
 
     /// Open a Socket Mode connection
     ///
     /// Returns a WebSocket URL that can be used to establish the connection.
     /// Note: Requires an app-level token (xapp-...), not a bot or user token.
+    /// With [`AuthConfig::app`](crate::AuthConfig::app) credentials the app
+    /// token is picked automatically.
     pub async fn open_connection(&self) -> Result<ConnectionOpenResponse> {
         self.client
             .post("apps.connections.open", &ConnectionOpenRequest {})
             .await
     }
 
+    /// Stream events from Socket Mode
+    ///
+    /// Connects in the background and reconnects like
+    /// [`start_with_reconnect`](Self::start_with_reconnect), using a pool of
+    /// [`connections`](Self::connections). Events are not acked
+    /// automatically: ack each one through its [`acker`](SocketModeEvent::acker)
+    /// within 3 seconds or Slack redelivers it. While
+    /// [`max_concurrency`](Self::max_concurrency) events are waiting to be
+    /// read, the connections stop reading.
+    ///
+    /// The stream ends after the [`shutdown`](Self::shutdown) token is
+    /// cancelled (or [`SocketModeStream::shutdown`] is called) and the
+    /// events already received are drained. Dropping the stream shuts the
+    /// connections down as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use slacko::{SlackClient, AuthConfig};
+    /// use futures_util::StreamExt;
+    /// use slacko::api::socket_mode::CancellationToken;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SlackClient::new(AuthConfig::app("xapp-token").with_bot_token("xoxb-token"))?;
+    /// let shutdown = CancellationToken::new();
+    /// let mut events = client.socket_mode().shutdown(shutdown.clone()).connect();
+    /// while let Some(event) = events.next().await {
+    ///     event.acker.ack().await?;
+    ///     println!("Received: {:?}", event.envelope_type);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect(&self) -> SocketModeStream {
+        let shutdown = self.shutdown.child_token();
+        let api = Self {
+            shutdown: shutdown.clone(),
+            ..self.clone()
+        };
+        let (tx, events) = mpsc::channel(self.max_concurrency);
+        tokio::spawn(async move { api.reconnect_loop(Handler::Stream(tx)).await });
+
+        SocketModeStream {
+            events,
+            shutdown: shutdown.clone(),
+            _guard: shutdown.drop_guard(),
+        }
+    }
+
     /// Start Socket Mode and listen for events
     ///
     /// This method connects to Socket Mode and calls the provided handler for each event.
-    /// The handler can optionally return a response payload.
+    /// The handler can optionally return a response payload. It returns when the
+    /// connection closes or the [`shutdown`](Self::shutdown) token is cancelled.
     ///
     /// # Arguments
     ///
@@ -303,7 +641,7 @@ impl SocketModeApi {
     /// ```no_run
     /// # use slacko::{SlackClient, AuthConfig};
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let client = SlackClient::new(AuthConfig::bot("xapp-token"))?;
+    /// let client = SlackClient::new(AuthConfig::app("xapp-token").with_bot_token("xoxb-token"))?;
     /// client.socket_mode().start(|event| {
     ///     println!("Received: {:?}", event.envelope_type);
     ///     None // No response payload
@@ -316,105 +654,322 @@ impl SocketModeApi {
         F: Fn(SocketModeEvent) -> Option<Value> + Send + Sync + 'static,
     {
         let conn = self.open_connection().await?;
-        self.run_connection(&conn.url, Arc::new(handler)).await
+        self.run_connection(&conn.url, &Handler::Sync(Arc::new(handler)), None)
+            .await
     }
 
     /// Start Socket Mode with automatic reconnection
     ///
     /// Like `start()`, but automatically reconnects if the connection is lost.
-    /// Uses exponential backoff between reconnection attempts.
+    /// Uses exponential backoff between reconnection attempts. When Slack
+    /// warns with a `disconnect` envelope that a connection will be closed, a
+    /// replacement is opened right away so no events are missed. Returns once
+    /// every connection has closed normally without such a warning.
     pub async fn start_with_reconnect<F>(&self, handler: F) -> Result<()>
     where
         F: Fn(SocketModeEvent) -> Option<Value> + Send + Sync + 'static,
     {
-        let handler = Arc::new(handler);
-        let mut backoff = Duration::from_secs(1);
+        self.reconnect_loop(Handler::Sync(Arc::new(handler))).await
+    }
+
+    /// Start Socket Mode with an async handler
+    ///
+    /// Each envelope is handled on its own task, so a slow handler does not
+    /// hold up later envelopes or their acks. With the default
+    /// [`AckMode::Immediate`] the envelope is acked before the handler runs;
+    /// use [`AckMode::AfterHandler`] for handlers that answer interactions
+    /// or commands with an ack payload. At most
+    /// [`max_concurrency`](Self::max_concurrency) handlers run at once.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use slacko::{SlackClient, AuthConfig};
+    /// use slacko::api::socket_mode::{AckMode, SocketModePayload};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SlackClient::new(AuthConfig::app("xapp-token").with_bot_token("xoxb-token"))?;
+    /// let replier = client.clone();
+    /// client
+    ///     .socket_mode()
+    ///     .ack_mode(AckMode::AfterHandler)
+    ///     .max_concurrency(8)
+    ///     .start_async(move |event| {
+    ///         let replier = replier.clone();
+    ///         async move {
+    ///             if let SocketModePayload::SlashCommand(command) = event.payload {
+    ///                 replier.chat().post_message(&command.channel_id, "Working on it").await.ok();
+    ///                 return Some(serde_json::json!({ "text": "Started" }));
+    ///             }
+    ///             None
+    ///         }
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_async<F, Fut>(&self, handler: F) -> Result<()>
+    where
+        F: Fn(SocketModeEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<Value>> + Send + 'static,
+    {
+        let conn = self.open_connection().await?;
+        self.run_connection(&conn.url, &self.async_handler(handler), None)
+            .await
+    }
+
+    /// Start Socket Mode with an async handler and automatic reconnection
+    ///
+    /// Combines [`start_async`](Self::start_async) with the reconnection of
+    /// [`start_with_reconnect`](Self::start_with_reconnect).
+    pub async fn start_async_with_reconnect<F, Fut>(&self, handler: F) -> Result<()>
+    where
+        F: Fn(SocketModeEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<Value>> + Send + 'static,
+    {
+        self.reconnect_loop(self.async_handler(handler)).await
+    }
+
+    fn async_handler<F, Fut>(&self, handler: F) -> Handler
+    where
+        F: Fn(SocketModeEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<Value>> + Send + 'static,
+    {
+        Handler::Async {
+            handler: Arc::new(move |event| handler(event).boxed()),
+            ack_mode: self.ack_mode,
+            permits: Arc::new(Semaphore::new(self.max_concurrency)),
+        }
+    }
+
+    /// Run a pool of connections until they all close normally or shut down
+    async fn reconnect_loop(&self, handler: Handler) -> Result<()> {
+        let dedupe = Arc::new(Mutex::new(Dedupe::default()));
+        future::join_all((0..self.connections).map(|_| self.run_slot(&handler, &dedupe))).await;
+        Ok(())
+    }
+
+    /// Keep one connection of the pool open
+    ///
+    /// Reconnects with exponential backoff on errors and opens a replacement
+    /// as soon as a connection gets a disconnect warning.
+    async fn run_slot(&self, handler: &Handler, dedupe: &Arc<Mutex<Dedupe>>) {
+        let (refresh, mut refresh_rx) = mpsc::unbounded_channel();
         let max_backoff = Duration::from_secs(60);
+        let mut backoff = Duration::from_secs(1);
+
+        let mut live = FuturesUnordered::new();
+        live.push(self.pooled_connection(handler, dedupe, &refresh, None));
 
         loop {
-            let conn = match self.open_connection().await {
-                Ok(c) => {
-                    backoff = Duration::from_secs(1); // Reset backoff on successful connection
-                    c
+            tokio::select! {
+                Some(()) = refresh_rx.recv() => {
+                    if !self.shutdown.is_cancelled() {
+                        info!("Opening a replacement Socket Mode connection");
+                        self.health.send_modify(|h| h.reconnects += 1);
+                        live.push(self.pooled_connection(handler, dedupe, &refresh, None));
+                    }
                 }
-                Err(e) => {
-                    error!("Failed to open Socket Mode connection: {}", e);
-                    tokio::time::sleep(backoff).await;
-                    backoff = (backoff * 2).min(max_backoff);
-                    continue;
+                Some(ended) = live.next() => {
+                    match ended {
+                        // The replacement is queued in `refresh_rx`
+                        Ended::Replaced if !self.shutdown.is_cancelled() => continue,
+                        Ended::Failed { error, opened } if !self.shutdown.is_cancelled() => {
+                            if opened {
+                                backoff = Duration::from_secs(1); // Reset backoff on successful connection
+                            }
+                            warn!("Socket Mode connection error: {}, reconnecting...", error);
+                            self.health.send_modify(|h| {
+                                h.reconnecting += 1;
+                                h.reconnects += 1;
+                            });
+                            live.push(self.pooled_connection(handler, dedupe, &refresh, Some(backoff)));
+                            backoff = (backoff * 2).min(max_backoff);
+                        }
+                        _ => {}
+                    }
+                    if live.is_empty() {
+                        info!("Socket Mode connection closed normally");
+                        return;
+                    }
                 }
-            };
+            }
+        }
+    }
 
-            match self.run_connection(&conn.url, handler.clone()).await {
-                Ok(()) => {
-                    info!("Socket Mode connection closed normally");
-                    break Ok(());
-                }
-                Err(e) => {
-                    warn!("Socket Mode connection error: {}, reconnecting...", e);
-                    tokio::time::sleep(backoff).await;
-                    backoff = (backoff * 2).min(max_backoff);
-                }
+    /// Open and run one connection of a pool, after `backoff` when
+    /// reconnecting after an error
+    async fn pooled_connection(
+        &self,
+        handler: &Handler,
+        dedupe: &Arc<Mutex<Dedupe>>,
+        refresh: &mpsc::UnboundedSender<()>,
+        backoff: Option<Duration>,
+    ) -> Ended {
+        if let Some(backoff) = backoff {
+            let cancelled = tokio::select! {
+                _ = tokio::time::sleep(backoff) => false,
+                _ = self.shutdown.cancelled() => true,
+            };
+            if cancelled {
+                self.health.send_modify(|h| h.reconnecting -= 1);
+                return Ended::Closed;
             }
         }
+        let opened = self.open_connection().await;
+        if backoff.is_some() {
+            self.health.send_modify(|h| h.reconnecting -= 1);
+        }
+        let conn = match opened {
+            Ok(c) => c,
+            Err(error) => {
+                error!("Failed to open Socket Mode connection: {}", error);
+                return Ended::Failed {
+                    error,
+                    opened: false,
+                };
+            }
+        };
+
+        let pool = PoolConnection {
+            dedupe: dedupe.clone(),
+            refresh: refresh.clone(),
+            replaced: AtomicBool::new(false),
+        };
+        let result = self.run_connection(&conn.url, handler, Some(&pool)).await;
+        match result {
+            _ if pool.replaced.load(Ordering::Relaxed) => Ended::Replaced,
+            Ok(()) => Ended::Closed,
+            Err(error) => Ended::Failed {
+                error,
+                opened: true,
+            },
+        }
     }
 
     /// Run a single Socket Mode connection
-    async fn run_connection<F>(&self, url: &str, handler: Arc<F>) -> Result<()>
-    where
-        F: Fn(SocketModeEvent) -> Option<Value> + Send + Sync + 'static,
-    {
+    ///
+    /// Fails if a ping goes unanswered for `ping_timeout` or no frame at all
+    /// arrives for `stale_timeout`, so that half-open sockets get replaced.
+    async fn run_connection(
+        &self,
+        url: &str,
+        handler: &Handler,
+        pool: Option<&PoolConnection>,
+    ) -> Result<()> {
         info!("Connecting to Socket Mode: {}", url);
 
-        let (ws_stream, _) = connect_async(url)
+        let ws_stream = websocket::connect(&self.client, url)
             .await
             .map_err(|e| SlackError::websocket_error(format!("Failed to connect: {}", e)))?;
 
         info!("Socket Mode connected");
+        self.health.send_modify(|h| h.connected += 1);
 
         let (mut write, mut read) = ws_stream.split();
 
-        // Channel for sending acknowledgments
+        // Channels for sending acknowledgments and ping probes
         let (ack_tx, mut ack_rx) = mpsc::channel::<SocketModeAck>(100);
-
-        // Spawn task to send acknowledgments
-        let write_task = tokio::spawn(async move {
-            while let Some(ack) = ack_rx.recv().await {
-                let msg =
-                    serde_json::to_string(&ack).expect("SocketModeAck is always serializable");
-                debug!("Sending ack: {}", msg);
-                if let Err(e) = write.send(WsMessage::Text(msg)).await {
-                    error!("Failed to send ack: {}", e);
-                    break;
+        let (ping_tx, mut ping_rx) = mpsc::channel::<Vec<u8>>(1);
+
+        // Spawn task to send acknowledgments and pings; it closes the socket
+        // once every ack sender, including unacked ackers, is gone
+        let mut write_task = tokio::spawn(async move {
+            loop {
+                let msg = tokio::select! {
+                    ack = ack_rx.recv() => match ack {
+                        Some(ack) => {
+                            let msg = serde_json::to_string(&ack)
+                                .expect("SocketModeAck is always serializable");
+                            debug!("Sending ack: {}", msg);
+                            WsMessage::Text(msg)
+                        }
+                        None => break,
+                    },
+                    Some(data) = ping_rx.recv() => WsMessage::Ping(data),
+                };
+                if let Err(e) = write.send(msg).await {
+                    error!("Failed to send to Socket Mode: {}", e);
+                    return;
                 }
             }
+            let _ = write.send(WsMessage::Close(None)).await;
         });
 
+        let mut last_frame = Instant::now();
+        let mut next_ping = last_frame.checked_add(self.ping_interval);
+        let mut pong_deadline = None;
+        let mut failure = None;
+
         // Process incoming messages
-        while let Some(msg) = read.next().await {
+        loop {
+            let msg = tokio::select! {
+                msg = read.next() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = sleep_until(next_ping) => {
+                    if ping_tx.try_send(Vec::new()).is_ok() {
+                        pong_deadline = Instant::now().checked_add(self.ping_timeout);
+                    }
+                    next_ping = None;
+                    continue;
+                }
+                _ = sleep_until(pong_deadline) => {
+                    failure = Some(SlackError::websocket_error(format!(
+                        "No pong within {:?}",
+                        self.ping_timeout
+                    )));
+                    break;
+                }
+                _ = sleep_until(last_frame.checked_add(self.stale_timeout)) => {
+                    failure = Some(SlackError::websocket_error(format!(
+                        "No frames for {:?}",
+                        self.stale_timeout
+                    )));
+                    break;
+                }
+                _ = self.shutdown.cancelled() => break,
+            };
+            last_frame = Instant::now();
+
             match msg {
                 Ok(WsMessage::Text(text)) => {
                     debug!("Socket Mode received: {}", text);
 
                     match serde_json::from_str::<SocketModeEnvelope>(&text) {
                         Ok(envelope) => {
-                            let event = Self::parse_envelope(envelope.clone());
-
-                            // Always acknowledge (except for hello which doesn't need it)
-                            if event.envelope_type != SocketModeEventType::Hello {
-                                let response = handler(event);
-                                let ack = SocketModeAck {
-                                    envelope_id: envelope.envelope_id,
-                                    payload: response,
-                                };
-                                if ack_tx.send(ack).await.is_err() {
-                                    error!("Failed to queue ack");
-                                    break;
+                            let event = Self::parse_envelope(envelope, &ack_tx);
+                            match &event.payload {
+                                SocketModePayload::Hello => {
+                                    info!("Socket Mode hello received");
+                                    continue;
+                                }
+                                SocketModePayload::Disconnect { reason } => {
+                                    info!("Socket Mode disconnect requested: {}", reason);
+                                    if let Some(pool) = pool {
+                                        Self::request_replacement(pool, reason);
+                                    }
+                                }
+                                _ => self
+                                    .health
+                                    .send_modify(|h| h.last_event_at = Some(Utc::now())),
+                            }
+
+                            let first_delivery = pool.is_none_or(|pool| {
+                                pool.dedupe.lock().unwrap().first_delivery(&event)
+                            });
+                            let delivered = if first_delivery {
+                                tokio::select! {
+                                    queued = Self::dispatch(handler, event) => queued,
+                                    _ = self.shutdown.cancelled() => break,
                                 }
                             } else {
-                                // Just log hello, call handler but don't ack
-                                info!("Socket Mode hello received");
-                                let _ = handler(event);
+                                debug!("Skipping duplicate envelope {}", event.envelope_id);
+                                event.acker.ack().await.is_ok()
+                            };
+                            if !delivered {
+                                error!("Failed to queue ack");
+                                break;
                             }
                         }
                         Err(e) => {
@@ -430,26 +985,106 @@ impl SocketModeApi {
                     debug!("Socket Mode ping received, data: {:?}", data);
                     // Pong is handled automatically by tungstenite
                 }
+                Ok(WsMessage::Pong(_)) => {
+                    debug!("Socket Mode pong received");
+                    pong_deadline = None;
+                    next_ping = Instant::now().checked_add(self.ping_interval);
+                }
                 Ok(_) => {
                     debug!("Socket Mode received other message type");
                 }
                 Err(e) => {
                     error!("Socket Mode error: {}", e);
-                    write_task.abort();
-                    return Err(SlackError::websocket_error(format!(
+                    failure = Some(SlackError::websocket_error(format!(
                         "WebSocket error: {}",
                         e
                     )));
+                    break;
                 }
             }
         }
 
-        write_task.abort();
+        self.health.send_modify(|h| h.connected -= 1);
+        if let Some(error) = failure {
+            write_task.abort();
+            return Err(error);
+        }
+
+        if self.shutdown.is_cancelled() {
+            info!("Socket Mode shutting down");
+            drop(ack_tx);
+            if tokio::time::timeout(SHUTDOWN_GRACE, &mut write_task)
+                .await
+                .is_err()
+            {
+                warn!("Acks still pending at shutdown; closing the connection anyway");
+                write_task.abort();
+            }
+        } else {
+            write_task.abort();
+        }
         Ok(())
     }
 
+    /// Ask the pool for a replacement connection, once per connection
+    ///
+    /// `link_disabled` means Socket Mode was turned off for the app, so
+    /// reconnecting would not help.
+    fn request_replacement(pool: &PoolConnection, reason: &str) {
+        if reason != "link_disabled" && !pool.replaced.swap(true, Ordering::Relaxed) {
+            let _ = pool.refresh.send(());
+        }
+    }
+
+    /// Hand an event to the handler and acknowledge it
+    ///
+    /// Every envelope with an ID is acked, unless the handler acked it
+    /// through its acker already; events sent to a stream are acked by the
+    /// consumer. Returns `false` if the event could not be delivered or its
+    /// ack could not be queued because the connection is gone.
+    async fn dispatch(handler: &Handler, event: SocketModeEvent) -> bool {
+        let acker = event.acker.clone();
+
+        match handler {
+            Handler::Sync(handler) => {
+                let payload = handler(event);
+                acker.send(payload).await.is_ok()
+            }
+            Handler::Async {
+                handler,
+                ack_mode,
+                permits,
+            } => {
+                let ack_after =
+                    *ack_mode == AckMode::AfterHandler && event.accepts_response_payload;
+                if !ack_after && acker.ack().await.is_err() {
+                    return false;
+                }
+
+                // Waiting here stops the read loop until a handler finishes
+                let Ok(permit) = permits.clone().acquire_owned().await else {
+                    return false;
+                };
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let payload = handler(event).await;
+                    drop(permit);
+                    if ack_after && acker.send(payload).await.is_err() {
+                        warn!("Connection closed before the handler finished; not acked");
+                    }
+                });
+                true
+            }
+            // Waiting here stops the read loop until the consumer catches up
+            Handler::Stream(events) => events.send(event).await.is_ok(),
+        }
+    }
+
     /// Parse a raw envelope into a typed event
-    fn parse_envelope(envelope: SocketModeEnvelope) -> SocketModeEvent {
+    fn parse_envelope(
+        envelope: SocketModeEnvelope,
+        acks: &mpsc::Sender<SocketModeAck>,
+    ) -> SocketModeEvent {
         let envelope_type = SocketModeEventType::from(envelope.envelope_type.as_str());
 
         let payload = match &envelope_type {
@@ -465,10 +1100,7 @@ impl SocketModeApi {
             }
             SocketModeEventType::Interactive => {
                 if let Some(p) = &envelope.payload {
-                    match serde_json::from_value::<InteractivePayload>(p.clone()) {
-                        Ok(interactive) => SocketModePayload::Interactive(interactive),
-                        Err(_) => SocketModePayload::Raw(p.clone()),
-                    }
+                    SocketModePayload::Interactive(InteractivePayload::from_value(p.clone()))
                 } else {
                     SocketModePayload::Raw(Value::Null)
                 }
@@ -486,12 +1118,17 @@ impl SocketModeApi {
             SocketModeEventType::Hello => SocketModePayload::Hello,
             SocketModeEventType::Disconnect => {
                 let reason = envelope
-                    .payload
-                    .as_ref()
-                    .and_then(|p| p.get("reason"))
-                    .and_then(|r| r.as_str())
-                    .unwrap_or("unknown")
-                    .to_string();
+                    .reason
+                    .clone()
+                    .or_else(|| {
+                        envelope
+                            .payload
+                            .as_ref()
+                            .and_then(|p| p.get("reason"))
+                            .and_then(|r| r.as_str())
+                            .map(str::to_string)
+                    })
+                    .unwrap_or_else(|| "unknown".to_string());
                 SocketModePayload::Disconnect { reason }
             }
             SocketModeEventType::Unknown(_) => {
@@ -500,10 +1137,121 @@ impl SocketModeApi {
         };
 
         SocketModeEvent {
+            acker: Acker::new(&envelope.envelope_id, acks),
             envelope_id: envelope.envelope_id,
             envelope_type,
             accepts_response_payload: envelope.accepts_response_payload,
             payload,
+            retry_attempt: envelope.retry_attempt,
+            retry_reason: envelope.retry_reason,
         }
     }
 }
+
+// ============================================
+// Event Streams
+// ============================================
+
+/// Socket Mode events from [`SocketModeApi::connect`]
+pub struct SocketModeStream {
+    events: mpsc::Receiver<SocketModeEvent>,
+    shutdown: CancellationToken,
+    _guard: DropGuard,
+}
+
+impl SocketModeStream {
+    /// Stop reading new envelopes
+    ///
+    /// The stream ends once the events already received are drained; their
+    /// ackers keep working for a few seconds.
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+
+    /// Share the events with several consumers
+    ///
+    /// Every [`subscribe`](SocketModeFanOut::subscribe)r sees every event
+    /// from the time it subscribed. Clones of an event share its acker, so
+    /// the first consumer to ack wins. A subscriber that falls more than
+    /// `capacity` events behind skips the oldest ones.
+    pub fn fan_out(mut self, capacity: usize) -> SocketModeFanOut {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        let done = CancellationToken::new();
+        let fan_out = SocketModeFanOut {
+            sender: sender.clone(),
+            shutdown: self.shutdown.clone(),
+            done: done.clone(),
+        };
+
+        tokio::spawn(async move {
+            while let Some(event) = self.next().await {
+                if sender.send(event).is_err() {
+                    debug!("No Socket Mode subscribers; dropping event");
+                }
+            }
+            done.cancel();
+        });
+        fan_out
+    }
+}
+
+impl Stream for SocketModeStream {
+    type Item = SocketModeEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+/// Socket Mode events shared by several consumers, from
+/// [`SocketModeStream::fan_out`]
+///
+/// Subscribers keep the connections open; dropping this handle does not.
+/// Call [`shutdown`](Self::shutdown) or cancel the shutdown token to stop.
+pub struct SocketModeFanOut {
+    sender: broadcast::Sender<SocketModeEvent>,
+    shutdown: CancellationToken,
+    /// Cancelled once the underlying stream has ended
+    done: CancellationToken,
+}
+
+impl SocketModeFanOut {
+    /// A new consumer of the events
+    ///
+    /// The stream ends after the underlying stream does.
+    pub fn subscribe(&self) -> impl Stream<Item = SocketModeEvent> + Send + 'static {
+        let receiver = self.sender.subscribe();
+        futures_util::stream::unfold(
+            (receiver, self.done.clone()),
+            |(mut receiver, done)| async move {
+                loop {
+                    let received = tokio::select! {
+                        biased;
+                        received = receiver.recv() => received,
+                        _ = done.cancelled() => return None,
+                    };
+                    match received {
+                        Ok(event) => return Some((event, (receiver, done))),
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!("Socket Mode subscriber lagged; skipped {} events", skipped);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            },
+        )
+    }
+
+    /// Stop reading new envelopes, like [`SocketModeStream::shutdown`]
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+}
+
+/// Sleep until `deadline`, or forever without one
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => future::pending().await,
+    }
+}
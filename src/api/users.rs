@@ -4,6 +4,7 @@
 
 use crate::client::SlackClient;
 use crate::error::Result;
+use crate::pagination::{impl_paginated, Paginator};
 use crate::types::{Channel, ResponseMetadata, User};
 use serde::{Deserialize, Serialize};
 
@@ -43,6 +44,14 @@ impl UsersApi {
         self.client.post("users.list", &params).await
     }
 
+    /// Stream every user, following pagination cursors
+    pub fn list_stream(
+        &self,
+        params: UsersListRequest,
+    ) -> Paginator<UsersListRequest, UsersListResponse> {
+        Paginator::new(self.client.clone(), "users.list", params)
+    }
+
     /// Get the profile of a user
     ///
     /// # Arguments
@@ -140,6 +149,14 @@ impl UsersApi {
         self.client.post("users.conversations", &params).await
     }
 
+    /// Stream every conversation the user may access, following pagination cursors
+    pub fn conversations_stream(
+        &self,
+        params: UserConversationsRequest,
+    ) -> Paginator<UserConversationsRequest, UserConversationsResponse> {
+        Paginator::new(self.client.clone(), "users.conversations", params)
+    }
+
     /// Get the identity of the authenticated user
     ///
     /// This returns the user's identity as an OAuth token owner.
@@ -233,7 +250,7 @@ pub struct UserInfoResponse {
     pub user: User,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct UsersListRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
@@ -248,6 +265,8 @@ pub struct UsersListResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(UsersListRequest, UsersListResponse, members: User);
+
 #[derive(Debug, Deserialize)]
 pub struct UserProfileResponse {
     pub profile: serde_json::Value,
@@ -277,7 +296,7 @@ pub struct UserGetPresenceResponse {
     pub manual_away: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct UserConversationsRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
@@ -298,6 +317,12 @@ pub struct UserConversationsResponse {
     pub response_metadata: Option<ResponseMetadata>,
 }
 
+impl_paginated!(
+    UserConversationsRequest,
+    UserConversationsResponse,
+    channels: Channel
+);
+
 #[derive(Debug, Deserialize)]
 pub struct UserIdentityResponse {
     pub user: UserIdentity,
@@ -1,7 +1,7 @@
 //! Authentication configuration for Slack API
 
 use crate::error::{Result, SlackError};
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, COOKIE, USER_AGENT};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, COOKIE, ORIGIN};
 
 /// Authentication configuration
 #[derive(Clone, Debug)]
@@ -24,8 +24,25 @@ pub enum AuthType {
 
     /// Bot token (xoxb-)
     Bot { token: String },
+
+    /// App-level token (xapp-), optionally combined with bot and user tokens
+    ///
+    /// The app token is used for `apps.connections.open` and
+    /// `apps.event.authorizations.list`. Every other method uses the bot
+    /// token, or the user token if there is no bot token.
+    App {
+        app_token: String,
+        bot_token: Option<String>,
+        user_token: Option<String>,
+    },
 }
 
+/// Methods that must be called with an app-level token
+const APP_TOKEN_METHODS: &[&str] = &["apps.connections.open", "apps.event.authorizations.list"];
+
+/// Origin the Slack web client connects from
+const STEALTH_ORIGIN: &str = "https://app.slack.com";
+
 impl AuthConfig {
     /// Create a stealth mode authentication configuration
     ///
@@ -92,12 +109,64 @@ impl AuthConfig {
         }
     }
 
+    /// Create an app-level token authentication configuration
+    ///
+    /// App-level tokens are needed for Socket Mode. Add a bot or user token
+    /// with [`with_bot_token`](Self::with_bot_token) or
+    /// [`with_user_token`](Self::with_user_token) so the same client can also
+    /// call the rest of the Web API.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The app-level token (starts with xapp-)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use slacko::AuthConfig;
+    ///
+    /// let auth = AuthConfig::app("xapp-123...").with_bot_token("xoxb-456...");
+    /// ```
+    pub fn app(token: impl Into<String>) -> Self {
+        Self {
+            auth_type: AuthType::App {
+                app_token: token.into(),
+                bot_token: None,
+                user_token: None,
+            },
+        }
+    }
+
+    /// Add a bot token to an app-level configuration
+    ///
+    /// Web API methods other than the app-level ones are called with this
+    /// token. Has no effect on other authentication types.
+    pub fn with_bot_token(mut self, token: impl Into<String>) -> Self {
+        if let AuthType::App { bot_token, .. } = &mut self.auth_type {
+            *bot_token = Some(token.into());
+        }
+        self
+    }
+
+    /// Add a user token to an app-level configuration
+    ///
+    /// Used for Web API methods when there is no bot token. Has no effect on
+    /// other authentication types.
+    pub fn with_user_token(mut self, token: impl Into<String>) -> Self {
+        if let AuthType::App { user_token, .. } = &mut self.auth_type {
+            *user_token = Some(token.into());
+        }
+        self
+    }
+
     /// Load authentication from environment variables
     ///
     /// Checks for the following environment variables in order:
     /// 1. `SLACK_XOXC_TOKEN` and `SLACK_XOXD_COOKIE` for stealth mode
-    /// 2. `SLACK_XOXP_TOKEN` for OAuth
-    /// 3. `SLACK_BOT_TOKEN` or `SLACK_TOKEN` for bot tokens
+    /// 2. `SLACK_APP_TOKEN` for an app-level token, combined with
+    ///    `SLACK_BOT_TOKEN` (or `SLACK_TOKEN`) and `SLACK_XOXP_TOKEN` if set
+    /// 3. `SLACK_XOXP_TOKEN` for OAuth
+    /// 4. `SLACK_BOT_TOKEN` or `SLACK_TOKEN` for bot tokens
     ///
     /// # Example
     ///
@@ -115,6 +184,20 @@ impl AuthConfig {
             return Ok(Self::stealth(xoxc, xoxd));
         }
 
+        // Try app-level token, with bot and user tokens for the Web API
+        if let Ok(token) = std::env::var("SLACK_APP_TOKEN") {
+            let mut auth = Self::app(token);
+            if let Ok(bot) =
+                std::env::var("SLACK_BOT_TOKEN").or_else(|_| std::env::var("SLACK_TOKEN"))
+            {
+                auth = auth.with_bot_token(bot);
+            }
+            if let Ok(user) = std::env::var("SLACK_XOXP_TOKEN") {
+                auth = auth.with_user_token(user);
+            }
+            return Ok(auth);
+        }
+
         // Try OAuth token
         if let Ok(token) = std::env::var("SLACK_XOXP_TOKEN") {
             return Ok(Self::oauth(token));
@@ -128,12 +211,35 @@ impl AuthConfig {
         }
 
         Err(SlackError::config_error(
-            "No Slack credentials found in environment. Set SLACK_XOXC_TOKEN + SLACK_XOXD_COOKIE, SLACK_XOXP_TOKEN, or SLACK_BOT_TOKEN",
+            "No Slack credentials found in environment. Set SLACK_XOXC_TOKEN + SLACK_XOXD_COOKIE, SLACK_APP_TOKEN, SLACK_XOXP_TOKEN, or SLACK_BOT_TOKEN",
         ))
     }
 
-    /// Build HTTP headers for API requests
-    pub(crate) fn build_headers(&self) -> HeaderMap {
+    /// Replace the Web API access token after a token rotation
+    ///
+    /// For app-level credentials this is the bot token, or the user token if
+    /// there is no bot token. Stealth credentials are left unchanged.
+    pub(crate) fn replace_token(&mut self, new_token: String) {
+        match &mut self.auth_type {
+            AuthType::OAuth { token } | AuthType::Bot { token } => *token = new_token,
+            AuthType::App {
+                bot_token: Some(token),
+                ..
+            }
+            | AuthType::App {
+                bot_token: None,
+                user_token: Some(token),
+                ..
+            } => *token = new_token,
+            AuthType::App { .. } | AuthType::Stealth { .. } => {}
+        }
+    }
+
+    /// Build HTTP headers for a call to `method`
+    ///
+    /// With app-level credentials, picks the app token for app-level methods
+    /// and the bot or user token for everything else.
+    pub(crate) fn build_headers_for(&self, method: &str) -> HeaderMap {
         let mut headers = HeaderMap::new();
 
         match &self.auth_type {
@@ -152,6 +258,8 @@ impl AuthConfig {
                         .unwrap_or_else(|_| HeaderValue::from_static("")),
                 );
             }
+            // An empty token sends no credentials, e.g. for OAuth exchanges
+            AuthType::OAuth { token } | AuthType::Bot { token } if token.is_empty() => {}
             AuthType::OAuth { token } | AuthType::Bot { token } => {
                 headers.insert(
                     AUTHORIZATION,
@@ -159,9 +267,46 @@ impl AuthConfig {
                         .unwrap_or_else(|_| HeaderValue::from_static("")),
                 );
             }
+            AuthType::App {
+                app_token,
+                bot_token,
+                user_token,
+            } => {
+                let token = if APP_TOKEN_METHODS.contains(&method) {
+                    app_token
+                } else {
+                    bot_token
+                        .as_ref()
+                        .or(user_token.as_ref())
+                        .unwrap_or(app_token)
+                };
+                headers.insert(
+                    AUTHORIZATION,
+                    HeaderValue::from_str(&format!("Bearer {}", token))
+                        .unwrap_or_else(|_| HeaderValue::from_static("")),
+                );
+            }
         }
 
-        headers.insert(USER_AGENT, HeaderValue::from_static("slack-sdk-rust/0.1.0"));
+        headers
+    }
+
+    /// Build headers for WebSocket handshakes
+    ///
+    /// RTM and Socket Mode URLs carry their own credentials, but the web
+    /// client's WebSocket used in stealth mode also needs the `d` cookie and
+    /// the web client's origin.
+    pub(crate) fn build_websocket_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        if let AuthType::Stealth { xoxd_cookie, .. } = &self.auth_type {
+            headers.insert(
+                COOKIE,
+                HeaderValue::from_str(&format!("d={}", xoxd_cookie))
+                    .unwrap_or_else(|_| HeaderValue::from_static("")),
+            );
+            headers.insert(ORIGIN, HeaderValue::from_static(STEALTH_ORIGIN));
+        }
 
         headers
     }
@@ -172,6 +317,48 @@ impl AuthConfig {
             AuthType::Stealth { .. } => "stealth",
             AuthType::OAuth { .. } => "oauth",
             AuthType::Bot { .. } => "bot",
+            AuthType::App { .. } => "app",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bearer(auth: &AuthConfig, method: &str) -> String {
+        auth.build_headers_for(method)[AUTHORIZATION]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_app_token_selection() {
+        let auth = AuthConfig::app("xapp-1").with_bot_token("xoxb-2");
+        assert_eq!(bearer(&auth, "apps.connections.open"), "Bearer xapp-1");
+        assert_eq!(bearer(&auth, "chat.postMessage"), "Bearer xoxb-2");
+
+        let auth = AuthConfig::app("xapp-1").with_user_token("xoxp-3");
+        assert_eq!(bearer(&auth, "chat.postMessage"), "Bearer xoxp-3");
+
+        let auth = AuthConfig::app("xapp-1");
+        assert_eq!(bearer(&auth, "chat.postMessage"), "Bearer xapp-1");
+
+        // Plain tokens are used for everything
+        let auth = AuthConfig::bot("xoxb-2").with_user_token("xoxp-3");
+        assert_eq!(bearer(&auth, "apps.connections.open"), "Bearer xoxb-2");
+    }
+
+    #[test]
+    fn test_websocket_headers() {
+        let headers = AuthConfig::stealth("xoxc-1", "xoxd-2").build_websocket_headers();
+        assert_eq!(headers[COOKIE], "d=xoxd-2");
+        assert_eq!(headers[ORIGIN], "https://app.slack.com");
+        assert!(!headers.contains_key(AUTHORIZATION));
+
+        assert!(AuthConfig::bot("xoxb-2")
+            .build_websocket_headers()
+            .is_empty());
+    }
+}
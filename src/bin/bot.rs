@@ -2,7 +2,7 @@
 //!
 //! This bot joins a channel and responds to messages.
 
-use slacko::{AuthConfig, SlackClient};
+use slacko::{AuthConfig, SlackClient, SlackErrorCode};
 use std::env;
 use tracing::info;
 
@@ -34,13 +34,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Join the channel
     match client.conversations().join(&channel).await {
         Ok(_) => info!("Successfully joined channel"),
-        Err(e) => {
-            if e.to_string().contains("already_in_channel") {
-                info!("Already in channel");
-            } else {
-                return Err(e.into());
-            }
+        Err(e) if e.code() == Some(&SlackErrorCode::AlreadyInChannel) => {
+            info!("Already in channel");
         }
+        Err(e) => return Err(e.into()),
     }
 
     // Start RTM and listen for messages in the channel
@@ -10,12 +10,26 @@ use crate::api::{
     usergroups::UsergroupsApi, users::UsersApi, views::ViewsApi, workflows::WorkflowsApi,
 };
 use crate::auth::AuthConfig;
+use crate::credentials::{self, CredentialProvider, CredentialRefresh, SharedAuth};
 use crate::error::{Result, SlackError};
+use crate::middleware::{
+    ApiRequest, ApiResponse, BodyEncoding, FilePart, Middleware, MultipartBody, RequestBody,
+    ResponseAction,
+};
+use crate::pagination::{Paginated, PaginatedRequest, Paginator};
+use crate::rate_limit::{self, RateLimiter, RetryPolicy};
+use crate::rotation::TokenRotation;
 use crate::types::SlackResponse;
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) const SLACK_API_BASE: &str = "https://slack.com/api";
+const DEFAULT_USER_AGENT: &str = concat!("slacko/", env!("CARGO_PKG_VERSION"));
 
-const SLACK_API_BASE: &str = "https://slack.com/api";
+/// Upper bound on pipeline runs per call, in case middleware keeps retrying
+const MAX_ATTEMPTS: u32 = 16;
 
 /// Main Slack API client
 ///
@@ -47,8 +61,32 @@ const SLACK_API_BASE: &str = "https://slack.com/api";
 #[derive(Clone)]
 pub struct SlackClient {
     pub(crate) http: reqwest::Client,
-    pub(crate) auth: Arc<AuthConfig>,
+    pub(crate) auth: SharedAuth,
+    pub(crate) credentials: Option<Arc<dyn CredentialProvider>>,
     pub(crate) base_url: String,
+    pub(crate) middleware: Vec<Arc<dyn Middleware>>,
+    pub(crate) network: Arc<NetworkConfig>,
+    pub(crate) encodings: Arc<HashMap<String, BodyEncoding>>,
+}
+
+/// Network settings shared by HTTP requests and WebSocket connections
+#[derive(Debug, Clone)]
+pub(crate) struct NetworkConfig {
+    pub(crate) user_agent: String,
+    pub(crate) headers: HeaderMap,
+    pub(crate) proxy: Option<String>,
+    pub(crate) connect_timeout: Option<Duration>,
+}
+
+impl NetworkConfig {
+    /// Custom headers plus the user agent, sent with every request
+    pub(crate) fn default_headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        if let Ok(value) = HeaderValue::from_str(&self.user_agent) {
+            headers.insert(USER_AGENT, value);
+        }
+        headers
+    }
 }
 
 impl SlackClient {
@@ -68,16 +106,89 @@ impl SlackClient {
     /// ).unwrap();
     /// ```
     pub fn new(auth: AuthConfig) -> Result<Self> {
-        let http = reqwest::Client::builder()
-            .user_agent("slack-sdk-rust/0.1.0")
-            .build()
-            .map_err(|e| SlackError::config_error(format!("Failed to build HTTP client: {}", e)))?;
+        Self::builder(auth).build()
+    }
 
-        Ok(Self {
-            http,
-            auth: Arc::new(auth),
-            base_url: SLACK_API_BASE.to_string(),
-        })
+    /// Create a client whose credentials come from a [`CredentialProvider`]
+    ///
+    /// The provider is queried once now, and again whenever Slack answers
+    /// `invalid_auth` or `token_expired`; the failed request is then retried
+    /// with the new credentials. Clones of the client share the refreshed
+    /// credentials. Use [`SlackClientBuilder::credential_provider`] to combine
+    /// a provider with other builder settings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use slacko::credentials::CommandProvider;
+    /// use slacko::SlackClient;
+    ///
+    /// # async fn example() -> slacko::Result<()> {
+    /// let client = SlackClient::from_provider(CommandProvider::new("fetch-slack-token")).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_provider(provider: impl CredentialProvider + 'static) -> Result<Self> {
+        let auth = provider.credentials().await?;
+        Self::builder(auth).credential_provider(provider).build()
+    }
+
+    /// Query the credential provider again and use its credentials from now on
+    ///
+    /// Does nothing for clients created without a provider.
+    pub async fn refresh_credentials(&self) -> Result<()> {
+        match &self.credentials {
+            Some(provider) => credentials::refresh(provider.as_ref(), &self.auth).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Create a builder for a client with custom network settings
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use slacko::{SlackClient, AuthConfig};
+    /// use std::time::Duration;
+    ///
+    /// let client = SlackClient::builder(AuthConfig::bot("xoxb-token"))
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .timeout(Duration::from_secs(30))
+    ///     .proxy("http://proxy.internal:3128")
+    ///     .user_agent("my-bot/1.0")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(auth: AuthConfig) -> SlackClientBuilder {
+        SlackClientBuilder::new(auth)
+    }
+
+    /// Enable automatic rate limit handling
+    ///
+    /// Requests are paced with per-method token buckets based on Slack's
+    /// rate limit tiers, and requests that still get an HTTP 429 response are
+    /// retried after the `Retry-After` delay.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use slacko::{SlackClient, AuthConfig, RetryPolicy};
+    ///
+    /// let client = SlackClient::new(AuthConfig::bot("xoxb-token"))
+    ///     .unwrap()
+    ///     .with_retry_policy(RetryPolicy::default());
+    /// ```
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        self.with_middleware(RateLimiter::new(policy))
+    }
+
+    /// Add a middleware to the request pipeline
+    ///
+    /// Middleware added first runs first before a request is sent and last
+    /// after the response is received. See [`crate::middleware`].
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
     }
 
     /// Get the Activity API client
@@ -294,47 +405,140 @@ impl SlackClient {
         MessagesApi::new(self.clone())
     }
 
-    /// Make a POST request to the Slack API
-    pub(crate) async fn post<T: serde::de::DeserializeOwned>(
+    /// Stream every item of a cursor-paginated method
+    ///
+    /// The built-in list methods have `*_stream` shortcuts; this is for
+    /// methods without one, or for your own request/response types that
+    /// implement [`PaginatedRequest`] and [`Paginated`].
+    pub fn paginate<Q: PaginatedRequest, R: Paginated>(
         &self,
         method: &str,
-        params: &impl serde::Serialize,
-    ) -> Result<T> {
-        let url = format!("{}/{}", self.base_url, method);
-        let headers = self.auth.build_headers();
-
-        let response = self
-            .http
-            .post(&url)
-            .headers(headers)
-            .json(params)
-            .send()
-            .await?;
-
-        // Check for rate limiting
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
+        request: Q,
+    ) -> Paginator<Q, R> {
+        Paginator::new(self.clone(), method, request)
+    }
 
-            return Err(SlackError::RateLimitExceeded { retry_after });
-        }
+    /// Call any Web API method by name
+    ///
+    /// An escape hatch for methods this crate does not wrap yet. The request
+    /// goes through the same pipeline as the built-in methods (authentication,
+    /// middleware, rate limit retries), and Slack errors are mapped the same
+    /// way. Returns the complete response body, including `ok` and
+    /// `response_metadata`.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Method name, e.g. `bookmarks.list`
+    /// * `params` - Parameters and their encoding, see [`RequestBody`]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use slacko::middleware::RequestBody;
+    /// use slacko::{AuthConfig, SlackClient};
+    ///
+    /// # async fn example() -> slacko::Result<()> {
+    /// let client = SlackClient::new(AuthConfig::bot("xoxb-token"))?;
+    ///
+    /// let response = client
+    ///     .call("team.billing.info", RequestBody::query([("team_id", "T123")]))
+    ///     .await?;
+    /// println!("Plan: {}", response["plan"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call(&self, method: &str, params: RequestBody) -> Result<serde_json::Value> {
+        let response = self.execute(method, params).await?;
+        let _: serde::de::IgnoredAny = Self::parse_response(method, &response)?;
+        Ok(serde_json::from_slice(&response.body)?)
+    }
 
-        let slack_response: SlackResponse<T> = response.json().await?;
+    /// Call any Web API method by name and deserialize the response
+    ///
+    /// Like [`call`](Self::call), but parses the response into `T`. The
+    /// `ok`, `error` and `warning` fields are checked and removed first, so
+    /// `T` only needs to describe the method's own fields.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use serde::Deserialize;
+    /// use slacko::middleware::RequestBody;
+    /// use slacko::{AuthConfig, SlackClient};
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Bookmarks {
+    ///     bookmarks: Vec<serde_json::Value>,
+    /// }
+    ///
+    /// # async fn example() -> slacko::Result<()> {
+    /// let client = SlackClient::new(AuthConfig::bot("xoxb-token"))?;
+    ///
+    /// let params = serde_json::json!({ "channel_id": "C123" });
+    /// let response: Bookmarks = client
+    ///     .call_as("bookmarks.list", RequestBody::json(&params)?)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call_as<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: RequestBody,
+    ) -> Result<T> {
+        self.send(method, params).await
+    }
 
-        if !slack_response.ok {
-            let error_msg = slack_response
-                .error
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(SlackError::api_error(method, error_msg));
-        }
+    /// Body encoding used for a method's POST requests
+    ///
+    /// Overrides set with [`SlackClientBuilder::body_encoding`] take
+    /// precedence over [`BodyEncoding::for_method`].
+    pub fn body_encoding(&self, method: &str) -> BodyEncoding {
+        self.encodings
+            .get(method)
+            .copied()
+            .unwrap_or_else(|| BodyEncoding::for_method(method))
+    }
 
-        slack_response
-            .data
-            .ok_or_else(|| SlackError::api_error(method, "No data in response"))
+    /// Make a POST request to the Slack API, encoded as the method expects
+    pub(crate) async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &impl serde::Serialize,
+    ) -> Result<T> {
+        self.post_with_encoding(method, params, self.body_encoding(method))
+            .await
+    }
+
+    /// Make a POST request to the Slack API with an explicit body encoding
+    ///
+    /// Overrides [`body_encoding`](Self::body_encoding) for this call only,
+    /// e.g. for a method that accepts a different encoding than its default.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use slacko::middleware::BodyEncoding;
+    /// use slacko::{AuthConfig, SlackClient};
+    ///
+    /// # async fn example() -> slacko::Result<()> {
+    /// let client = SlackClient::new(AuthConfig::bot("xoxb-token"))?;
+    ///
+    /// let params = serde_json::json!({ "channel": "C123", "text": "Hello" });
+    /// let response: serde_json::Value = client
+    ///     .post_with_encoding("chat.postMessage", &params, BodyEncoding::Form)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn post_with_encoding<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &impl serde::Serialize,
+        encoding: BodyEncoding,
+    ) -> Result<T> {
+        let body = RequestBody::encode(params, encoding)?;
+        self.send(method, body).await
     }
 
     /// Make a GET request to the Slack API
@@ -343,47 +547,32 @@ impl SlackClient {
         method: &str,
         params: &[(&str, &str)],
     ) -> Result<T> {
-        let url = format!("{}/{}", self.base_url, method);
-        let headers = self.auth.build_headers();
-
-        let response = self
-            .http
-            .get(&url)
-            .headers(headers)
-            .query(params)
-            .send()
-            .await?;
-
-        // Check for rate limiting
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
-
-            return Err(SlackError::RateLimitExceeded { retry_after });
-        }
-
-        let slack_response: SlackResponse<T> = response.json().await?;
+        let body = RequestBody::Query(Self::owned_pairs(params));
+        self.send(method, body).await
+    }
 
-        if !slack_response.ok {
-            let error_msg = slack_response
-                .error
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(SlackError::api_error(method, error_msg));
-        }
+    /// Make a POST request using application/x-www-form-urlencoded
+    pub(crate) async fn post_form<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        let body = RequestBody::Form(Self::owned_pairs(params));
+        self.send(method, body).await
+    }
 
-        slack_response
-            .data
-            .ok_or_else(|| SlackError::api_error(method, "No data in response"))
+    /// Get headers for a call to `method`, with the token that method needs
+    pub(crate) fn headers_for(&self, method: &str) -> HeaderMap {
+        let mut headers = self.network.default_headers();
+        headers.extend(self.auth.read().unwrap().build_headers_for(method));
+        headers
     }
 
-    /// Get headers for API requests
-    #[allow(dead_code)]
-    pub(crate) fn headers(&self) -> HeaderMap {
-        self.auth.build_headers()
+    /// Get headers for WebSocket handshakes, with the stealth cookie if any
+    pub(crate) fn websocket_headers(&self) -> HeaderMap {
+        let mut headers = self.network.default_headers();
+        headers.extend(self.auth.read().unwrap().build_websocket_headers());
+        headers
     }
 
     /// Upload a file via multipart form
@@ -407,241 +596,435 @@ impl SlackClient {
         file_name: &str,
         params: &[(&str, &str)],
     ) -> Result<T> {
-        use reqwest::multipart::{Form, Part};
+        let body = MultipartBody {
+            fields: Self::owned_pairs(params),
+            files: vec![FilePart::new(field_name, file_name, file_data)],
+        };
+
+        self.send(method, RequestBody::Multipart(body)).await
+    }
 
-        let url = format!("{}/{}", self.base_url, method);
-        let headers = self.auth.build_headers();
+    fn owned_pairs(params: &[(&str, &str)]) -> Vec<(String, String)> {
+        params
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// Send a request through the pipeline and parse the standard Slack response
+    pub(crate) async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        body: RequestBody,
+    ) -> Result<T> {
+        let response = self.execute(method, body).await?;
+        Self::parse_response(method, &response)
+    }
+
+    /// Run a request through the middleware pipeline and return the raw response
+    ///
+    /// This is the single place where Web API requests are sent. Middleware
+    /// `before_request` hooks run in order, then the request is sent (unless a
+    /// middleware supplied a response), then `after_response` hooks run in
+    /// reverse order. If any of them asks for a retry, the whole pipeline runs
+    /// again with an incremented attempt number.
+    pub(crate) async fn execute(&self, method: &str, body: RequestBody) -> Result<ApiResponse> {
+        let mut attempt = 0;
+        let mut extensions = http::Extensions::new();
+
+        loop {
+            let mut request = ApiRequest {
+                method: method.to_string(),
+                url: format!("{}/{}", self.base_url, method),
+                headers: self.headers_for(method),
+                body: body.clone(),
+                attempt,
+                extensions,
+            };
+
+            let mut response = None;
+            for middleware in self.middleware.iter() {
+                if let Some(early) = middleware.before_request(&mut request).await? {
+                    response = Some(early);
+                    break;
+                }
+            }
 
-        let part = Part::bytes(file_data).file_name(file_name.to_string());
+            let mut response = match response {
+                Some(response) => response,
+                None => self.send_http(&request).await?,
+            };
+
+            let mut retry = false;
+            for middleware in self.middleware.iter().rev() {
+                if middleware.after_response(&request, &mut response).await?
+                    == ResponseAction::Retry
+                {
+                    retry = true;
+                }
+            }
 
-        let mut form = Form::new().part(field_name.to_string(), part);
+            if retry && attempt < MAX_ATTEMPTS {
+                attempt += 1;
+                extensions = request.extensions;
+                continue;
+            }
 
-        for (key, value) in params {
-            form = form.text(key.to_string(), value.to_string());
+            return Ok(response);
         }
+    }
+
+    /// Send a request over HTTP
+    async fn send_http(&self, request: &ApiRequest) -> Result<ApiResponse> {
+        tracing::debug!("Sending {} (attempt {})", request.method, request.attempt);
+
+        let builder = match &request.body {
+            RequestBody::Json(value) => self.http.post(&request.url).json(value),
+            RequestBody::Form(pairs) => self.http.post(&request.url).form(pairs),
+            RequestBody::Multipart(body) => self.http.post(&request.url).multipart(body.to_form()?),
+            RequestBody::Query(pairs) => self.http.get(&request.url).query(pairs),
+        };
 
-        let response = self
-            .http
-            .post(&url)
-            .headers(headers)
-            .multipart(form)
-            .send()
-            .await?;
-
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
+        let response = builder.headers(request.headers.clone()).send().await?;
 
+        Ok(ApiResponse {
+            status: response.status().as_u16(),
+            headers: response.headers().clone(),
+            body: response.bytes().await?.to_vec(),
+        })
+    }
+
+    /// Turn an HTTP 429 response into `RateLimitExceeded`
+    fn check_rate_limit(response: &ApiResponse) -> Result<()> {
+        if response.status == 429 {
+            let retry_after = rate_limit::retry_after_secs(&response.headers);
             return Err(SlackError::RateLimitExceeded { retry_after });
         }
+        Ok(())
+    }
 
-        let slack_response: SlackResponse<T> = response.json().await?;
+    /// Parse a raw response into the standard Slack response envelope
+    fn parse_response<T: serde::de::DeserializeOwned>(
+        method: &str,
+        response: &ApiResponse,
+    ) -> Result<T> {
+        Self::check_rate_limit(response)?;
+
+        let slack_response: SlackResponse<T> = serde_json::from_slice(&response.body)?;
+        Self::log_warnings(method, response);
 
         if !slack_response.ok {
-            let error_msg = slack_response
+            let error_code = slack_response
                 .error
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(SlackError::api_error(method, error_msg));
+                .unwrap_or_else(|| "unknown_error".to_string());
+            return Err(SlackError::from_api_response(
+                method,
+                error_code,
+                slack_response.needed,
+                slack_response.provided,
+            ));
         }
 
         slack_response
             .data
-            .ok_or_else(|| SlackError::api_error(method, "No data in response"))
+            .ok_or_else(|| SlackError::Other(format!("No data in {} response", method)))
     }
 
-    /// Make a POST request using multipart/form-data
-    /// This encodes each struct field as a separate form-data part
-    pub(crate) async fn post_multipart<T: serde::de::DeserializeOwned>(
-        &self,
-        method: &str,
-        params: &impl serde::Serialize,
-    ) -> Result<T> {
-        let url = format!("{}/{}", self.base_url, method);
-        let headers = self.auth.build_headers();
-
-        // Serialize struct to Value first, then convert to multipart form
-        let value = serde_json::to_value(params)?;
-        let form = self.build_multipart_form(&value)?;
-
-        let response = self
-            .http
-            .post(&url)
-            .headers(headers)
-            .multipart(form)
-            .send()
-            .await?;
-
-        // Check for rate limiting
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
+    /// Emit a `tracing` warning for each notice Slack attached to a response
+    fn log_warnings(method: &str, response: &ApiResponse) {
+        for warning in Self::response_warnings(response) {
+            tracing::warn!(method, "Slack API warning: {}", warning);
+        }
+    }
 
-            return Err(SlackError::RateLimitExceeded { retry_after });
+    /// Collect the top-level `warning` and `response_metadata` warnings/messages
+    ///
+    /// These sit next to the data rather than in it, so they are read from the
+    /// raw body instead of the typed response.
+    fn response_warnings(response: &ApiResponse) -> Vec<String> {
+        #[derive(serde::Deserialize)]
+        struct Envelope {
+            warning: Option<String>,
+            response_metadata: Option<Notices>,
         }
 
-        let slack_response: SlackResponse<T> = response.json().await?;
+        #[derive(serde::Deserialize)]
+        struct Notices {
+            #[serde(default)]
+            warnings: Vec<String>,
+            #[serde(default)]
+            messages: Vec<String>,
+        }
 
-        if !slack_response.ok {
-            let error_msg = slack_response
-                .error
-                .unwrap_or_else(|| "Unknown error".to_string());
-            return Err(SlackError::api_error(method, error_msg));
+        let Ok(envelope) = serde_json::from_slice::<Envelope>(&response.body) else {
+            return Vec::new();
+        };
+
+        // `warning` is a comma-separated list that usually repeats
+        // `response_metadata.warnings`
+        let mut warnings: Vec<String> = envelope
+            .warning
+            .iter()
+            .flat_map(|w| w.split(','))
+            .map(|w| w.trim().to_string())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        if let Some(notices) = envelope.response_metadata {
+            for warning in notices.warnings.into_iter().chain(notices.messages) {
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+            }
         }
 
-        slack_response
-            .data
-            .ok_or_else(|| SlackError::api_error(method, "No data in response"))
+        warnings
     }
+}
 
-    /// Make a POST request using multipart/form-data
-    /// This encodes each struct field as a separate form-data part
-    pub(crate) async fn post_multipart_direct<T: serde::de::DeserializeOwned>(
-        &self,
-        method: &str,
-        params: &impl serde::Serialize,
-    ) -> Result<T> {
-        let url = format!("{}/{}", self.base_url, method);
-        let headers = self.auth.build_headers();
-
-        // Serialize struct to Value first, then convert to multipart form
-        let value = serde_json::to_value(params)?;
-        let form = self.build_multipart_form(&value)?;
-
-        let response = self
-            .http
-            .post(&url)
-            .headers(headers)
-            .multipart(form)
-            .send()
-            .await?;
-
-        // Check for rate limiting
-        if response.status().as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(60);
+/// Builder for [`SlackClient`] with custom network settings
+///
+/// Created with [`SlackClient::builder`]. The user agent, custom headers,
+/// connect timeout and proxy also apply to RTM and Socket Mode WebSocket
+/// connections.
+pub struct SlackClientBuilder {
+    auth: AuthConfig,
+    base_url: String,
+    user_agent: String,
+    headers: HeaderMap,
+    proxy: Option<String>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    http: Option<reqwest::Client>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    encodings: HashMap<String, BodyEncoding>,
+    credentials: Option<Arc<dyn CredentialProvider>>,
+    rotation: Option<TokenRotation>,
+    error: Option<SlackError>,
+}
 
-            return Err(SlackError::RateLimitExceeded { retry_after });
+impl SlackClientBuilder {
+    fn new(auth: AuthConfig) -> Self {
+        Self {
+            auth,
+            base_url: SLACK_API_BASE.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            headers: HeaderMap::new(),
+            proxy: None,
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            http: None,
+            middleware: Vec::new(),
+            encodings: HashMap::new(),
+            credentials: None,
+            rotation: None,
+            error: None,
         }
+    }
 
-        let rsp: serde_json::Value = response.json().await?;
-        let rsp: T = serde_json::from_value(rsp)?;
+    /// Set the Web API base URL (default: `https://slack.com/api`)
+    ///
+    /// Useful for pointing the client at a local fake Slack in tests.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
 
-        Ok(rsp)
+    /// Set the `User-Agent` header
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
     }
 
-    /// Build multipart form from JSON value using reqwest's Form
-    fn build_multipart_form(&self, value: &serde_json::Value) -> Result<reqwest::multipart::Form> {
-        use reqwest::multipart::Form;
+    /// Add a custom header sent with every request
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.headers.insert(name, value);
+            }
+            _ => {
+                self.error = Some(SlackError::config_error(format!(
+                    "Invalid header: {}",
+                    name
+                )));
+            }
+        }
+        self
+    }
 
-        let mut form = Form::new();
+    /// Route requests through a proxy
+    ///
+    /// Supports `http://`, `https://` and `socks5://`/`socks5h://` proxy URLs,
+    /// with optional `user:password@` credentials. WebSocket connections
+    /// support `http://` and SOCKS5 proxies.
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
 
-        // Flatten the JSON object to handle nested structures
-        let flat_map = self.flatten_json_for_multipart(value, None)?;
+    /// Timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
 
-        for (key, val) in flat_map {
-            form = form.text(key, val);
-        }
+    /// Timeout for each read from an established connection
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
 
-        Ok(form)
+    /// Total timeout for each request, from connecting until the body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
     }
 
-    /// Flatten a JSON value into key-value pairs suitable for multipart form data
-    /// Handles nested objects and arrays by converting them to JSON strings
-    fn flatten_json_for_multipart(
-        &self,
-        value: &serde_json::Value,
-        prefix: Option<&str>,
-    ) -> Result<std::collections::HashMap<String, String>> {
-        let mut result = std::collections::HashMap::new();
-
-        match value {
-            serde_json::Value::Object(map) => {
-                for (key, val) in map {
-                    // Skip null values (same as serde skip_serializing_if)
-                    if val.is_null() {
-                        continue;
-                    }
-
-                    let full_key = match prefix {
-                        Some(p) => format!("{}[{}]", p, key),
-                        None => key.clone(),
-                    };
-
-                    match val {
-                        serde_json::Value::String(s) => {
-                            result.insert(full_key, s.clone());
-                        }
-                        serde_json::Value::Bool(b) => {
-                            result.insert(full_key, b.to_string());
-                        }
-                        serde_json::Value::Number(n) => {
-                            result.insert(full_key, n.to_string());
-                        }
-                        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
-                            // For objects, encode as JSON string
-                            let json_str = serde_json::to_string(val).map_err(|e| {
-                                SlackError::config_error(format!(
-                                    "Failed to serialize object: {}",
-                                    e
-                                ))
-                            })?;
-                            result.insert(full_key, json_str);
-                        }
-                        serde_json::Value::Null => {
-                            // Skip null values
-                        }
-                    }
+    /// Use a pre-built `reqwest::Client`
+    ///
+    /// The proxy and timeout settings of this builder are not applied to the
+    /// given client, only to WebSocket connections. The user agent and custom
+    /// headers are still sent with every request.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Enable automatic rate limit handling (see [`SlackClient::with_retry_policy`])
+    pub fn retry_policy(self, policy: RetryPolicy) -> Self {
+        self.middleware(RateLimiter::new(policy))
+    }
+
+    /// Add a middleware to the request pipeline (see [`SlackClient::with_middleware`])
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Re-query this provider when Slack rejects the credentials
+    ///
+    /// The credentials passed to [`SlackClient::builder`] are used until
+    /// then. See [`SlackClient::from_provider`].
+    pub fn credential_provider(mut self, provider: impl CredentialProvider + 'static) -> Self {
+        self.credentials = Some(Arc::new(provider));
+        self
+    }
+
+    /// Rotate the access token automatically (see [`crate::rotation`])
+    ///
+    /// The credentials passed to [`SlackClient::builder`] hold the current
+    /// access token.
+    pub fn token_rotation(mut self, rotation: TokenRotation) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    /// Override the POST body encoding for a method
+    ///
+    /// Built-in methods default to [`BodyEncoding::for_method`]. Use this when
+    /// a method needs a different encoding, e.g. a web-client method that only
+    /// accepts form fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use slacko::middleware::BodyEncoding;
+    /// use slacko::{AuthConfig, SlackClient};
+    ///
+    /// let client = SlackClient::builder(AuthConfig::bot("xoxb-token"))
+    ///     .body_encoding("conversations.list", BodyEncoding::Form)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(client.body_encoding("conversations.list"), BodyEncoding::Form);
+    /// ```
+    pub fn body_encoding(mut self, method: impl Into<String>, encoding: BodyEncoding) -> Self {
+        self.encodings.insert(method.into(), encoding);
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Result<SlackClient> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let http = match self.http {
+            Some(http) => http,
+            None => {
+                let mut builder = reqwest::Client::builder().user_agent(&self.user_agent);
+
+                if let Some(proxy) = &self.proxy {
+                    let proxy = reqwest::Proxy::all(proxy).map_err(|e| {
+                        SlackError::config_error(format!("Invalid proxy URL: {}", e))
+                    })?;
+                    builder = builder.proxy(proxy);
                 }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.read_timeout {
+                    builder = builder.read_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+
+                builder.build().map_err(|e| {
+                    SlackError::config_error(format!("Failed to build HTTP client: {}", e))
+                })?
             }
-            _ => {
-                // For non-object root values, use the prefix or default key
-                let key = prefix.unwrap_or("value").to_string();
-                let value_str = match value {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Bool(b) => b.to_string(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    serde_json::Value::Array(arr) => {
-                        // For arrays, join with commas
-                        let string_values: Vec<String> = arr
-                            .iter()
-                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                            .collect();
-                        if string_values.len() == arr.len() {
-                            string_values.join(",")
-                        } else {
-                            return Err(SlackError::config_error(
-                                "Arrays must contain only strings for multipart encoding",
-                            ));
-                        }
-                    }
-                    serde_json::Value::Object(_) => serde_json::to_string(value).map_err(|e| {
-                        SlackError::config_error(format!("Failed to serialize value: {}", e))
-                    })?,
-                    serde_json::Value::Null => return Ok(result), // Skip null values
-                };
-                result.insert(key, value_str);
-            }
+        };
+
+        let auth: SharedAuth = Arc::new(std::sync::RwLock::new(self.auth));
+        let mut client = SlackClient {
+            http,
+            auth: auth.clone(),
+            credentials: self.credentials.clone(),
+            base_url: self.base_url,
+            middleware: Vec::new(),
+            network: Arc::new(NetworkConfig {
+                user_agent: self.user_agent,
+                headers: self.headers,
+                proxy: self.proxy,
+                connect_timeout: self.connect_timeout,
+            }),
+            encodings: Arc::new(self.encodings),
+        };
+
+        // Built-in middleware runs before user middleware, so a retried
+        // request already carries the refreshed token when user hooks see it
+        let mut middleware: Vec<Arc<dyn Middleware>> = Vec::new();
+        if let Some(rotation) = self.rotation {
+            // Refresh calls authenticate with the client credentials alone, so
+            // they go through a copy of the client with its own empty token
+            // and no middleware, sharing only the network settings
+            let oauth = SlackClient {
+                auth: Arc::new(std::sync::RwLock::new(AuthConfig::bot(""))),
+                credentials: None,
+                ..client.clone()
+            };
+            middleware.push(Arc::new(rotation.into_middleware(oauth, auth.clone())));
         }
+        if let Some(provider) = self.credentials {
+            middleware.push(Arc::new(CredentialRefresh::new(provider, auth)));
+        }
+        middleware.extend(self.middleware);
+        client.middleware = middleware;
 
-        Ok(result)
+        Ok(client)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::SlackErrorCode;
 
     #[test]
     fn test_client_creation() {
@@ -654,4 +1037,201 @@ mod tests {
         let client = SlackClient::new(AuthConfig::stealth("xoxc-token", "xoxd-cookie"));
         assert!(client.is_ok());
     }
+
+    #[test]
+    fn test_builder_settings() {
+        let client = SlackClient::builder(AuthConfig::bot("xoxb-token"))
+            .base_url("http://127.0.0.1:8080/api/")
+            .user_agent("test-agent/1.0")
+            .header("X-Custom", "value")
+            .proxy("socks5://127.0.0.1:1080")
+            .connect_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url, "http://127.0.0.1:8080/api");
+
+        let headers = client.headers_for("auth.test");
+        assert_eq!(headers.get(USER_AGENT).unwrap(), "test-agent/1.0");
+        assert_eq!(headers.get("x-custom").unwrap(), "value");
+        assert_eq!(headers.get("authorization").unwrap(), "Bearer xoxb-token");
+    }
+
+    #[test]
+    fn test_body_encoding() {
+        let client = SlackClient::builder(AuthConfig::bot("xoxb-token"))
+            .body_encoding("chat.postMessage", BodyEncoding::Form)
+            .build()
+            .unwrap();
+
+        assert_eq!(client.body_encoding("chat.postMessage"), BodyEncoding::Form);
+        assert_eq!(client.body_encoding("chat.update"), BodyEncoding::Json);
+        assert_eq!(
+            client.body_encoding("activity.feed"),
+            BodyEncoding::Multipart
+        );
+
+        let params = serde_json::json!({
+            "channel": "C1",
+            "limit": 10,
+            "inclusive": true,
+            "blocks": [{"type": "divider"}],
+            "cursor": null,
+        });
+        let RequestBody::Form(fields) = RequestBody::encode(&params, BodyEncoding::Form).unwrap()
+        else {
+            panic!("expected a form body");
+        };
+        assert_eq!(
+            fields,
+            vec![
+                ("blocks".to_string(), r#"[{"type":"divider"}]"#.to_string()),
+                ("channel".to_string(), "C1".to_string()),
+                ("inclusive".to_string(), "true".to_string()),
+                ("limit".to_string(), "10".to_string()),
+            ]
+        );
+    }
+
+    /// Serves canned responses in order instead of hitting the network
+    struct Canned(std::sync::Mutex<Vec<ApiResponse>>);
+
+    #[async_trait::async_trait]
+    impl Middleware for Canned {
+        async fn before_request(&self, _request: &mut ApiRequest) -> Result<Option<ApiResponse>> {
+            Ok(Some(self.0.lock().unwrap().remove(0)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_short_circuit() {
+        let client = SlackClient::new(AuthConfig::bot("xoxb-token"))
+            .unwrap()
+            .with_middleware(Canned(std::sync::Mutex::new(vec![ApiResponse::json(
+                &serde_json::json!({"ok": true, "url": "wss://example"}),
+            )])));
+
+        let value: serde_json::Value = client.post("rtm.connect", &()).await.unwrap();
+        assert_eq!(value["url"], "wss://example");
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_retries_rate_limited_request() {
+        let mut limited = ApiResponse::json(&serde_json::json!({"ok": false}));
+        limited.status = 429;
+        limited
+            .headers
+            .insert(reqwest::header::RETRY_AFTER, HeaderValue::from_static("0"));
+
+        let client = SlackClient::new(AuthConfig::bot("xoxb-token"))
+            .unwrap()
+            .with_retry_policy(RetryPolicy::default().pace_requests(false))
+            .with_middleware(Canned(std::sync::Mutex::new(vec![
+                limited.clone(),
+                ApiResponse::json(&serde_json::json!({"ok": true, "ts": "1.2"})),
+            ])));
+
+        let value: serde_json::Value = client.post("chat.update", &()).await.unwrap();
+        assert_eq!(value["ts"], "1.2");
+
+        // Without a retry policy the 429 is surfaced as an error
+        let client = SlackClient::new(AuthConfig::bot("xoxb-token"))
+            .unwrap()
+            .with_middleware(Canned(std::sync::Mutex::new(vec![limited])));
+        let err = client
+            .post::<serde_json::Value>("chat.update", &())
+            .await
+            .unwrap_err();
+        assert!(err.is_rate_limit());
+    }
+
+    #[tokio::test]
+    async fn test_error_code_mapping() {
+        let client = SlackClient::new(AuthConfig::bot("xoxb-token"))
+            .unwrap()
+            .with_middleware(Canned(std::sync::Mutex::new(vec![
+                ApiResponse::json(&serde_json::json!({
+                    "ok": false,
+                    "error": "missing_scope",
+                    "needed": "chat:write",
+                    "provided": "channels:read"
+                })),
+                ApiResponse::json(&serde_json::json!({"ok": false, "error": "channel_not_found"})),
+                ApiResponse::json(&serde_json::json!({"ok": false, "error": "brand_new_error"})),
+                ApiResponse::json(&serde_json::json!({"ok": false, "error": "user_is_bot"})),
+            ])));
+
+        match client
+            .post::<serde_json::Value>("chat.postMessage", &())
+            .await
+        {
+            Err(SlackError::PermissionDenied {
+                method,
+                needed,
+                provided,
+                ..
+            }) => {
+                assert_eq!(method.as_deref(), Some("chat.postMessage"));
+                assert_eq!(needed.as_deref(), Some("chat:write"));
+                assert_eq!(provided.as_deref(), Some("channels:read"));
+            }
+            other => panic!("Expected PermissionDenied, got {:?}", other),
+        }
+
+        let err = client
+            .post::<serde_json::Value>("conversations.info", &())
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.code(), Some(&SlackErrorCode::ChannelNotFound));
+
+        let err = client
+            .post::<serde_json::Value>("chat.update", &())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.code(),
+            Some(&SlackErrorCode::Other("brand_new_error".to_string()))
+        );
+        assert_eq!(err.method(), Some("chat.update"));
+
+        let err = client
+            .post::<serde_json::Value>("search.messages", &())
+            .await
+            .unwrap_err();
+        assert!(err.is_permission_denied());
+        assert_eq!(err.code(), Some(&SlackErrorCode::UserIsBot));
+    }
+
+    #[test]
+    fn test_response_warnings() {
+        let response = ApiResponse::json(&serde_json::json!({
+            "ok": true,
+            "warning": "missing_charset,method_deprecated",
+            "response_metadata": {
+                "warnings": ["missing_charset"],
+                "messages": ["[WARN] files.upload is deprecated"]
+            }
+        }));
+
+        assert_eq!(
+            SlackClient::response_warnings(&response),
+            vec![
+                "missing_charset",
+                "method_deprecated",
+                "[WARN] files.upload is deprecated"
+            ]
+        );
+
+        let response = ApiResponse::json(&serde_json::json!({"ok": true}));
+        assert!(SlackClient::response_warnings(&response).is_empty());
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_header() {
+        let result = SlackClient::builder(AuthConfig::bot("xoxb-token"))
+            .header("Bad Header", "value")
+            .build();
+        assert!(result.is_err());
+    }
 }
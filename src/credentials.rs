@@ -0,0 +1,445 @@
+//! Credential providers
+//!
+//! A [`CredentialProvider`] supplies the [`AuthConfig`] a client uses. A
+//! client built with [`SlackClient::from_provider`] asks the provider again
+//! whenever Slack answers `invalid_auth` or `token_expired`, and retries the
+//! request once with the new credentials, so rotated tokens are picked up
+//! without restarting.
+//!
+//! Built-in providers:
+//!
+//! - [`EnvProvider`]: the environment variables read by [`AuthConfig::from_env`]
+//! - [`ProfileProvider`]: a named profile in `~/.config/slacko/credentials.toml`
+//! - [`CommandProvider`]: the output of an external command, like git
+//!   credential helpers
+//! - [`StaticProvider`]: a fixed [`AuthConfig`]
+//!
+//! # Profiles file
+//!
+//! ```toml
+//! [work]
+//! app_token = "xapp-..."
+//! bot_token = "xoxb-..."
+//!
+//! [personal]
+//! xoxc_token = "xoxc-..."
+//! xoxd_cookie = "xoxd-..."
+//! ```
+//!
+//! Each profile, and the output of a credential command, uses the same keys:
+//! `bot_token`, `user_token`, `app_token`, `xoxc_token` and `xoxd_cookie`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use slacko::credentials::ProfileProvider;
+//! use slacko::SlackClient;
+//!
+//! # async fn example() -> slacko::Result<()> {
+//! let client = SlackClient::from_provider(ProfileProvider::new("work")).await?;
+//! client.chat().post_message("#general", "Hello").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`SlackClient::from_provider`]: crate::SlackClient::from_provider
+
+use crate::auth::AuthConfig;
+use crate::error::{Result, SlackError};
+use crate::middleware::{ApiRequest, ApiResponse, Middleware, ResponseAction};
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Source of Slack credentials
+///
+/// Called once when the client is created and again when Slack rejects the
+/// current credentials. Implementations should return the latest
+/// credentials each time, re-reading files or re-running commands as needed.
+#[async_trait]
+pub trait CredentialProvider: Send + Sync {
+    /// Fetch the current credentials
+    async fn credentials(&self) -> Result<AuthConfig>;
+}
+
+/// Credentials from environment variables (see [`AuthConfig::from_env`])
+#[derive(Debug, Clone, Default)]
+pub struct EnvProvider;
+
+#[async_trait]
+impl CredentialProvider for EnvProvider {
+    async fn credentials(&self) -> Result<AuthConfig> {
+        AuthConfig::from_env()
+    }
+}
+
+/// A fixed set of credentials
+#[derive(Debug, Clone)]
+pub struct StaticProvider(pub AuthConfig);
+
+#[async_trait]
+impl CredentialProvider for StaticProvider {
+    async fn credentials(&self) -> Result<AuthConfig> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Credentials from a named profile in a TOML file
+///
+/// The file is re-read on every call, so editing it takes effect the next
+/// time the client re-queries its provider.
+#[derive(Debug, Clone)]
+pub struct ProfileProvider {
+    path: Option<PathBuf>,
+    profile: String,
+}
+
+impl ProfileProvider {
+    /// Read `profile` from the default credentials file
+    ///
+    /// The default file is `$XDG_CONFIG_HOME/slacko/credentials.toml`, or
+    /// `~/.config/slacko/credentials.toml` if `XDG_CONFIG_HOME` is not set.
+    pub fn new(profile: impl Into<String>) -> Self {
+        Self {
+            path: None,
+            profile: profile.into(),
+        }
+    }
+
+    /// Read profiles from this file instead of the default one
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Default location of the credentials file
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(config_dir.join("slacko").join("credentials.toml"))
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for ProfileProvider {
+    async fn credentials(&self) -> Result<AuthConfig> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => Self::default_path().ok_or_else(|| {
+                SlackError::config_error("Cannot locate credentials file: HOME is not set")
+            })?,
+        };
+
+        let contents = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            SlackError::config_error(format!("Cannot read {}: {}", path.display(), e))
+        })?;
+        let mut profiles: HashMap<String, CredentialFields> = toml::from_str(&contents)
+            .map_err(|e| SlackError::config_error(format!("Invalid {}: {}", path.display(), e)))?;
+
+        profiles
+            .remove(&self.profile)
+            .ok_or_else(|| {
+                SlackError::config_error(format!(
+                    "No profile [{}] in {}",
+                    self.profile,
+                    path.display()
+                ))
+            })?
+            .into_auth()
+    }
+}
+
+/// Credentials printed by an external command
+///
+/// The command is run on every call and should print `key=value` lines
+/// using the profile keys (`bot_token`, `app_token`, ...), or just a single
+/// token, whose type is inferred from its prefix.
+///
+/// # Example
+///
+/// ```no_run
+/// use slacko::credentials::CommandProvider;
+/// use slacko::SlackClient;
+///
+/// # async fn example() -> slacko::Result<()> {
+/// let provider = CommandProvider::new("pass").arg("show").arg("slack/bot-token");
+/// let client = SlackClient::from_provider(provider).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommandProvider {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandProvider {
+    /// Run `program` with no arguments
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Add an argument
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for CommandProvider {
+    async fn credentials(&self) -> Result<AuthConfig> {
+        let output = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await
+            .map_err(|e| SlackError::config_error(format!("Cannot run {}: {}", self.program, e)))?;
+
+        if !output.status.success() {
+            return Err(SlackError::config_error(format!(
+                "{} failed with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        CredentialFields::parse(&String::from_utf8_lossy(&output.stdout))?.into_auth()
+    }
+}
+
+/// Credentials as written in a profile or printed by a command
+#[derive(Debug, Default, Deserialize)]
+struct CredentialFields {
+    bot_token: Option<String>,
+    user_token: Option<String>,
+    app_token: Option<String>,
+    xoxc_token: Option<String>,
+    xoxd_cookie: Option<String>,
+}
+
+impl CredentialFields {
+    /// Parse `key=value` lines, or a single bare token
+    fn parse(output: &str) -> Result<Self> {
+        let mut fields = Self::default();
+        let lines: Vec<&str> = output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        if let [token] = lines[..] {
+            if !token.contains('=') {
+                match token.get(..5) {
+                    Some("xoxb-") => fields.bot_token = Some(token.to_string()),
+                    Some("xoxp-") => fields.user_token = Some(token.to_string()),
+                    Some("xapp-") => fields.app_token = Some(token.to_string()),
+                    _ => {
+                        return Err(SlackError::config_error(
+                            "Credential command printed a token of unknown type",
+                        ))
+                    }
+                }
+                return Ok(fields);
+            }
+        }
+
+        for line in lines {
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(SlackError::config_error(
+                    "Credential command output must be key=value lines",
+                ));
+            };
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "bot_token" => fields.bot_token = value,
+                "user_token" => fields.user_token = value,
+                "app_token" => fields.app_token = value,
+                "xoxc_token" => fields.xoxc_token = value,
+                "xoxd_cookie" => fields.xoxd_cookie = value,
+                // Ignore unknown keys, like git credential helpers do
+                _ => {}
+            }
+        }
+
+        Ok(fields)
+    }
+
+    /// Pick the authentication type, in the same order as `AuthConfig::from_env`
+    fn into_auth(self) -> Result<AuthConfig> {
+        if let (Some(xoxc), Some(xoxd)) = (self.xoxc_token, self.xoxd_cookie) {
+            return Ok(AuthConfig::stealth(xoxc, xoxd));
+        }
+
+        match (self.app_token, self.bot_token, self.user_token) {
+            (Some(app), bot, user) => {
+                let mut auth = AuthConfig::app(app);
+                if let Some(bot) = bot {
+                    auth = auth.with_bot_token(bot);
+                }
+                if let Some(user) = user {
+                    auth = auth.with_user_token(user);
+                }
+                Ok(auth)
+            }
+            (None, _, Some(user)) => Ok(AuthConfig::oauth(user)),
+            (None, Some(bot), None) => Ok(AuthConfig::bot(bot)),
+            (None, None, None) => Err(SlackError::config_error("No Slack credentials found")),
+        }
+    }
+}
+
+/// Credentials shared by a client and its clones
+pub(crate) type SharedAuth = Arc<RwLock<AuthConfig>>;
+
+/// Error codes after which the provider is asked for new credentials
+const REFRESH_ERRORS: &[&str] = &["invalid_auth", "token_expired"];
+
+/// Middleware that re-queries the provider when Slack rejects the credentials
+pub(crate) struct CredentialRefresh {
+    provider: Arc<dyn CredentialProvider>,
+    auth: SharedAuth,
+}
+
+impl CredentialRefresh {
+    pub(crate) fn new(provider: Arc<dyn CredentialProvider>, auth: SharedAuth) -> Self {
+        Self { provider, auth }
+    }
+}
+
+/// Replace the shared credentials with fresh ones from the provider
+pub(crate) async fn refresh(provider: &dyn CredentialProvider, auth: &SharedAuth) -> Result<()> {
+    let fresh = provider.credentials().await?;
+    *auth.write().unwrap() = fresh;
+    Ok(())
+}
+
+/// Set once a call has been retried with refreshed credentials
+#[derive(Clone, Default)]
+struct Retried(Arc<AtomicBool>);
+
+/// Whether `used` already carries every credential header in `current`
+fn same_credentials(used: &HeaderMap, current: &HeaderMap) -> bool {
+    current
+        .keys()
+        .all(|name| used.get_all(name).iter().eq(current.get_all(name).iter()))
+}
+
+#[async_trait]
+impl Middleware for CredentialRefresh {
+    async fn before_request(&self, request: &mut ApiRequest) -> Result<Option<ApiResponse>> {
+        request.extensions.get_or_insert_default::<Retried>();
+        Ok(None)
+    }
+
+    async fn after_response(
+        &self,
+        request: &ApiRequest,
+        response: &mut ApiResponse,
+    ) -> Result<ResponseAction> {
+        let rejected = response
+            .body_json()
+            .ok()
+            .and_then(|body| body["error"].as_str().map(str::to_string))
+            .is_some_and(|code| REFRESH_ERRORS.contains(&code.as_str()));
+        if !rejected {
+            return Ok(ResponseAction::Continue);
+        }
+
+        // Fresh credentials that are rejected too won't be retried again
+        let retried = request.extensions.get::<Retried>();
+        if retried.is_some_and(|retried| retried.0.load(Ordering::SeqCst)) {
+            return Ok(ResponseAction::Continue);
+        }
+
+        // Compare all credential headers, so a rotated stealth cookie counts
+        let unchanged = || {
+            let current = self.auth.read().unwrap().build_headers_for(&request.method);
+            same_credentials(&request.headers, &current)
+        };
+
+        // Another request may have refreshed the credentials already
+        if unchanged() {
+            if let Err(e) = refresh(self.provider.as_ref(), &self.auth).await {
+                tracing::warn!(method = %request.method, "Failed to refresh credentials: {}", e);
+                return Ok(ResponseAction::Continue);
+            }
+        }
+
+        // Only retry if the provider actually had something new
+        if !unchanged() {
+            tracing::debug!(method = %request.method, "Retrying with refreshed credentials");
+            if let Some(retried) = retried {
+                retried.0.store(true, Ordering::SeqCst);
+            }
+            Ok(ResponseAction::Retry)
+        } else {
+            Ok(ResponseAction::Continue)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_output() {
+        let auth = CredentialFields::parse("xoxb-123\n")
+            .unwrap()
+            .into_auth()
+            .unwrap();
+        assert_eq!(auth.auth_type_str(), "bot");
+
+        let auth =
+            CredentialFields::parse("app_token=xapp-1\nbot_token = xoxb-2\nhost=slack.com\n")
+                .unwrap()
+                .into_auth()
+                .unwrap();
+        assert_eq!(auth.auth_type_str(), "app");
+
+        assert!(CredentialFields::parse("hunter2").is_err());
+        assert!(CredentialFields::parse("").unwrap().into_auth().is_err());
+    }
+
+    #[test]
+    fn test_same_credentials_compares_cookie() {
+        let used = AuthConfig::stealth("xoxc-1", "xoxd-1").build_headers_for("auth.test");
+        let rotated = AuthConfig::stealth("xoxc-1", "xoxd-2").build_headers_for("auth.test");
+        assert!(same_credentials(&used, &used));
+        assert!(!same_credentials(&used, &rotated));
+    }
+
+    #[tokio::test]
+    async fn test_profile_file() {
+        let path =
+            std::env::temp_dir().join(format!("slacko-profiles-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[work]\nbot_token = \"xoxb-work\"\n\n[personal]\nxoxc_token = \"xoxc-1\"\nxoxd_cookie = \"xoxd-2\"\n",
+        )
+        .unwrap();
+
+        let work = ProfileProvider::new("work").path(&path);
+        assert_eq!(work.credentials().await.unwrap().auth_type_str(), "bot");
+        let personal = ProfileProvider::new("personal").path(&path);
+        assert_eq!(
+            personal.credentials().await.unwrap().auth_type_str(),
+            "stealth"
+        );
+        let missing = ProfileProvider::new("missing").path(&path);
+        assert!(missing.credentials().await.is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,5 +1,7 @@
 //! Error types for the Slack SDK
 
+use std::fmt;
+
 /// Result type alias for Slack SDK operations
 pub type Result<T> = std::result::Result<T, SlackError>;
 
@@ -11,8 +13,13 @@ pub enum SlackError {
     HttpError(#[from] reqwest::Error),
 
     /// API error returned by Slack
-    #[error("Slack API error: {code} - {message}")]
-    ApiError { code: String, message: String },
+    #[error("Slack API error in {method}: {code}")]
+    ApiError {
+        /// The Web API method that failed, e.g. `chat.postMessage`
+        method: String,
+        /// The error code returned by Slack
+        code: SlackErrorCode,
+    },
 
     /// JSON serialization/deserialization error
     #[error("JSON error: {0}")]
@@ -23,8 +30,17 @@ pub enum SlackError {
     WebSocketError(String),
 
     /// Authentication error
-    #[error("Authentication error: {0}")]
-    AuthError(String),
+    ///
+    /// Returned for Slack's `not_authed`, `invalid_auth`, `account_inactive`,
+    /// `token_revoked` and `token_expired` errors.
+    #[error("Authentication error: {message}")]
+    AuthError {
+        message: String,
+        /// The Web API method that failed, if this came from Slack
+        method: Option<String>,
+        /// The error code returned by Slack, if any
+        code: Option<SlackErrorCode>,
+    },
 
     /// Invalid configuration
     #[error("Configuration error: {0}")]
@@ -39,12 +55,33 @@ pub enum SlackError {
     RateLimitExceeded { retry_after: u64 },
 
     /// Resource not found
-    #[error("Resource not found: {0}")]
-    NotFound(String),
+    ///
+    /// Returned for Slack's `*_not_found` errors.
+    #[error("Resource not found: {message}")]
+    NotFound {
+        message: String,
+        /// The Web API method that failed, if this came from Slack
+        method: Option<String>,
+        /// The error code returned by Slack, if any
+        code: Option<SlackErrorCode>,
+    },
 
     /// Permission denied
-    #[error("Permission denied: {0}")]
-    PermissionDenied(String),
+    ///
+    /// Returned for Slack's `missing_scope`, `no_permission`,
+    /// `not_allowed_token_type`, `restricted_action` and `access_denied` errors.
+    #[error("Permission denied: {message}")]
+    PermissionDenied {
+        message: String,
+        /// The Web API method that failed, if this came from Slack
+        method: Option<String>,
+        /// The error code returned by Slack, if any
+        code: Option<SlackErrorCode>,
+        /// Scopes required by the method (for `missing_scope`)
+        needed: Option<String>,
+        /// Scopes granted to the token (for `missing_scope`)
+        provided: Option<String>,
+    },
 
     /// Generic error
     #[error("{0}")]
@@ -52,11 +89,63 @@ pub enum SlackError {
 }
 
 impl SlackError {
-    /// Create an API error from Slack's error response
+    /// Create an error from Slack's error response
+    ///
+    /// The error code is mapped to [`AuthError`](SlackError::AuthError),
+    /// [`NotFound`](SlackError::NotFound) or
+    /// [`PermissionDenied`](SlackError::PermissionDenied) where it fits, and
+    /// to [`ApiError`](SlackError::ApiError) otherwise.
+    pub fn api_error_for(method: impl Into<String>, code: impl Into<String>) -> Self {
+        Self::from_api_response(method, code, None, None)
+    }
+
+    /// Create an error from Slack's error response
+    ///
+    /// As in earlier versions, `code` is the method name and `message` is the
+    /// error Slack returned.
+    #[deprecated(note = "use `SlackError::api_error_for(method, code)`")]
     pub fn api_error(code: impl Into<String>, message: impl Into<String>) -> Self {
-        Self::ApiError {
-            code: code.into(),
-            message: message.into(),
+        Self::api_error_for(code, message)
+    }
+
+    /// Create an error from Slack's error response, including the
+    /// `needed`/`provided` scopes sent with `missing_scope`
+    pub(crate) fn from_api_response(
+        method: impl Into<String>,
+        code: impl Into<String>,
+        needed: Option<String>,
+        provided: Option<String>,
+    ) -> Self {
+        let method = method.into();
+        let code = SlackErrorCode::from(code.into().as_str());
+        let message = format!("{}: {}", method, code);
+
+        if code.is_auth_error() {
+            Self::AuthError {
+                message,
+                method: Some(method),
+                code: Some(code),
+            }
+        } else if code.is_not_found() {
+            Self::NotFound {
+                message,
+                method: Some(method),
+                code: Some(code),
+            }
+        } else if code.is_permission_denied() {
+            let message = match &needed {
+                Some(needed) => format!("{} (needed: {})", message, needed),
+                None => message,
+            };
+            Self::PermissionDenied {
+                message,
+                method: Some(method),
+                code: Some(code),
+                needed,
+                provided,
+            }
+        } else {
+            Self::ApiError { method, code }
         }
     }
 
@@ -67,7 +156,11 @@ impl SlackError {
 
     /// Create an authentication error
     pub fn auth_error(msg: impl Into<String>) -> Self {
-        Self::AuthError(msg.into())
+        Self::AuthError {
+            message: msg.into(),
+            method: None,
+            code: None,
+        }
     }
 
     /// Create a configuration error
@@ -75,6 +168,28 @@ impl SlackError {
         Self::ConfigError(msg.into())
     }
 
+    /// The Slack error code, if this error came from a Slack API response
+    pub fn code(&self) -> Option<&SlackErrorCode> {
+        match self {
+            SlackError::ApiError { code, .. } => Some(code),
+            SlackError::AuthError { code, .. }
+            | SlackError::NotFound { code, .. }
+            | SlackError::PermissionDenied { code, .. } => code.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// The Web API method that failed, if this error came from a Slack API response
+    pub fn method(&self) -> Option<&str> {
+        match self {
+            SlackError::ApiError { method, .. } => Some(method),
+            SlackError::AuthError { method, .. }
+            | SlackError::NotFound { method, .. }
+            | SlackError::PermissionDenied { method, .. } => method.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Check if this is a rate limit error
     pub fn is_rate_limit(&self) -> bool {
         matches!(self, SlackError::RateLimitExceeded { .. })
@@ -82,7 +197,17 @@ impl SlackError {
 
     /// Check if this is an authentication error
     pub fn is_auth_error(&self) -> bool {
-        matches!(self, SlackError::AuthError(_))
+        matches!(self, SlackError::AuthError { .. })
+    }
+
+    /// Check if this is a not found error
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, SlackError::NotFound { .. })
+    }
+
+    /// Check if this is a permission error
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self, SlackError::PermissionDenied { .. })
     }
 }
 
@@ -91,3 +216,282 @@ impl From<tokio_tungstenite::tungstenite::Error> for SlackError {
         SlackError::WebSocketError(err.to_string())
     }
 }
+
+macro_rules! slack_error_codes {
+    ($($(#[$doc:meta])* $variant:ident => $code:literal,)*) => {
+        /// Error codes returned by the Slack Web API
+        ///
+        /// Covers the codes documented across Slack's Web API methods. Codes
+        /// that are not known to this crate are kept in
+        /// [`Other`](SlackErrorCode::Other).
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum SlackErrorCode {
+            $($(#[$doc])* $variant,)*
+            /// A code not known to this crate
+            Other(String),
+        }
+
+        impl SlackErrorCode {
+            /// The error code as sent by Slack, e.g. `channel_not_found`
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(SlackErrorCode::$variant => $code,)*
+                    SlackErrorCode::Other(code) => code,
+                }
+            }
+        }
+
+        impl From<&str> for SlackErrorCode {
+            fn from(code: &str) -> Self {
+                match code {
+                    $($code => SlackErrorCode::$variant,)*
+                    other => SlackErrorCode::Other(other.to_string()),
+                }
+            }
+        }
+    };
+}
+
+slack_error_codes! {
+    // Authentication
+    /// No authentication token provided
+    NotAuthed => "not_authed",
+    /// Invalid authentication token
+    InvalidAuth => "invalid_auth",
+    /// The token belongs to a deleted user or workspace
+    AccountInactive => "account_inactive",
+    /// The token has been revoked
+    TokenRevoked => "token_revoked",
+    /// The token has expired (token rotation)
+    TokenExpired => "token_expired",
+    /// Two-factor authentication setup is required
+    TwoFactorSetupRequired => "two_factor_setup_required",
+
+    // Permissions
+    /// The token lacks a required scope
+    MissingScope => "missing_scope",
+    /// The token does not have permission for this action
+    NoPermission => "no_permission",
+    /// The method can't be called with this token type
+    NotAllowedTokenType => "not_allowed_token_type",
+    /// A workspace preference prevents this action
+    RestrictedAction => "restricted_action",
+    /// Access to this resource was denied
+    AccessDenied => "access_denied",
+    /// The method can't be called by a bot user
+    UserIsBot => "user_is_bot",
+    /// The method is only available to paid workspaces
+    PaidTeamsOnly => "paid_teams_only",
+    /// The workspace must be part of an Enterprise organization
+    EnterpriseIsRestricted => "enterprise_is_restricted",
+    /// An Enterprise Key Management policy denied access
+    EkmAccessDenied => "ekm_access_denied",
+    /// The workspace has not granted the app access
+    TeamAccessNotGranted => "team_access_not_granted",
+    /// Login with the organization is required
+    OrgLoginRequired => "org_login_required",
+
+    // Not found
+    /// Channel not found
+    ChannelNotFound => "channel_not_found",
+    /// User not found
+    UserNotFound => "user_not_found",
+    /// One or more users not found
+    UsersNotFound => "users_not_found",
+    /// Message not found
+    MessageNotFound => "message_not_found",
+    /// File not found
+    FileNotFound => "file_not_found",
+    /// Thread not found
+    ThreadNotFound => "thread_not_found",
+    /// Team not found
+    TeamNotFound => "team_not_found",
+    /// User group not found
+    NoSuchSubteam => "no_such_subteam",
+    /// Bot not found
+    BotNotFound => "bot_not_found",
+    /// Generic not found (reminders, bookmarks, calls, ...)
+    NotFound => "not_found",
+
+    // Channels and membership
+    /// The caller is not a member of the channel
+    NotInChannel => "not_in_channel",
+    /// The user is already a member of the channel
+    AlreadyInChannel => "already_in_channel",
+    /// The channel is archived
+    IsArchived => "is_archived",
+    /// The channel is already archived
+    AlreadyArchived => "already_archived",
+    /// The channel is not archived
+    NotArchived => "not_archived",
+    /// A channel with this name already exists
+    NameTaken => "name_taken",
+    /// The channel name is invalid
+    InvalidName => "invalid_name",
+    /// The caller can't invite themselves
+    CantInviteSelf => "cant_invite_self",
+    /// The caller can't remove themselves
+    CantKickSelf => "cant_kick_self",
+    /// The general channel can't be left or archived
+    CantLeaveGeneral => "cant_leave_general",
+    /// The method can't be used on this channel type
+    MethodNotSupportedForChannelType => "method_not_supported_for_channel_type",
+
+    // Messages
+    /// The message has no text or blocks
+    NoText => "no_text",
+    /// The message text is too long
+    MsgTooLong => "msg_too_long",
+    /// Too many attachments
+    TooManyAttachments => "too_many_attachments",
+    /// Blocks are invalid
+    InvalidBlocks => "invalid_blocks",
+    /// Blocks are not in the expected format
+    InvalidBlocksFormat => "invalid_blocks_format",
+    /// The message can't be updated
+    CantUpdateMessage => "cant_update_message",
+    /// The message can't be deleted
+    CantDeleteMessage => "cant_delete_message",
+    /// The edit window for the message has closed
+    EditWindowClosed => "edit_window_closed",
+    /// The scheduled time is invalid
+    TimeInPast => "time_in_past",
+
+    // Reactions, pins and stars
+    /// The reaction was already added
+    AlreadyReacted => "already_reacted",
+    /// The reaction does not exist
+    NoReaction => "no_reaction",
+    /// Too many reactions on the item
+    TooManyReactions => "too_many_reactions",
+    /// The emoji name is invalid
+    InvalidEmojiName => "invalid_emoji_name",
+    /// The item is already pinned
+    AlreadyPinned => "already_pinned",
+    /// The item is not pinned
+    NotPinned => "not_pinned",
+    /// The item is already starred
+    AlreadyStarred => "already_starred",
+    /// The item is not starred
+    NotStarred => "not_starred",
+
+    // Views and interactivity
+    /// The trigger ID is invalid
+    InvalidTriggerId => "invalid_trigger_id",
+    /// The trigger ID has expired
+    ExpiredTriggerId => "expired_trigger_id",
+    /// The view is too large
+    ViewTooLarge => "view_too_large",
+    /// The view hash is outdated
+    HashConflict => "hash_conflict",
+
+    // Request errors
+    /// Invalid arguments
+    InvalidArguments => "invalid_arguments",
+    /// An argument name is invalid
+    InvalidArgName => "invalid_arg_name",
+    /// An array argument is invalid
+    InvalidArrayArg => "invalid_array_arg",
+    /// The charset is invalid
+    InvalidCharset => "invalid_charset",
+    /// The form data is invalid
+    InvalidFormData => "invalid_form_data",
+    /// The POST content type is invalid
+    InvalidPostType => "invalid_post_type",
+    /// The POST content type is missing
+    MissingPostType => "missing_post_type",
+    /// The request body is invalid JSON
+    InvalidJson => "invalid_json",
+    /// The JSON body is not an object
+    JsonNotObject => "json_not_object",
+    /// The pagination cursor is invalid
+    InvalidCursor => "invalid_cursor",
+    /// The `limit` argument is invalid
+    InvalidLimit => "invalid_limit",
+    /// The `latest` timestamp is invalid
+    InvalidTsLatest => "invalid_ts_latest",
+    /// The `oldest` timestamp is invalid
+    InvalidTsOldest => "invalid_ts_oldest",
+    /// The request timed out
+    RequestTimeout => "request_timeout",
+    /// The method is deprecated
+    MethodDeprecated => "method_deprecated",
+    /// The endpoint is deprecated
+    DeprecatedEndpoint => "deprecated_endpoint",
+    /// The method does not exist
+    UnknownMethod => "unknown_method",
+    /// The request was rate limited
+    Ratelimited => "ratelimited",
+    /// The workspace is being migrated
+    TeamAddedToOrg => "team_added_to_org",
+
+    // Server errors
+    /// Slack encountered a fatal error
+    FatalError => "fatal_error",
+    /// Slack encountered an internal error
+    InternalError => "internal_error",
+    /// Slack is temporarily unavailable
+    ServiceUnavailable => "service_unavailable",
+}
+
+impl SlackErrorCode {
+    /// Whether this code means the token is missing, invalid or no longer valid
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            SlackErrorCode::NotAuthed
+                | SlackErrorCode::InvalidAuth
+                | SlackErrorCode::AccountInactive
+                | SlackErrorCode::TokenRevoked
+                | SlackErrorCode::TokenExpired
+        )
+    }
+
+    /// Whether this code means the requested resource does not exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            SlackErrorCode::ChannelNotFound
+                | SlackErrorCode::UserNotFound
+                | SlackErrorCode::UsersNotFound
+                | SlackErrorCode::MessageNotFound
+                | SlackErrorCode::FileNotFound
+                | SlackErrorCode::ThreadNotFound
+                | SlackErrorCode::TeamNotFound
+                | SlackErrorCode::NoSuchSubteam
+                | SlackErrorCode::BotNotFound
+                | SlackErrorCode::NotFound
+        )
+    }
+
+    /// Whether this code means the token is not allowed to perform the call
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(
+            self,
+            SlackErrorCode::MissingScope
+                | SlackErrorCode::NoPermission
+                | SlackErrorCode::NotAllowedTokenType
+                | SlackErrorCode::RestrictedAction
+                | SlackErrorCode::AccessDenied
+                | SlackErrorCode::UserIsBot
+                | SlackErrorCode::PaidTeamsOnly
+                | SlackErrorCode::EnterpriseIsRestricted
+                | SlackErrorCode::EkmAccessDenied
+                | SlackErrorCode::TeamAccessNotGranted
+                | SlackErrorCode::OrgLoginRequired
+        )
+    }
+}
+
+impl fmt::Display for SlackErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for SlackErrorCode {
+    fn from(code: String) -> Self {
+        SlackErrorCode::from(code.as_str())
+    }
+}
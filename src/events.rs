@@ -0,0 +1,488 @@
+//! Typed Events API and RTM events
+//!
+//! [`Event`] covers the common events delivered through the Events API
+//! (Socket Mode or HTTP) and RTM. Events the enum does not know, and known
+//! events whose shape does not match, are kept as [`Event::Unknown`] with the
+//! raw JSON, so new Slack events never break parsing.
+//!
+//! ```
+//! use slacko::events::{Event, MessageSubtype};
+//!
+//! let event: Event = serde_json::from_str(
+//!     r#"{"type": "message", "channel": "C1", "user": "U1", "text": "hi", "ts": "1.2"}"#,
+//! )
+//! .unwrap();
+//!
+//! match event {
+//!     Event::Message(message) if message.subtype.is_none() => {
+//!         println!("{:?} said {:?}", message.user, message.text);
+//!     }
+//!     Event::Message(message) if message.subtype == Some(MessageSubtype::MessageChanged) => {}
+//!     Event::AppMention(mention) => println!("Mentioned in {}", mention.channel),
+//!     Event::Unknown(raw) => println!("Other event: {}", raw["type"]),
+//!     _ => {}
+//! }
+//! ```
+
+use crate::types::{Attachment, Edited, File, User};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// An Events API or RTM event, keyed by its `type`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Event {
+    /// A message, including edits, deletions and other subtypes
+    Message(Box<MessageEvent>),
+    /// The app was mentioned
+    AppMention(AppMentionEvent),
+    /// A reaction was added to an item
+    ReactionAdded(ReactionEvent),
+    /// A reaction was removed from an item
+    ReactionRemoved(ReactionEvent),
+    /// A user joined a channel
+    MemberJoinedChannel(MemberChannelEvent),
+    /// A user left a channel
+    MemberLeftChannel(MemberChannelEvent),
+    /// A channel was created
+    ChannelCreated(ChannelCreatedEvent),
+    /// A channel was renamed
+    #[serde(rename = "channel_rename")]
+    ChannelRenamed(ChannelRenamedEvent),
+    /// A channel was archived
+    ChannelArchive(ChannelEvent),
+    /// A channel was unarchived
+    ChannelUnarchive(ChannelEvent),
+    /// A channel was deleted
+    ChannelDeleted(ChannelEvent),
+    /// A new member joined the workspace
+    TeamJoin(UserEvent),
+    /// A member's profile or settings changed
+    UserChange(UserEvent),
+    /// A file was shared
+    FileShared(FileEvent),
+    /// A file was deleted
+    FileDeleted(FileEvent),
+    /// A message contains a link in one of the app's unfurl domains
+    LinkShared(LinkSharedEvent),
+    /// A user opened the app's Home, Messages or About tab
+    AppHomeOpened(AppHomeOpenedEvent),
+    /// An item was pinned to a channel
+    PinAdded(PinEvent),
+    /// An item was unpinned from a channel
+    PinRemoved(PinEvent),
+    /// The app was uninstalled from the workspace
+    AppUninstalled,
+    /// Tokens for the app were revoked
+    TokensRevoked(TokensRevokedEvent),
+    /// RTM: the connection is ready
+    Hello,
+    /// RTM: a user is typing
+    UserTyping(UserTypingEvent),
+    /// RTM: a user's presence changed
+    PresenceChange(PresenceChangeEvent),
+    /// RTM: the connecting user set their presence manually
+    ManualPresenceChange(ManualPresenceChangeEvent),
+    /// RTM: the server is about to close the connection; reconnect
+    Goodbye,
+    /// Any other event, as raw JSON
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+impl Event {
+    /// Parse an event, falling back to [`Event::Unknown`]
+    pub fn from_value(value: Value) -> Self {
+        Self::deserialize(&value).unwrap_or(Self::Unknown(value))
+    }
+}
+
+/// A `message` event
+///
+/// Plain messages have no `subtype`. For `message_changed` the new version
+/// is in `message` and the old one in `previous_message`; for
+/// `message_deleted` the deleted message's timestamp is `deleted_ts`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageEvent {
+    #[serde(default)]
+    pub subtype: Option<MessageSubtype>,
+    pub channel: Option<String>,
+    /// `channel`, `group`, `im` or `mpim`
+    pub channel_type: Option<String>,
+    pub user: Option<String>,
+    pub bot_id: Option<String>,
+    pub username: Option<String>,
+    pub text: Option<String>,
+    pub ts: Option<String>,
+    pub thread_ts: Option<String>,
+    pub event_ts: Option<String>,
+    pub team: Option<String>,
+    #[serde(default)]
+    pub hidden: bool,
+    pub edited: Option<Edited>,
+    pub message: Option<Box<MessageEvent>>,
+    pub previous_message: Option<Box<MessageEvent>>,
+    pub deleted_ts: Option<String>,
+    #[serde(default)]
+    pub files: Vec<File>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub blocks: Vec<Value>,
+}
+
+/// The `subtype` of a message event
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+#[non_exhaustive]
+pub enum MessageSubtype {
+    BotMessage,
+    MeMessage,
+    MessageChanged,
+    MessageDeleted,
+    MessageReplied,
+    ThreadBroadcast,
+    FileShare,
+    ChannelJoin,
+    ChannelLeave,
+    ChannelTopic,
+    ChannelPurpose,
+    ChannelName,
+    ChannelArchive,
+    ChannelUnarchive,
+    ChannelPostingPermissions,
+    GroupJoin,
+    GroupLeave,
+    GroupTopic,
+    GroupPurpose,
+    GroupName,
+    GroupArchive,
+    GroupUnarchive,
+    PinnedItem,
+    UnpinnedItem,
+    EkmAccessDenied,
+    ReminderAdd,
+    /// A subtype not listed here
+    Other(String),
+}
+
+impl MessageSubtype {
+    /// The subtype as Slack names it, e.g. `message_changed`
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::BotMessage => "bot_message",
+            Self::MeMessage => "me_message",
+            Self::MessageChanged => "message_changed",
+            Self::MessageDeleted => "message_deleted",
+            Self::MessageReplied => "message_replied",
+            Self::ThreadBroadcast => "thread_broadcast",
+            Self::FileShare => "file_share",
+            Self::ChannelJoin => "channel_join",
+            Self::ChannelLeave => "channel_leave",
+            Self::ChannelTopic => "channel_topic",
+            Self::ChannelPurpose => "channel_purpose",
+            Self::ChannelName => "channel_name",
+            Self::ChannelArchive => "channel_archive",
+            Self::ChannelUnarchive => "channel_unarchive",
+            Self::ChannelPostingPermissions => "channel_posting_permissions",
+            Self::GroupJoin => "group_join",
+            Self::GroupLeave => "group_leave",
+            Self::GroupTopic => "group_topic",
+            Self::GroupPurpose => "group_purpose",
+            Self::GroupName => "group_name",
+            Self::GroupArchive => "group_archive",
+            Self::GroupUnarchive => "group_unarchive",
+            Self::PinnedItem => "pinned_item",
+            Self::UnpinnedItem => "unpinned_item",
+            Self::EkmAccessDenied => "ekm_access_denied",
+            Self::ReminderAdd => "reminder_add",
+            Self::Other(other) => other,
+        }
+    }
+}
+
+impl From<String> for MessageSubtype {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "bot_message" => Self::BotMessage,
+            "me_message" => Self::MeMessage,
+            "message_changed" => Self::MessageChanged,
+            "message_deleted" => Self::MessageDeleted,
+            "message_replied" => Self::MessageReplied,
+            "thread_broadcast" => Self::ThreadBroadcast,
+            "file_share" => Self::FileShare,
+            "channel_join" => Self::ChannelJoin,
+            "channel_leave" => Self::ChannelLeave,
+            "channel_topic" => Self::ChannelTopic,
+            "channel_purpose" => Self::ChannelPurpose,
+            "channel_name" => Self::ChannelName,
+            "channel_archive" => Self::ChannelArchive,
+            "channel_unarchive" => Self::ChannelUnarchive,
+            "channel_posting_permissions" => Self::ChannelPostingPermissions,
+            "group_join" => Self::GroupJoin,
+            "group_leave" => Self::GroupLeave,
+            "group_topic" => Self::GroupTopic,
+            "group_purpose" => Self::GroupPurpose,
+            "group_name" => Self::GroupName,
+            "group_archive" => Self::GroupArchive,
+            "group_unarchive" => Self::GroupUnarchive,
+            "pinned_item" => Self::PinnedItem,
+            "unpinned_item" => Self::UnpinnedItem,
+            "ekm_access_denied" => Self::EkmAccessDenied,
+            "reminder_add" => Self::ReminderAdd,
+            _ => Self::Other(s),
+        }
+    }
+}
+
+/// An `app_mention` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppMentionEvent {
+    pub user: Option<String>,
+    pub bot_id: Option<String>,
+    #[serde(default)]
+    pub text: String,
+    pub ts: String,
+    pub channel: String,
+    pub thread_ts: Option<String>,
+    pub event_ts: Option<String>,
+    pub team: Option<String>,
+    #[serde(default)]
+    pub blocks: Vec<Value>,
+}
+
+/// A `reaction_added` or `reaction_removed` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReactionEvent {
+    /// User who reacted
+    pub user: String,
+    /// Emoji name, without colons
+    pub reaction: String,
+    /// Author of the item reacted to
+    pub item_user: Option<String>,
+    pub item: ReactionItem,
+    pub event_ts: Option<String>,
+}
+
+/// What a reaction was added to or removed from
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReactionItem {
+    /// `message`, `file` or `file_comment`
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub channel: Option<String>,
+    pub ts: Option<String>,
+    pub file: Option<String>,
+    pub file_comment: Option<String>,
+}
+
+/// A `member_joined_channel` or `member_left_channel` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct MemberChannelEvent {
+    pub user: String,
+    pub channel: String,
+    /// `C` for public channels, `G` for private ones
+    pub channel_type: Option<String>,
+    pub team: Option<String>,
+    /// Who added the user, if they were invited
+    pub inviter: Option<String>,
+    pub event_ts: Option<String>,
+}
+
+/// A `channel_created` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelCreatedEvent {
+    pub channel: ChannelInfo,
+}
+
+/// A `channel_rename` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelRenamedEvent {
+    pub channel: ChannelInfo,
+    pub event_ts: Option<String>,
+}
+
+/// Channel details in channel events
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub created: Option<i64>,
+    pub creator: Option<String>,
+}
+
+/// A `channel_archive`, `channel_unarchive` or `channel_deleted` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelEvent {
+    pub channel: String,
+    /// Who made the change, when Slack says
+    pub user: Option<String>,
+    pub event_ts: Option<String>,
+}
+
+/// A `team_join` or `user_change` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserEvent {
+    pub user: User,
+    pub event_ts: Option<String>,
+}
+
+/// A `file_shared` or `file_deleted` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileEvent {
+    pub file_id: String,
+    pub user_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub event_ts: Option<String>,
+}
+
+/// A `link_shared` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkSharedEvent {
+    pub channel: String,
+    pub user: String,
+    pub message_ts: String,
+    pub thread_ts: Option<String>,
+    #[serde(default)]
+    pub links: Vec<SharedLink>,
+    /// ID to pass to `chat.unfurl` instead of `channel` and `ts`
+    pub unfurl_id: Option<String>,
+    /// `conversations_history` or `composer`
+    pub source: Option<String>,
+    pub event_ts: Option<String>,
+}
+
+/// A link in a [`LinkSharedEvent`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedLink {
+    pub domain: String,
+    pub url: String,
+}
+
+/// An `app_home_opened` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppHomeOpenedEvent {
+    pub user: String,
+    pub channel: String,
+    /// `home`, `messages` or `about`
+    pub tab: String,
+    /// The Home tab view, if one was published
+    pub view: Option<Value>,
+    pub event_ts: Option<String>,
+}
+
+/// A `pin_added` or `pin_removed` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct PinEvent {
+    pub user: String,
+    pub channel_id: String,
+    pub item: Option<Value>,
+    pub event_ts: Option<String>,
+}
+
+/// A `tokens_revoked` event
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokensRevokedEvent {
+    pub tokens: RevokedTokens,
+}
+
+/// User IDs whose tokens were revoked, by token type
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RevokedTokens {
+    #[serde(default)]
+    pub oauth: Vec<String>,
+    #[serde(default)]
+    pub bot: Vec<String>,
+}
+
+/// A `user_typing` RTM event
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserTypingEvent {
+    pub channel: String,
+    pub user: String,
+}
+
+/// A `presence_change` RTM event
+#[derive(Debug, Clone, Deserialize)]
+pub struct PresenceChangeEvent {
+    /// Set for a single user
+    pub user: Option<String>,
+    /// Set for batched presence changes
+    #[serde(default)]
+    pub users: Vec<String>,
+    /// `active` or `away`
+    pub presence: String,
+}
+
+/// A `manual_presence_change` RTM event
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManualPresenceChangeEvent {
+    /// `active` or `away`
+    pub presence: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_events() {
+        let event = Event::from_value(json!({
+            "type": "message",
+            "subtype": "message_changed",
+            "channel": "C1",
+            "hidden": true,
+            "message": { "type": "message", "user": "U1", "text": "new", "ts": "1.0" },
+            "previous_message": { "type": "message", "user": "U1", "text": "old", "ts": "1.0" },
+            "event_ts": "2.0",
+        }));
+        let Event::Message(message) = event else {
+            panic!("expected a message, got {:?}", event);
+        };
+        assert_eq!(message.subtype, Some(MessageSubtype::MessageChanged));
+        assert_eq!(message.message.unwrap().text.as_deref(), Some("new"));
+        assert_eq!(
+            message.previous_message.unwrap().text.as_deref(),
+            Some("old")
+        );
+
+        let event = Event::from_value(json!({
+            "type": "reaction_added",
+            "user": "U1",
+            "reaction": "thumbsup",
+            "item_user": "U2",
+            "item": { "type": "message", "channel": "C1", "ts": "1.0" },
+            "event_ts": "2.0",
+        }));
+        assert!(
+            matches!(&event, Event::ReactionAdded(r) if r.item.channel.as_deref() == Some("C1")),
+            "{:?}",
+            event
+        );
+
+        let event: Event = serde_json::from_str(r#"{"type": "hello", "start": true}"#).unwrap();
+        assert!(matches!(event, Event::Hello));
+        let event: Event = serde_json::from_str(r#"{"type": "goodbye", "source": "gs"}"#).unwrap();
+        assert!(matches!(event, Event::Goodbye));
+
+        let event: Event = serde_json::from_value(json!({
+            "type": "message",
+            "subtype": "huddle_thread",
+            "channel": "C1",
+        }))
+        .unwrap();
+        assert!(matches!(
+            &event,
+            Event::Message(message)
+                if message.subtype == Some(MessageSubtype::Other("huddle_thread".into()))
+        ));
+
+        // Unknown events, and known ones with an unexpected shape, are kept raw
+        let event = Event::from_value(json!({ "type": "function_executed", "function": {} }));
+        assert!(matches!(&event, Event::Unknown(raw) if raw["type"] == "function_executed"));
+        let event: Event = serde_json::from_value(json!({ "type": "app_mention" })).unwrap();
+        assert!(matches!(event, Event::Unknown(_)));
+    }
+}
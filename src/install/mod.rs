@@ -0,0 +1,578 @@
+//! OAuth v2 install flow for distributed apps
+//!
+//! Enabled with the `install` cargo feature. [`InstallFlow`] runs the "Add to
+//! Slack" flow: it builds authorize URLs with a one-time `state`, which is also
+//! set in a cookie so the redirect is only accepted from the browser that
+//! started the install (CSRF protection), exchanges the code from the redirect
+//! with `oauth.v2.access`, and saves the result to an [`InstallationStore`]. Afterwards,
+//! [`InstallFlow::client_for`] gives a client for any installed workspace or
+//! organization.
+//!
+//! [`InstallFlow::serve`] runs a small HTTP server that handles both ends of
+//! the flow:
+//!
+//! - `GET /slack/install` sets the [`STATE_COOKIE`] and redirects to Slack's
+//!   authorize page
+//! - `GET /slack/oauth_redirect` (or the path of the redirect URI) completes
+//!   the install
+//!
+//! To use your own web framework instead, call [`InstallFlow::authorize`] and
+//! [`InstallFlow::complete`] from your handlers, setting the cookie from
+//! [`InstallFlow::state_cookie`] on the redirect and passing its value back.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use slacko::install::{FileInstallationStore, InstallFlow};
+//!
+//! # async fn example() -> slacko::Result<()> {
+//! let flow = InstallFlow::new(
+//!     "client-id",
+//!     "client-secret",
+//!     FileInstallationStore::new("installations"),
+//! )
+//! .scopes(["chat:write", "channels:read"])
+//! .redirect_uri("https://example.com/slack/oauth_redirect");
+//!
+//! let server = flow.serve("0.0.0.0:3000").await?;
+//! println!("Install at http://{}/slack/install", server.local_addr());
+//!
+//! // Later, for an event from team T123
+//! let client = flow.client_for(None, Some("T123")).await?;
+//! client.chat().post_message("#general", "Thanks for installing!").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod store;
+
+pub use store::{FileInstallationStore, Installation, InstallationStore, MemoryInstallationStore};
+
+use crate::auth::AuthConfig;
+use crate::client::{SlackClient, SLACK_API_BASE};
+use crate::error::{Result, SlackError};
+use crate::rotation::{RotatedTokens, TokenRotation};
+use crate::server;
+use futures_util::future::BoxFuture;
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use rand::distributions::{Alphanumeric, DistString};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+/// Slack's authorize page
+const AUTHORIZE_URL: &str = "https://slack.com/oauth/v2/authorize";
+
+/// Path that starts an install
+const INSTALL_PATH: &str = "/slack/install";
+
+/// Redirect path used when no redirect URI is set
+const DEFAULT_REDIRECT_PATH: &str = "/slack/oauth_redirect";
+
+/// Default lifetime of an issued `state`
+const DEFAULT_STATE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Cookie that ties a `state` to the browser that started the install
+pub const STATE_COOKIE: &str = "slack_oauth_state";
+
+/// The "Add to Slack" OAuth flow
+///
+/// Clones share the issued states and the store.
+#[derive(Clone)]
+pub struct InstallFlow {
+    client_id: String,
+    client_secret: String,
+    scopes: Vec<String>,
+    user_scopes: Vec<String>,
+    redirect_uri: Option<String>,
+    state_ttl: Duration,
+    api_url: String,
+    states: Arc<Mutex<HashMap<String, Instant>>>,
+    store: Arc<dyn InstallationStore>,
+}
+
+impl std::fmt::Debug for InstallFlow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstallFlow")
+            .field("client_id", &self.client_id)
+            .field("scopes", &self.scopes)
+            .field("user_scopes", &self.user_scopes)
+            .field("redirect_uri", &self.redirect_uri)
+            .finish_non_exhaustive()
+    }
+}
+
+impl InstallFlow {
+    /// Create a flow for the app with the given credentials
+    pub fn new(
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+        store: impl InstallationStore + 'static,
+    ) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            scopes: Vec::new(),
+            user_scopes: Vec::new(),
+            redirect_uri: None,
+            state_ttl: DEFAULT_STATE_TTL,
+            api_url: SLACK_API_BASE.to_string(),
+            states: Arc::new(Mutex::new(HashMap::new())),
+            store: Arc::new(store),
+        }
+    }
+
+    /// Bot scopes to request
+    pub fn scopes<I, S>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// User scopes to request
+    pub fn user_scopes<I, S>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.user_scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Redirect URI; must match one configured for the app
+    ///
+    /// Without one, Slack uses the app's first configured redirect URL.
+    pub fn redirect_uri(mut self, redirect_uri: impl Into<String>) -> Self {
+        self.redirect_uri = Some(redirect_uri.into());
+        self
+    }
+
+    /// How long an issued `state` stays valid (default: 10 minutes)
+    pub fn state_ttl(mut self, state_ttl: Duration) -> Self {
+        self.state_ttl = state_ttl;
+        self
+    }
+
+    /// Web API base URL, e.g. for a mock server (default: `https://slack.com/api`)
+    pub fn api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = api_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// The store installations are saved to
+    pub fn store(&self) -> &Arc<dyn InstallationStore> {
+        &self.store
+    }
+
+    /// Issue a new `state` and build the URL to send the installing user to
+    ///
+    /// Set the cookie from [`state_cookie`](Self::state_cookie) on the same
+    /// response that redirects to the URL.
+    pub fn authorize(&self) -> InstallRedirect {
+        let state = self.issue_state();
+
+        let mut url = url::Url::parse(AUTHORIZE_URL).expect("authorize URL is valid");
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("client_id", &self.client_id);
+            query.append_pair("scope", &self.scopes.join(","));
+            if !self.user_scopes.is_empty() {
+                query.append_pair("user_scope", &self.user_scopes.join(","));
+            }
+            if let Some(redirect_uri) = &self.redirect_uri {
+                query.append_pair("redirect_uri", redirect_uri);
+            }
+            query.append_pair("state", &state);
+        }
+        InstallRedirect {
+            url: url.into(),
+            state,
+        }
+    }
+
+    /// `Set-Cookie` value that stores `state` in the installing browser
+    ///
+    /// The cookie is sent back only to the redirect path and expires with the
+    /// state.
+    pub fn state_cookie(&self, state: &str) -> String {
+        format!(
+            "{}={}; Max-Age={}; {}",
+            STATE_COOKIE,
+            state,
+            self.state_ttl.as_secs(),
+            self.cookie_attributes()
+        )
+    }
+
+    /// `Set-Cookie` value that removes the state cookie after the redirect
+    pub fn clear_state_cookie(&self) -> String {
+        format!("{}=; Max-Age=0; {}", STATE_COOKIE, self.cookie_attributes())
+    }
+
+    fn cookie_attributes(&self) -> String {
+        // Lax, so the cookie comes along on the top-level redirect from Slack
+        let mut attributes = format!("Path={}; HttpOnly; SameSite=Lax", self.redirect_path());
+        if self
+            .redirect_uri
+            .as_deref()
+            .is_some_and(|uri| uri.starts_with("https://"))
+        {
+            attributes.push_str("; Secure");
+        }
+        attributes
+    }
+
+    /// Path of the redirect URI, or the default one
+    fn redirect_path(&self) -> String {
+        self.redirect_uri
+            .as_deref()
+            .and_then(|uri| url::Url::parse(uri).ok())
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|| DEFAULT_REDIRECT_PATH.to_string())
+    }
+
+    fn issue_state(&self) -> String {
+        let state = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+        let now = Instant::now();
+
+        let mut states = self.states.lock().unwrap();
+        states.retain(|_, expires_at| *expires_at > now);
+        states.insert(state.clone(), now + self.state_ttl);
+        state
+    }
+
+    /// Check a `state` from the redirect; each state can only be used once
+    fn consume_state(&self, state: &str) -> bool {
+        self.states
+            .lock()
+            .unwrap()
+            .remove(state)
+            .is_some_and(|expires_at| expires_at > Instant::now())
+    }
+
+    /// Complete an install with the `code` and `state` from the redirect
+    ///
+    /// `cookie_state` is the value of the [`STATE_COOKIE`] the browser sent
+    /// with the redirect. Checks that it matches the state, that the state
+    /// was issued here, exchanges the code for tokens and saves the
+    /// installation. Fails with [`SlackError::InvalidParameter`] for a
+    /// missing or mismatched cookie, or an unknown, expired or reused state.
+    pub async fn complete(
+        &self,
+        code: &str,
+        state: &str,
+        cookie_state: Option<&str>,
+    ) -> Result<Installation> {
+        if cookie_state != Some(state) {
+            return Err(SlackError::InvalidParameter(
+                "OAuth state was not issued to this browser".to_string(),
+            ));
+        }
+        if !self.consume_state(state) {
+            return Err(SlackError::InvalidParameter(
+                "Invalid or expired OAuth state".to_string(),
+            ));
+        }
+
+        let response = self
+            .api_client(AuthConfig::bot(""))?
+            .oauth()
+            .access(
+                code,
+                &self.client_id,
+                &self.client_secret,
+                self.redirect_uri.as_deref(),
+            )
+            .await?;
+
+        let installation = Installation::from(response);
+        self.store.save(&installation).await?;
+        tracing::info!(key = %installation.key(), "Saved installation");
+
+        Ok(installation)
+    }
+
+    /// Create a client for an installed team or enterprise
+    ///
+    /// Uses the bot token, or the user token for installs without one. When
+    /// the token rotates, the client refreshes it and saves the new tokens
+    /// to the store. Fails with [`SlackError::NotFound`] if nothing is
+    /// installed for the team.
+    pub async fn client_for(
+        &self,
+        enterprise_id: Option<&str>,
+        team_id: Option<&str>,
+    ) -> Result<SlackClient> {
+        let installation = self
+            .store
+            .find(enterprise_id, team_id)
+            .await?
+            .ok_or_else(|| SlackError::NotFound {
+                message: format!(
+                    "No installation for enterprise {:?}, team {:?}",
+                    enterprise_id, team_id
+                ),
+                method: None,
+                code: None,
+            })?;
+
+        let (auth, refresh_token, expires_at) = match &installation.bot_token {
+            Some(token) => (
+                AuthConfig::bot(token),
+                installation.bot_refresh_token.clone(),
+                installation.bot_token_expires_at,
+            ),
+            None => {
+                let token = installation.user_token.as_deref().ok_or_else(|| {
+                    SlackError::config_error(format!(
+                        "Installation {} has no tokens",
+                        installation.key()
+                    ))
+                })?;
+                (
+                    AuthConfig::oauth(token),
+                    installation.user_refresh_token.clone(),
+                    installation.user_token_expires_at,
+                )
+            }
+        };
+
+        let mut builder = SlackClient::builder(auth).base_url(&self.api_url);
+        if let Some(refresh_token) = refresh_token {
+            let mut rotation =
+                TokenRotation::new(&self.client_id, &self.client_secret, refresh_token)
+                    .on_refresh_async(self.save_rotated(installation));
+            if let Some(expires_at) = expires_at {
+                rotation = rotation.expires_at(expires_at.into());
+            }
+            builder = builder.token_rotation(rotation);
+        }
+
+        builder.build()
+    }
+
+    /// Callback that saves rotated tokens back to the store
+    ///
+    /// The rotation awaits each save before the next refresh, so the store
+    /// always ends up with the latest token pair.
+    fn save_rotated(
+        &self,
+        installation: Installation,
+    ) -> impl Fn(RotatedTokens) -> BoxFuture<'static, Result<()>> + Send + Sync + 'static {
+        let store = self.store.clone();
+        let installation = Arc::new(Mutex::new(installation));
+
+        move |tokens| {
+            let installation = {
+                let mut installation = installation.lock().unwrap();
+                let expires_at = tokens.expires_at.map(Into::into);
+                if installation.bot_token.is_some() {
+                    installation.bot_token = Some(tokens.access_token);
+                    installation.bot_refresh_token = Some(tokens.refresh_token);
+                    installation.bot_token_expires_at = expires_at;
+                } else {
+                    installation.user_token = Some(tokens.access_token);
+                    installation.user_refresh_token = Some(tokens.refresh_token);
+                    installation.user_token_expires_at = expires_at;
+                }
+                installation.clone()
+            };
+
+            let store = store.clone();
+            Box::pin(async move {
+                store.save(&installation).await.inspect_err(|e| {
+                    tracing::warn!(key = %installation.key(), "Failed to save rotated tokens: {}", e);
+                })
+            })
+        }
+    }
+
+    fn api_client(&self, auth: AuthConfig) -> Result<SlackClient> {
+        SlackClient::builder(auth).base_url(&self.api_url).build()
+    }
+
+    /// Serve the install and redirect endpoints on `addr`
+    ///
+    /// The server runs on a background task until the returned
+    /// [`InstallServer`] is dropped.
+    pub async fn serve(&self, addr: impl ToSocketAddrs) -> Result<InstallServer> {
+        let listener = TcpListener::bind(addr).await.map_err(|e| {
+            SlackError::config_error(format!("Failed to bind install server: {}", e))
+        })?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| SlackError::config_error(e.to_string()))?;
+
+        let redirect_path = self.redirect_path();
+
+        let flow = Arc::new(self.clone());
+        let redirect_path: Arc<str> = redirect_path.into();
+        let server = tokio::spawn(async move {
+            loop {
+                let stream = server::accept(&listener).await;
+
+                let flow = flow.clone();
+                let redirect_path = redirect_path.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |request| {
+                        let flow = flow.clone();
+                        let redirect_path = redirect_path.clone();
+                        async move { handle(request, &flow, &redirect_path).await }
+                    });
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        Ok(InstallServer { local_addr, server })
+    }
+}
+
+/// An authorize URL and the `state` it carries
+#[derive(Debug, Clone)]
+pub struct InstallRedirect {
+    pub url: String,
+    pub state: String,
+}
+
+/// A running install server, stopped when dropped
+#[derive(Debug)]
+pub struct InstallServer {
+    local_addr: SocketAddr,
+    server: JoinHandle<()>,
+}
+
+impl InstallServer {
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for InstallServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    flow: &InstallFlow,
+    redirect_path: &str,
+) -> std::result::Result<Response<Full<Bytes>>, Infallible> {
+    if request.method() != hyper::Method::GET {
+        return Ok(html(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"));
+    }
+
+    let path = request.uri().path();
+    let response = if path == INSTALL_PATH {
+        let redirect = flow.authorize();
+        Response::builder()
+            .status(StatusCode::FOUND)
+            .header(hyper::header::LOCATION, redirect.url)
+            .header(
+                hyper::header::SET_COOKIE,
+                flow.state_cookie(&redirect.state),
+            )
+            .body(Full::new(Bytes::new()))
+            .expect("redirect response is valid")
+    } else if path == redirect_path {
+        let cookie_state = cookie(request.headers(), STATE_COOKIE);
+        let query: HashMap<String, String> = request
+            .uri()
+            .query()
+            .map(|q| {
+                url::form_urlencoded::parse(q.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut response = redirect(flow, &query, cookie_state.as_deref()).await;
+        if let Ok(clear) = flow.clear_state_cookie().parse() {
+            response
+                .headers_mut()
+                .insert(hyper::header::SET_COOKIE, clear);
+        }
+        response
+    } else {
+        html(StatusCode::NOT_FOUND, "Not found")
+    };
+
+    Ok(response)
+}
+
+/// Value of the cookie `name` in a request's `Cookie` headers
+fn cookie(headers: &hyper::HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(hyper::header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Handle the redirect back from Slack
+async fn redirect(
+    flow: &InstallFlow,
+    query: &HashMap<String, String>,
+    cookie_state: Option<&str>,
+) -> Response<Full<Bytes>> {
+    if let Some(error) = query.get("error") {
+        return html(
+            StatusCode::BAD_REQUEST,
+            &format!("Installation cancelled: {}", error),
+        );
+    }
+    let (Some(code), Some(state)) = (query.get("code"), query.get("state")) else {
+        return html(StatusCode::BAD_REQUEST, "Missing code or state");
+    };
+
+    match flow.complete(code, state, cookie_state).await {
+        Ok(installation) => {
+            let name = installation
+                .team_name
+                .or(installation.enterprise_name)
+                .unwrap_or_default();
+            html(
+                StatusCode::OK,
+                &format!("Installed to {}. You can close this window.", name),
+            )
+        }
+        Err(SlackError::InvalidParameter(message)) => html(StatusCode::BAD_REQUEST, &message),
+        Err(e) => {
+            tracing::warn!("Installation failed: {}", e);
+            html(StatusCode::INTERNAL_SERVER_ERROR, "Installation failed")
+        }
+    }
+}
+
+fn html(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
+    let escaped = message
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .body(Full::new(Bytes::from(format!(
+            "<!DOCTYPE html><html><body><p>{}</p></body></html>",
+            escaped
+        ))))
+        .expect("HTML response is valid")
+}
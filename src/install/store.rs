@@ -0,0 +1,365 @@
+//! Installation storage
+
+use crate::api::oauth::OAuthAccessResponse;
+use crate::error::{Result, SlackError};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use rand::distributions::{Alphanumeric, DistString};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::io::AsyncWriteExt;
+
+/// Tokens and metadata from one app installation
+///
+/// A workspace install has a `team_id`; an org-wide Enterprise Grid install
+/// has an `enterprise_id` and no `team_id`, and covers every workspace in the
+/// organization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Installation {
+    pub app_id: String,
+    pub enterprise_id: Option<String>,
+    pub enterprise_name: Option<String>,
+    pub team_id: Option<String>,
+    pub team_name: Option<String>,
+    #[serde(default)]
+    pub is_enterprise_install: bool,
+    /// Bot token, if bot scopes were requested
+    pub bot_token: Option<String>,
+    pub bot_user_id: Option<String>,
+    #[serde(default)]
+    pub bot_scopes: Vec<String>,
+    /// Refresh token for `bot_token`, when token rotation is enabled
+    pub bot_refresh_token: Option<String>,
+    pub bot_token_expires_at: Option<DateTime<Utc>>,
+    /// The user who installed the app
+    pub user_id: Option<String>,
+    /// User token, if user scopes were requested
+    pub user_token: Option<String>,
+    #[serde(default)]
+    pub user_scopes: Vec<String>,
+    /// Refresh token for `user_token`, when token rotation is enabled
+    pub user_refresh_token: Option<String>,
+    pub user_token_expires_at: Option<DateTime<Utc>>,
+    pub installed_at: DateTime<Utc>,
+}
+
+impl Installation {
+    /// Key identifying this installation in a store
+    pub fn key(&self) -> String {
+        let team_id = if self.is_enterprise_install {
+            None
+        } else {
+            self.team_id.as_deref()
+        };
+        installation_key(self.enterprise_id.as_deref(), team_id)
+    }
+}
+
+impl From<OAuthAccessResponse> for Installation {
+    fn from(response: OAuthAccessResponse) -> Self {
+        let now = Utc::now();
+        let expires_at = |secs: Option<u64>| {
+            secs.and_then(|secs| i64::try_from(secs).ok())
+                .map(|secs| now + Duration::seconds(secs))
+        };
+        let scopes = |scope: &str| {
+            scope
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let user = response.authed_user.as_ref();
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+        Self {
+            app_id: response.app_id.clone(),
+            enterprise_id: response.enterprise.as_ref().map(|e| e.id.clone()),
+            enterprise_name: response.enterprise.as_ref().map(|e| e.name.clone()),
+            team_id: non_empty(&response.team.id),
+            team_name: non_empty(&response.team.name),
+            is_enterprise_install: response.is_enterprise_install.unwrap_or(false),
+            bot_token: non_empty(&response.access_token),
+            bot_user_id: response.bot_user_id.clone(),
+            bot_scopes: scopes(&response.scope),
+            bot_refresh_token: response.refresh_token.clone(),
+            bot_token_expires_at: expires_at(response.expires_in),
+            user_id: user.map(|u| u.id.clone()),
+            user_token: user.and_then(|u| u.access_token.clone()),
+            user_scopes: user
+                .and_then(|u| u.scope.as_deref())
+                .map(scopes)
+                .unwrap_or_default(),
+            user_refresh_token: user.and_then(|u| u.refresh_token.clone()),
+            user_token_expires_at: expires_at(user.and_then(|u| u.expires_in)),
+            installed_at: now,
+        }
+    }
+}
+
+/// Key for an installation, e.g. `none-T123` or `E123-none`
+fn installation_key(enterprise_id: Option<&str>, team_id: Option<&str>) -> String {
+    format!(
+        "{}-{}",
+        enterprise_id.unwrap_or("none"),
+        team_id.unwrap_or("none")
+    )
+}
+
+/// Keys to try when looking up a team, most specific first
+///
+/// A workspace in an organization with an org-wide install is served by the
+/// enterprise's installation.
+fn lookup_keys(enterprise_id: Option<&str>, team_id: Option<&str>) -> Vec<String> {
+    let mut keys = vec![installation_key(enterprise_id, team_id)];
+    if enterprise_id.is_some() && team_id.is_some() {
+        keys.push(installation_key(enterprise_id, None));
+    }
+    keys
+}
+
+/// Where installations are kept
+///
+/// Implement this to keep installations in a database; [`MemoryInstallationStore`]
+/// and [`FileInstallationStore`] are provided.
+#[async_trait]
+pub trait InstallationStore: Send + Sync {
+    /// Save an installation, replacing any previous one for the same team
+    async fn save(&self, installation: &Installation) -> Result<()>;
+
+    /// Find the installation for a team or enterprise
+    ///
+    /// For a team in an enterprise, falls back to the org-wide installation.
+    async fn find(
+        &self,
+        enterprise_id: Option<&str>,
+        team_id: Option<&str>,
+    ) -> Result<Option<Installation>>;
+
+    /// Delete the installation for a team or enterprise, e.g. after `app_uninstalled`
+    async fn delete(&self, enterprise_id: Option<&str>, team_id: Option<&str>) -> Result<()>;
+}
+
+/// Installation store that lives in memory
+#[derive(Debug, Default)]
+pub struct MemoryInstallationStore {
+    installations: Mutex<HashMap<String, Installation>>,
+}
+
+impl MemoryInstallationStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl InstallationStore for MemoryInstallationStore {
+    async fn save(&self, installation: &Installation) -> Result<()> {
+        self.installations
+            .lock()
+            .unwrap()
+            .insert(installation.key(), installation.clone());
+        Ok(())
+    }
+
+    async fn find(
+        &self,
+        enterprise_id: Option<&str>,
+        team_id: Option<&str>,
+    ) -> Result<Option<Installation>> {
+        let installations = self.installations.lock().unwrap();
+        Ok(lookup_keys(enterprise_id, team_id)
+            .iter()
+            .find_map(|key| installations.get(key).cloned()))
+    }
+
+    async fn delete(&self, enterprise_id: Option<&str>, team_id: Option<&str>) -> Result<()> {
+        self.installations
+            .lock()
+            .unwrap()
+            .remove(&installation_key(enterprise_id, team_id));
+        Ok(())
+    }
+}
+
+/// Installation store with one JSON file per installation in a directory
+///
+/// The files contain tokens, so they are created readable only by their
+/// owner. IDs that aren't Slack IDs (uppercase letters and digits) are
+/// rejected with [`SlackError::InvalidParameter`].
+#[derive(Debug, Clone)]
+pub struct FileInstallationStore {
+    dir: PathBuf,
+}
+
+impl FileInstallationStore {
+    /// Keep installations in `dir`, which is created on the first save
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+/// Check that IDs only use Slack's ID alphabet, since they end up in file names
+fn check_ids(enterprise_id: Option<&str>, team_id: Option<&str>) -> Result<()> {
+    for id in [enterprise_id, team_id].into_iter().flatten() {
+        let valid = !id.is_empty()
+            && id
+                .bytes()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+        if !valid {
+            return Err(SlackError::InvalidParameter(format!(
+                "Invalid Slack ID {:?}",
+                id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Create a new file readable only by its owner, since it holds tokens
+async fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path).await?;
+    file.write_all(contents).await?;
+    file.sync_all().await
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> SlackError {
+    SlackError::Other(format!("Cannot access {}: {}", path.display(), e))
+}
+
+#[async_trait]
+impl InstallationStore for FileInstallationStore {
+    async fn save(&self, installation: &Installation) -> Result<()> {
+        check_ids(
+            installation.enterprise_id.as_deref(),
+            installation.team_id.as_deref(),
+        )?;
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| io_error(&self.dir, e))?;
+
+        // Write and rename so readers never see a partial file; each save
+        // has its own temporary file, so concurrent saves can't interleave
+        let path = self.path(&installation.key());
+        let suffix = Alphanumeric.sample_string(&mut rand::thread_rng(), 12);
+        let tmp = path.with_extension(format!("json.{}.tmp", suffix));
+        let contents = serde_json::to_vec_pretty(installation)?;
+        if let Err(e) = write_private(&tmp, &contents).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(io_error(&tmp, e));
+        }
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| io_error(&path, e))
+    }
+
+    async fn find(
+        &self,
+        enterprise_id: Option<&str>,
+        team_id: Option<&str>,
+    ) -> Result<Option<Installation>> {
+        check_ids(enterprise_id, team_id)?;
+        for key in lookup_keys(enterprise_id, team_id) {
+            let path = self.path(&key);
+            match tokio::fs::read(&path).await {
+                Ok(contents) => return Ok(Some(serde_json::from_slice(&contents)?)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(io_error(&path, e)),
+            }
+        }
+        Ok(None)
+    }
+
+    async fn delete(&self, enterprise_id: Option<&str>, team_id: Option<&str>) -> Result<()> {
+        check_ids(enterprise_id, team_id)?;
+        let path = self.path(&installation_key(enterprise_id, team_id));
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(&path, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installation(enterprise_id: Option<&str>, team_id: Option<&str>) -> Installation {
+        let response: OAuthAccessResponse = serde_json::from_value(serde_json::json!({
+            "ok": true,
+            "access_token": "xoxb-1",
+            "token_type": "bot",
+            "scope": "chat:write,channels:read",
+            "app_id": "A1",
+            "team": team_id.map(|id| serde_json::json!({ "id": id, "name": "Team" })),
+            "enterprise": enterprise_id.map(|id| serde_json::json!({ "id": id, "name": "Org" })),
+            "is_enterprise_install": team_id.is_none(),
+            "authed_user": { "id": "U1" },
+        }))
+        .unwrap();
+        Installation::from(response)
+    }
+
+    #[tokio::test]
+    async fn test_file_store() {
+        let dir = std::env::temp_dir().join(format!("slacko-install-{}", std::process::id()));
+        let store = FileInstallationStore::new(&dir);
+
+        let team = installation(None, Some("T1"));
+        assert_eq!(team.key(), "none-T1");
+        assert_eq!(team.bot_scopes, ["chat:write", "channels:read"]);
+        store.save(&team).await.unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.join("none-T1.json"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Concurrent saves of one installation each leave a complete file
+        let saves: Vec<_> = (0..8).map(|_| store.save(&team)).collect();
+        for result in futures_util::future::join_all(saves).await {
+            result.unwrap();
+        }
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let found = store.find(None, Some("T1")).await.unwrap().unwrap();
+        assert_eq!(found.bot_token.as_deref(), Some("xoxb-1"));
+        assert_eq!(found.user_id.as_deref(), Some("U1"));
+        assert!(store.find(None, Some("T2")).await.unwrap().is_none());
+
+        // A workspace in the organization falls back to the org-wide install
+        let org = installation(Some("E1"), None);
+        assert_eq!(org.key(), "E1-none");
+        store.save(&org).await.unwrap();
+        let found = store.find(Some("E1"), Some("T9")).await.unwrap().unwrap();
+        assert!(found.is_enterprise_install);
+
+        store.delete(None, Some("T1")).await.unwrap();
+        store.delete(None, Some("T1")).await.unwrap();
+        assert!(store.find(None, Some("T1")).await.unwrap().is_none());
+
+        // IDs that could point outside the directory are rejected
+        let err = store.find(None, Some("../../x")).await.unwrap_err();
+        assert!(matches!(err, SlackError::InvalidParameter(_)));
+        assert!(store.delete(Some("E1/.."), None).await.is_err());
+        assert!(store.save(&installation(None, Some("t1"))).await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
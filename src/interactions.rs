@@ -0,0 +1,560 @@
+//! Typed interactive payloads
+//!
+//! [`InteractivePayload`] covers what Slack sends when a user clicks a
+//! button, submits or closes a modal, runs a shortcut or types into an
+//! external select. Payloads of an unknown `type`, or whose shape does not
+//! match, are kept as [`InteractivePayload::Unknown`] with the raw JSON.
+//!
+//! ```
+//! use slacko::interactions::{ElementValue, InteractivePayload};
+//!
+//! let payload = InteractivePayload::from_value(serde_json::json!({
+//!     "type": "block_actions",
+//!     "user": { "id": "U1" },
+//!     "actions": [{ "type": "button", "action_id": "approve", "value": "42" }],
+//! }));
+//!
+//! if let InteractivePayload::BlockActions(payload) = payload {
+//!     for action in &payload.actions {
+//!         if let ElementValue::Button { value, .. } = &action.value {
+//!             println!("{} clicked {} ({:?})", payload.user.id, action.action_id, value);
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::blocks::OptionObject;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// An interactive payload, keyed by its `type`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum InteractivePayload {
+    /// A user interacted with a Block Kit element in a message, modal or App Home
+    BlockActions(Box<BlockActionsPayload>),
+    /// A user submitted a modal
+    ViewSubmission(Box<ViewSubmissionPayload>),
+    /// A user closed a modal that has `notify_on_close` set
+    ViewClosed(Box<ViewClosedPayload>),
+    /// A global shortcut was run
+    Shortcut(ShortcutPayload),
+    /// A message shortcut was run
+    MessageAction(Box<MessageActionPayload>),
+    /// An external select menu needs options
+    BlockSuggestion(Box<BlockSuggestionPayload>),
+    /// A legacy attachment action (button or menu) was used
+    InteractiveMessage(Box<InteractiveMessagePayload>),
+    /// Any other payload, as raw JSON
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+impl InteractivePayload {
+    /// Parse a payload, falling back to [`InteractivePayload::Unknown`]
+    pub fn from_value(value: Value) -> Self {
+        Self::deserialize(&value).unwrap_or(Self::Unknown(value))
+    }
+
+    /// The payload's `type`, e.g. `block_actions`
+    pub fn interaction_type(&self) -> &str {
+        match self {
+            Self::BlockActions(_) => "block_actions",
+            Self::ViewSubmission(_) => "view_submission",
+            Self::ViewClosed(_) => "view_closed",
+            Self::Shortcut(_) => "shortcut",
+            Self::MessageAction(_) => "message_action",
+            Self::BlockSuggestion(_) => "block_suggestion",
+            Self::InteractiveMessage(_) => "interactive_message",
+            Self::Unknown(raw) => raw["type"].as_str().unwrap_or_default(),
+        }
+    }
+
+    /// The user who triggered the interaction
+    pub fn user(&self) -> Option<&InteractiveUser> {
+        match self {
+            Self::BlockActions(p) => Some(&p.user),
+            Self::ViewSubmission(p) => Some(&p.user),
+            Self::ViewClosed(p) => Some(&p.user),
+            Self::Shortcut(p) => Some(&p.user),
+            Self::MessageAction(p) => Some(&p.user),
+            Self::BlockSuggestion(p) => Some(&p.user),
+            Self::InteractiveMessage(p) => Some(&p.user),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// Trigger ID for opening a modal, valid for three seconds
+    pub fn trigger_id(&self) -> Option<&str> {
+        match self {
+            Self::BlockActions(p) => p.trigger_id.as_deref(),
+            Self::ViewSubmission(p) => p.trigger_id.as_deref(),
+            Self::Shortcut(p) => Some(&p.trigger_id),
+            Self::MessageAction(p) => Some(&p.trigger_id),
+            Self::InteractiveMessage(p) => p.trigger_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// User in interactive payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct InteractiveUser {
+    pub id: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub team_id: Option<String>,
+}
+
+/// Channel in interactive payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct InteractiveChannel {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Workspace in interactive payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct InteractiveTeam {
+    pub id: String,
+    #[serde(default)]
+    pub domain: Option<String>,
+}
+
+/// A `block_actions` payload
+///
+/// `container` says where the element lives: a message (with `channel` and
+/// `message` set) or a view (with `view` set).
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockActionsPayload {
+    pub user: InteractiveUser,
+    pub team: Option<InteractiveTeam>,
+    pub api_app_id: Option<String>,
+    pub channel: Option<InteractiveChannel>,
+    pub trigger_id: Option<String>,
+    pub response_url: Option<String>,
+    pub container: Option<Value>,
+    pub message: Option<Value>,
+    pub view: Option<View>,
+    /// Current input values in the message or view
+    pub state: Option<ViewState>,
+    #[serde(default)]
+    pub actions: Vec<BlockAction>,
+}
+
+/// A `view_submission` payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct ViewSubmissionPayload {
+    pub user: InteractiveUser,
+    pub team: Option<InteractiveTeam>,
+    pub api_app_id: Option<String>,
+    pub trigger_id: Option<String>,
+    pub view: View,
+    /// Set when the modal has inputs with `response_url_enabled`
+    #[serde(default)]
+    pub response_urls: Vec<Value>,
+}
+
+/// A `view_closed` payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct ViewClosedPayload {
+    pub user: InteractiveUser,
+    pub team: Option<InteractiveTeam>,
+    pub api_app_id: Option<String>,
+    pub view: View,
+    /// Whether the whole view stack was closed
+    #[serde(default)]
+    pub is_cleared: bool,
+}
+
+/// A `shortcut` (global shortcut) payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShortcutPayload {
+    pub callback_id: String,
+    pub trigger_id: String,
+    pub user: InteractiveUser,
+    pub team: Option<InteractiveTeam>,
+    pub action_ts: Option<String>,
+}
+
+/// A `message_action` (message shortcut) payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageActionPayload {
+    pub callback_id: String,
+    pub trigger_id: String,
+    pub user: InteractiveUser,
+    pub team: Option<InteractiveTeam>,
+    pub channel: Option<InteractiveChannel>,
+    pub response_url: Option<String>,
+    pub message_ts: Option<String>,
+    pub message: Option<Value>,
+    pub action_ts: Option<String>,
+}
+
+/// A `block_suggestion` payload, sent while a user types in an external select
+///
+/// Respond with `{"options": [...]}` or `{"option_groups": [...]}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockSuggestionPayload {
+    pub user: InteractiveUser,
+    pub team: Option<InteractiveTeam>,
+    pub api_app_id: Option<String>,
+    pub action_id: String,
+    pub block_id: String,
+    /// What the user has typed so far
+    #[serde(default)]
+    pub value: String,
+    pub channel: Option<InteractiveChannel>,
+    pub container: Option<Value>,
+    pub view: Option<View>,
+}
+
+/// An `interactive_message` payload from a legacy attachment action
+#[derive(Debug, Clone, Deserialize)]
+pub struct InteractiveMessagePayload {
+    pub callback_id: String,
+    pub user: InteractiveUser,
+    pub team: Option<InteractiveTeam>,
+    pub channel: Option<InteractiveChannel>,
+    pub trigger_id: Option<String>,
+    pub response_url: Option<String>,
+    pub action_ts: Option<String>,
+    pub message_ts: Option<String>,
+    pub attachment_id: Option<String>,
+    pub original_message: Option<Value>,
+    #[serde(default)]
+    pub actions: Vec<Value>,
+}
+
+/// A modal or App Home view as sent back in interactive payloads
+#[derive(Debug, Clone, Deserialize)]
+pub struct View {
+    pub id: String,
+    /// `modal` or `home`
+    #[serde(rename = "type")]
+    pub view_type: String,
+    pub team_id: Option<String>,
+    pub app_id: Option<String>,
+    pub bot_id: Option<String>,
+    #[serde(default)]
+    pub callback_id: String,
+    #[serde(default)]
+    pub private_metadata: String,
+    pub external_id: Option<String>,
+    pub hash: Option<String>,
+    pub root_view_id: Option<String>,
+    pub previous_view_id: Option<String>,
+    pub title: Option<Value>,
+    #[serde(default)]
+    pub blocks: Vec<Value>,
+    #[serde(default)]
+    pub state: ViewState,
+}
+
+/// Input values of a view or message, keyed by `block_id` then `action_id`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ViewState {
+    #[serde(default)]
+    pub values: HashMap<String, HashMap<String, ElementValue>>,
+}
+
+impl ViewState {
+    /// The value of the element `action_id` in block `block_id`
+    pub fn get(&self, block_id: &str, action_id: &str) -> Option<&ElementValue> {
+        self.values.get(block_id)?.get(action_id)
+    }
+}
+
+/// An action taken on a Block Kit element in a `block_actions` payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockAction {
+    pub action_id: String,
+    pub block_id: Option<String>,
+    pub action_ts: Option<String>,
+    /// The element kind and what it now holds
+    #[serde(flatten)]
+    pub value: ElementValue,
+}
+
+/// The value of an interactive element, keyed by the element's `type`
+///
+/// Used both for actions and for `state.values`. Selections that were
+/// cleared are `None` or empty.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ElementValue {
+    Button {
+        value: Option<String>,
+    },
+    StaticSelect {
+        selected_option: Option<OptionObject>,
+    },
+    ExternalSelect {
+        selected_option: Option<OptionObject>,
+    },
+    UsersSelect {
+        selected_user: Option<String>,
+    },
+    ConversationsSelect {
+        selected_conversation: Option<String>,
+    },
+    ChannelsSelect {
+        selected_channel: Option<String>,
+    },
+    MultiStaticSelect {
+        #[serde(default)]
+        selected_options: Vec<OptionObject>,
+    },
+    MultiExternalSelect {
+        #[serde(default)]
+        selected_options: Vec<OptionObject>,
+    },
+    MultiUsersSelect {
+        #[serde(default)]
+        selected_users: Vec<String>,
+    },
+    MultiConversationsSelect {
+        #[serde(default)]
+        selected_conversations: Vec<String>,
+    },
+    MultiChannelsSelect {
+        #[serde(default)]
+        selected_channels: Vec<String>,
+    },
+    Overflow {
+        selected_option: Option<OptionObject>,
+    },
+    Checkboxes {
+        #[serde(default)]
+        selected_options: Vec<OptionObject>,
+    },
+    RadioButtons {
+        selected_option: Option<OptionObject>,
+    },
+    /// `YYYY-MM-DD`
+    Datepicker {
+        selected_date: Option<String>,
+    },
+    /// `HH:mm`
+    Timepicker {
+        selected_time: Option<String>,
+    },
+    /// Unix timestamp
+    Datetimepicker {
+        selected_date_time: Option<i64>,
+    },
+    PlainTextInput {
+        value: Option<String>,
+    },
+    EmailTextInput {
+        value: Option<String>,
+    },
+    UrlTextInput {
+        value: Option<String>,
+    },
+    /// The number as typed, e.g. `"4.5"`
+    NumberInput {
+        value: Option<String>,
+    },
+    RichTextInput {
+        rich_text_value: Option<Value>,
+    },
+    FileInput {
+        #[serde(default)]
+        files: Vec<Value>,
+    },
+    /// Any other element, as raw JSON
+    #[serde(untagged)]
+    Unknown(Value),
+}
+
+impl ElementValue {
+    /// The single string value of a button or text-like input
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Self::Button { value }
+            | Self::PlainTextInput { value }
+            | Self::EmailTextInput { value }
+            | Self::UrlTextInput { value }
+            | Self::NumberInput { value } => value.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The option values picked in a select, overflow, checkbox or radio group
+    pub fn selected_values(&self) -> Vec<&str> {
+        match self {
+            Self::StaticSelect { selected_option }
+            | Self::ExternalSelect { selected_option }
+            | Self::Overflow { selected_option }
+            | Self::RadioButtons { selected_option } => selected_option
+                .iter()
+                .map(|option| option.value.as_str())
+                .collect(),
+            Self::MultiStaticSelect { selected_options }
+            | Self::MultiExternalSelect { selected_options }
+            | Self::Checkboxes { selected_options } => selected_options
+                .iter()
+                .map(|option| option.value.as_str())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_block_actions() {
+        let payload = InteractivePayload::from_value(json!({
+            "type": "block_actions",
+            "user": { "id": "U1", "username": "alice", "team_id": "T1" },
+            "team": { "id": "T1", "domain": "acme" },
+            "channel": { "id": "C1", "name": "general" },
+            "trigger_id": "1.2.abc",
+            "response_url": "https://hooks.slack.com/actions/1",
+            "container": { "type": "message", "message_ts": "1.0" },
+            "actions": [
+                { "type": "button", "action_id": "approve", "block_id": "b1", "value": "42", "action_ts": "2.0" },
+                {
+                    "type": "static_select",
+                    "action_id": "pick",
+                    "block_id": "b2",
+                    "selected_option": { "text": { "type": "plain_text", "text": "Red" }, "value": "red" }
+                },
+                { "type": "multi_users_select", "action_id": "who", "selected_users": ["U2", "U3"] },
+                { "type": "datepicker", "action_id": "when", "selected_date": "2026-01-31" },
+                { "type": "fancy_new_element", "action_id": "new", "whatever": 1 },
+            ],
+        }));
+        assert_eq!(payload.interaction_type(), "block_actions");
+        assert_eq!(payload.user().unwrap().id, "U1");
+        assert_eq!(payload.trigger_id(), Some("1.2.abc"));
+
+        let InteractivePayload::BlockActions(payload) = payload else {
+            panic!("expected block_actions, got {:?}", payload);
+        };
+        assert_eq!(payload.channel.unwrap().id, "C1");
+        let actions = &payload.actions;
+        assert_eq!(actions.len(), 5);
+        assert_eq!(actions[0].action_id, "approve");
+        assert_eq!(actions[0].block_id.as_deref(), Some("b1"));
+        assert_eq!(actions[0].value.value(), Some("42"));
+        assert_eq!(actions[1].value.selected_values(), vec!["red"]);
+        assert!(matches!(
+            &actions[2].value,
+            ElementValue::MultiUsersSelect { selected_users } if selected_users == &["U2", "U3"]
+        ));
+        assert!(matches!(
+            &actions[3].value,
+            ElementValue::Datepicker { selected_date: Some(d) } if d == "2026-01-31"
+        ));
+        assert_eq!(actions[4].action_id, "new");
+        assert!(matches!(
+            &actions[4].value,
+            ElementValue::Unknown(raw) if raw["whatever"] == 1
+        ));
+    }
+
+    #[test]
+    fn test_parse_view_submission() {
+        let payload = InteractivePayload::from_value(json!({
+            "type": "view_submission",
+            "user": { "id": "U1" },
+            "trigger_id": "1.2.abc",
+            "view": {
+                "id": "V1",
+                "type": "modal",
+                "callback_id": "feedback",
+                "private_metadata": "C1",
+                "state": {
+                    "values": {
+                        "title": { "title_input": { "type": "plain_text_input", "value": "Hello" } },
+                        "colors": {
+                            "colors_input": {
+                                "type": "checkboxes",
+                                "selected_options": [
+                                    { "text": { "type": "plain_text", "text": "Red" }, "value": "red" },
+                                    { "text": { "type": "plain_text", "text": "Blue" }, "value": "blue" }
+                                ]
+                            }
+                        },
+                        "due": { "due_input": { "type": "datepicker", "selected_date": null } }
+                    }
+                }
+            }
+        }));
+        let InteractivePayload::ViewSubmission(payload) = payload else {
+            panic!("expected view_submission, got {:?}", payload);
+        };
+        let view = &payload.view;
+        assert_eq!(view.callback_id, "feedback");
+        assert_eq!(view.private_metadata, "C1");
+        assert_eq!(
+            view.state.get("title", "title_input").unwrap().value(),
+            Some("Hello")
+        );
+        assert_eq!(
+            view.state
+                .get("colors", "colors_input")
+                .unwrap()
+                .selected_values(),
+            vec!["red", "blue"]
+        );
+        assert!(matches!(
+            view.state.get("due", "due_input"),
+            Some(ElementValue::Datepicker {
+                selected_date: None
+            })
+        ));
+        assert!(view.state.get("title", "missing").is_none());
+    }
+
+    #[test]
+    fn test_parse_other_payloads() {
+        let payload = InteractivePayload::from_value(json!({
+            "type": "shortcut",
+            "callback_id": "new_ticket",
+            "trigger_id": "1.2.abc",
+            "user": { "id": "U1" },
+        }));
+        assert!(
+            matches!(&payload, InteractivePayload::Shortcut(s) if s.callback_id == "new_ticket")
+        );
+
+        let payload = InteractivePayload::from_value(json!({
+            "type": "block_suggestion",
+            "user": { "id": "U1" },
+            "action_id": "search",
+            "block_id": "b1",
+            "value": "ac",
+        }));
+        assert!(matches!(&payload, InteractivePayload::BlockSuggestion(s) if s.value == "ac"));
+
+        let payload = InteractivePayload::from_value(json!({
+            "type": "view_closed",
+            "user": { "id": "U1" },
+            "view": { "id": "V1", "type": "modal" },
+            "is_cleared": true,
+        }));
+        assert!(matches!(&payload, InteractivePayload::ViewClosed(c) if c.is_cleared));
+
+        let payload = InteractivePayload::from_value(json!({
+            "type": "workflow_step_edit",
+            "callback_id": "step",
+        }));
+        assert_eq!(payload.interaction_type(), "workflow_step_edit");
+        assert!(payload.user().is_none());
+        assert!(matches!(payload, InteractivePayload::Unknown(_)));
+    }
+}
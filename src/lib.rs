@@ -11,7 +11,7 @@
 //! - **Block Kit Support**: Builders for rich message layouts
 //! - **Type Safety**: Strongly typed API responses and requests
 //! - **Async/Await**: Built on tokio for high-performance async operations
-//! - **Rate Limit Handling**: Automatic detection with retry information
+//! - **Rate Limit Handling**: Opt-in retries and per-method pacing based on Slack's rate limit tiers
 //!
 //! ## Quick Start
 //!
@@ -125,7 +125,7 @@
 //! The SDK provides detailed error types:
 //!
 //! ```no_run
-//! use slacko::{SlackClient, AuthConfig, SlackError};
+//! use slacko::{SlackClient, AuthConfig, SlackError, SlackErrorCode};
 //!
 //! async fn example() {
 //!     let client = SlackClient::new(AuthConfig::bot("xoxb-token")).unwrap();
@@ -135,8 +135,12 @@
 //!         Err(SlackError::RateLimitExceeded { retry_after }) => {
 //!             println!("Rate limited, retry after {} seconds", retry_after);
 //!         }
-//!         Err(SlackError::ApiError { code, message }) => {
-//!             println!("API error {}: {}", code, message);
+//!         Err(SlackError::NotFound { .. }) => println!("Channel does not exist"),
+//!         Err(SlackError::PermissionDenied { needed, .. }) => {
+//!             println!("Missing scope: {}", needed.unwrap_or_default());
+//!         }
+//!         Err(SlackError::ApiError { method, code: SlackErrorCode::NotInChannel }) => {
+//!             println!("Join the channel before calling {}", method);
 //!         }
 //!         Err(e) => println!("Error: {}", e),
 //!     }
@@ -146,15 +150,40 @@
 pub mod auth;
 pub mod blocks;
 pub mod client;
+pub mod credentials;
 pub mod error;
+pub mod events;
+#[cfg(feature = "install")]
+pub mod install;
+pub mod interactions;
+pub mod middleware;
+pub mod oidc;
+pub mod pagination;
+pub mod rate_limit;
+#[cfg(feature = "receiver")]
+pub mod receiver;
+pub mod rotation;
+#[cfg(any(feature = "testing", feature = "install", feature = "receiver"))]
+mod server;
+pub mod signature;
 pub mod types;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+pub(crate) mod websocket;
+
 pub mod api;
 
 // Re-export commonly used types
 pub use auth::{AuthConfig, AuthType};
-pub use client::SlackClient;
-pub use error::{Result, SlackError};
+pub use client::{SlackClient, SlackClientBuilder};
+pub use credentials::CredentialProvider;
+pub use error::{Result, SlackError, SlackErrorCode};
+pub use events::Event;
+pub use middleware::Middleware;
+pub use pagination::{Paginated, PaginatedRequest, Paginator};
+pub use rate_limit::{RateLimitTier, RetryPolicy};
+pub use rotation::TokenRotation;
 
 // Re-export Block Kit builders for convenience
 pub use blocks::{
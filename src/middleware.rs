@@ -0,0 +1,432 @@
+//! Request pipeline middleware
+//!
+//! Every Web API call made by [`SlackClient`](crate::SlackClient) goes through
+//! a single pipeline: the request is built, passed to each middleware's
+//! [`Middleware::before_request`] hook, sent, and the response is passed to
+//! each middleware's [`Middleware::after_response`] hook in reverse order.
+//!
+//! Middleware can inspect or modify requests (logging, request signing,
+//! auditing), serve responses without touching the network (caching, fault
+//! injection), and ask for a request to be retried. Rate limit handling is
+//! itself implemented as a middleware.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use slacko::middleware::{ApiRequest, ApiResponse, Middleware, ResponseAction};
+//! use slacko::{AuthConfig, Result, SlackClient};
+//!
+//! struct Logger;
+//!
+//! #[async_trait::async_trait]
+//! impl Middleware for Logger {
+//!     async fn before_request(&self, request: &mut ApiRequest) -> Result<Option<ApiResponse>> {
+//!         println!("-> {}", request.method);
+//!         Ok(None)
+//!     }
+//!
+//!     async fn after_response(
+//!         &self,
+//!         request: &ApiRequest,
+//!         response: &mut ApiResponse,
+//!     ) -> Result<ResponseAction> {
+//!         println!("<- {} {}", request.method, response.status);
+//!         Ok(ResponseAction::Continue)
+//!     }
+//! }
+//!
+//! let client = SlackClient::new(AuthConfig::bot("xoxb-token"))
+//!     .unwrap()
+//!     .with_middleware(Logger);
+//! ```
+
+use crate::error::Result;
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A hook into the request pipeline
+///
+/// Middleware run in the order they were added for [`before_request`] and in
+/// reverse order for [`after_response`], so the first middleware added wraps
+/// all the others.
+///
+/// [`before_request`]: Middleware::before_request
+/// [`after_response`]: Middleware::after_response
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Called before a request is sent
+    ///
+    /// The request can be modified in place. Returning `Ok(Some(response))`
+    /// skips the network and the remaining `before_request` hooks, and uses
+    /// the given response instead. Returning an error aborts the call.
+    async fn before_request(&self, request: &mut ApiRequest) -> Result<Option<ApiResponse>> {
+        let _ = request;
+        Ok(None)
+    }
+
+    /// Called after a response is received
+    ///
+    /// The response can be modified in place. Returning
+    /// [`ResponseAction::Retry`] sends the request again from the start of
+    /// the pipeline. Returning an error aborts the call.
+    async fn after_response(
+        &self,
+        request: &ApiRequest,
+        response: &mut ApiResponse,
+    ) -> Result<ResponseAction> {
+        let _ = (request, response);
+        Ok(ResponseAction::Continue)
+    }
+}
+
+/// What the pipeline should do after a middleware has seen a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseAction {
+    /// Pass the response on
+    Continue,
+    /// Send the request again
+    Retry,
+}
+
+/// A Web API request on its way through the pipeline
+#[derive(Debug, Clone)]
+pub struct ApiRequest {
+    /// Slack method name, e.g. `chat.postMessage`
+    pub method: String,
+    /// Full request URL
+    pub url: String,
+    /// Request headers, including authentication
+    pub headers: HeaderMap,
+    /// Request body
+    pub body: RequestBody,
+    /// Zero-based attempt number; incremented on every retry
+    pub attempt: u32,
+    /// Per-call state for middleware, carried over to retries
+    pub extensions: http::Extensions,
+}
+
+impl ApiRequest {
+    /// Look up a top-level parameter in the request body
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.body.param(name)
+    }
+}
+
+/// Request body, kept in structured form so it can be inspected and resent
+#[derive(Debug, Clone)]
+pub enum RequestBody {
+    /// `application/json` POST body
+    Json(Value),
+    /// `application/x-www-form-urlencoded` POST body
+    Form(Vec<(String, String)>),
+    /// `multipart/form-data` POST body
+    Multipart(MultipartBody),
+    /// GET request with query parameters
+    Query(Vec<(String, String)>),
+}
+
+impl RequestBody {
+    /// `application/json` body from any serializable value
+    pub fn json(params: &impl serde::Serialize) -> Result<Self> {
+        Ok(RequestBody::Json(serde_json::to_value(params)?))
+    }
+
+    /// `application/x-www-form-urlencoded` body from key/value pairs
+    pub fn form<K: Into<String>, V: Into<String>>(
+        params: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        RequestBody::Form(owned_pairs(params))
+    }
+
+    /// GET query string from key/value pairs
+    pub fn query<K: Into<String>, V: Into<String>>(
+        params: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        RequestBody::Query(owned_pairs(params))
+    }
+
+    /// `multipart/form-data` body, for uploads
+    pub fn multipart(body: MultipartBody) -> Self {
+        RequestBody::Multipart(body)
+    }
+
+    /// Encode serializable parameters as a JSON, form or multipart body
+    ///
+    /// For form and multipart bodies, each top-level field becomes one form
+    /// field. Strings, numbers and booleans are sent as-is, nested objects and
+    /// arrays as JSON strings, and `null` fields are skipped.
+    pub fn encode(params: &impl serde::Serialize, encoding: BodyEncoding) -> Result<Self> {
+        if encoding == BodyEncoding::Json {
+            return Self::json(params);
+        }
+
+        let value = serde_json::to_value(params)?;
+        let mut fields: Vec<(String, String)> = flatten_params(&value, None)?.into_iter().collect();
+        fields.sort();
+
+        Ok(match encoding {
+            BodyEncoding::Form => RequestBody::Form(fields),
+            _ => RequestBody::Multipart(MultipartBody {
+                fields,
+                files: Vec::new(),
+            }),
+        })
+    }
+
+    /// Look up a top-level parameter by name
+    pub fn param(&self, name: &str) -> Option<&str> {
+        let find = |pairs: &'_ [(String, String)]| -> Option<usize> {
+            pairs.iter().position(|(key, _)| key == name)
+        };
+
+        match self {
+            RequestBody::Json(value) => value.get(name).and_then(|v| v.as_str()),
+            RequestBody::Form(pairs) | RequestBody::Query(pairs) => {
+                find(pairs).map(|i| pairs[i].1.as_str())
+            }
+            RequestBody::Multipart(body) => find(&body.fields).map(|i| body.fields[i].1.as_str()),
+        }
+    }
+}
+
+/// How a method's parameters are encoded in a POST body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BodyEncoding {
+    /// `application/json`
+    Json,
+    /// `application/x-www-form-urlencoded`
+    Form,
+    /// `multipart/form-data`
+    Multipart,
+}
+
+impl BodyEncoding {
+    /// Default encoding for a built-in method
+    ///
+    /// Most write methods accept JSON. OAuth token exchanges, a few read
+    /// methods and the web-client methods used with `xoxc` tokens only read
+    /// form fields.
+    pub fn for_method(method: &str) -> Self {
+        match method {
+            "oauth.v2.access"
+            | "oauth.v2.exchange"
+            | "openid.connect.token"
+            | "conversations.history"
+            | "conversations.replies"
+            | "conversations.members"
+            | "files.getUploadURLExternal"
+            | "saved.list"
+            | "saved.delete" => BodyEncoding::Form,
+
+            "activity.feed" | "messages.list" => BodyEncoding::Multipart,
+
+            _ => BodyEncoding::Json,
+        }
+    }
+}
+
+/// Flatten a JSON value into key-value pairs suitable for form data
+/// Handles nested objects and arrays by converting them to JSON strings
+fn flatten_params(value: &Value, prefix: Option<&str>) -> Result<HashMap<String, String>> {
+    let mut result = HashMap::new();
+
+    match value {
+        Value::Object(map) => {
+            for (key, val) in map {
+                // Skip null values (same as serde skip_serializing_if)
+                if val.is_null() {
+                    continue;
+                }
+
+                let full_key = match prefix {
+                    Some(p) => format!("{}[{}]", p, key),
+                    None => key.clone(),
+                };
+
+                match val {
+                    Value::String(s) => {
+                        result.insert(full_key, s.clone());
+                    }
+                    Value::Bool(b) => {
+                        result.insert(full_key, b.to_string());
+                    }
+                    Value::Number(n) => {
+                        result.insert(full_key, n.to_string());
+                    }
+                    Value::Array(_) | Value::Object(_) => {
+                        // For objects, encode as JSON string
+                        let json_str = serde_json::to_string(val).map_err(|e| {
+                            crate::error::SlackError::config_error(format!(
+                                "Failed to serialize object: {}",
+                                e
+                            ))
+                        })?;
+                        result.insert(full_key, json_str);
+                    }
+                    Value::Null => {
+                        // Skip null values
+                    }
+                }
+            }
+        }
+        _ => {
+            // For non-object root values, use the prefix or default key
+            let key = prefix.unwrap_or("value").to_string();
+            let value_str = match value {
+                Value::String(s) => s.clone(),
+                Value::Bool(b) => b.to_string(),
+                Value::Number(n) => n.to_string(),
+                Value::Array(arr) => {
+                    // For arrays, join with commas
+                    let string_values: Vec<String> = arr
+                        .iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect();
+                    if string_values.len() == arr.len() {
+                        string_values.join(",")
+                    } else {
+                        return Err(crate::error::SlackError::config_error(
+                            "Arrays must contain only strings for form encoding",
+                        ));
+                    }
+                }
+                Value::Object(_) => serde_json::to_string(value).map_err(|e| {
+                    crate::error::SlackError::config_error(format!(
+                        "Failed to serialize value: {}",
+                        e
+                    ))
+                })?,
+                Value::Null => return Ok(result), // Skip null values
+            };
+            result.insert(key, value_str);
+        }
+    }
+
+    Ok(result)
+}
+
+fn owned_pairs<K: Into<String>, V: Into<String>>(
+    params: impl IntoIterator<Item = (K, V)>,
+) -> Vec<(String, String)> {
+    params
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.into()))
+        .collect()
+}
+
+/// Multipart form with text fields and file parts
+#[derive(Debug, Clone, Default)]
+pub struct MultipartBody {
+    /// Text fields
+    pub fields: Vec<(String, String)>,
+    /// File parts
+    pub files: Vec<FilePart>,
+}
+
+impl MultipartBody {
+    /// Create an empty multipart body
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a text field
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    /// Add a file part
+    pub fn file(mut self, part: FilePart) -> Self {
+        self.files.push(part);
+        self
+    }
+
+    /// Build a `reqwest` multipart form
+    pub(crate) fn to_form(&self) -> Result<reqwest::multipart::Form> {
+        use reqwest::multipart::{Form, Part};
+
+        let mut form = Form::new();
+
+        for (key, value) in &self.fields {
+            form = form.text(key.clone(), value.clone());
+        }
+
+        for file in &self.files {
+            let mut part = Part::bytes(file.data.clone()).file_name(file.file_name.clone());
+            if let Some(mime_type) = &file.mime_type {
+                part = part.mime_str(mime_type).map_err(|e| {
+                    crate::error::SlackError::config_error(format!("Invalid mime type: {}", e))
+                })?;
+            }
+            form = form.part(file.field.clone(), part);
+        }
+
+        Ok(form)
+    }
+}
+
+/// A file in a multipart request
+#[derive(Debug, Clone)]
+pub struct FilePart {
+    /// Form field name, e.g. `file`
+    pub field: String,
+    /// File name sent with the part
+    pub file_name: String,
+    /// MIME type of the part, if set
+    pub mime_type: Option<String>,
+    /// File content
+    pub data: Vec<u8>,
+}
+
+impl FilePart {
+    /// Create a file part
+    pub fn new(field: impl Into<String>, file_name: impl Into<String>, data: Vec<u8>) -> Self {
+        Self {
+            field: field.into(),
+            file_name: file_name.into(),
+            mime_type: None,
+            data,
+        }
+    }
+
+    /// Set the MIME type of the part
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+}
+
+/// A raw HTTP response from the Web API
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers
+    pub headers: HeaderMap,
+    /// Response body
+    pub body: Vec<u8>,
+}
+
+impl ApiResponse {
+    /// Create a `200 OK` response with a JSON body
+    pub fn json(body: &Value) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            reqwest::header::HeaderValue::from_static("application/json; charset=utf-8"),
+        );
+
+        Self {
+            status: 200,
+            headers,
+            body: serde_json::to_vec(body).unwrap_or_default(),
+        }
+    }
+
+    /// Parse the body as JSON
+    pub fn body_json(&self) -> Result<Value> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
@@ -0,0 +1,645 @@
+//! Sign in with Slack: local verification of OpenID Connect ID tokens
+//!
+//! [`OpenIDApi::token`] returns an `id_token`, a JWT signed by Slack that
+//! carries the user's identity. [`IdTokenVerifier`] checks its RS256
+//! signature against Slack's published keys (JWKS) and its `iss`, `aud`,
+//! `exp` and `nonce` claims, and returns the typed [`IdTokenClaims`], so no
+//! `openid.connect.userInfo` call is needed.
+//!
+//! [`AuthorizeRequest`] builds the URL that starts the flow, with a random
+//! `state` and `nonce` to check when the user comes back.
+//!
+//! ```no_run
+//! use slacko::oidc::{AuthorizeRequest, IdTokenVerifier};
+//! use slacko::{AuthConfig, SlackClient};
+//!
+//! # async fn example(code: &str) -> slacko::Result<()> {
+//! let authorization = AuthorizeRequest::new("client-id", "https://example.com/callback").build();
+//! // Redirect the user to authorization.url, keep state and nonce in the session
+//!
+//! // On the callback, after checking the state:
+//! let client = SlackClient::new(AuthConfig::bot(""))?;
+//! let tokens = client
+//!     .openid()
+//!     .token(code, "client-id", "client-secret", Some("https://example.com/callback"))
+//!     .await?;
+//!
+//! let verifier = IdTokenVerifier::new("client-id");
+//! let claims = verifier
+//!     .verify(&tokens.id_token, Some(&authorization.nonce))
+//!     .await?;
+//! println!("{} from team {}", claims.user_id, claims.team_id);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`OpenIDApi::token`]: crate::api::openid::OpenIDApi::token
+
+use crate::error::{Result, SlackError};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::{Alphanumeric, DistString};
+use ring::signature::{RsaPublicKeyComponents, RSA_PKCS1_2048_8192_SHA256};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Slack's OpenID Connect issuer
+pub const SLACK_ISSUER: &str = "https://slack.com";
+
+/// Where Slack publishes its signing keys
+pub const SLACK_JWKS_URL: &str = "https://slack.com/openid/connect/keys";
+
+/// Slack's Sign in with Slack authorize page
+const AUTHORIZE_URL: &str = "https://slack.com/openid/connect/authorize";
+
+/// Default allowance for clock differences when checking `exp` and `iat`
+const DEFAULT_LEEWAY: Duration = Duration::from_secs(60);
+
+/// Shortest time between two key fetches, so tokens with made-up key IDs
+/// can't make the verifier hammer the JWKS endpoint
+const MIN_REFETCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Claims in a Slack ID token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    /// Issuer, `https://slack.com`
+    pub iss: String,
+    /// Subject; the user ID
+    pub sub: String,
+    /// Audience; your app's client ID
+    #[serde(deserialize_with = "audience")]
+    pub aud: Vec<String>,
+    /// Expiry, in seconds since the epoch
+    pub exp: u64,
+    /// Issue time, in seconds since the epoch
+    pub iat: u64,
+    pub auth_time: Option<u64>,
+    /// The nonce from the authorize request
+    pub nonce: Option<String>,
+    pub at_hash: Option<String>,
+    #[serde(rename = "https://slack.com/team_id")]
+    pub team_id: String,
+    #[serde(rename = "https://slack.com/user_id")]
+    pub user_id: String,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub locale: Option<String>,
+    #[serde(rename = "https://slack.com/team_name")]
+    pub team_name: Option<String>,
+    #[serde(rename = "https://slack.com/team_domain")]
+    pub team_domain: Option<String>,
+    #[serde(rename = "https://slack.com/team_image_230")]
+    pub team_image_230: Option<String>,
+}
+
+/// `aud` is either a single string or an array
+fn audience<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Audience {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Audience::deserialize(deserializer)? {
+        Audience::One(aud) => vec![aud],
+        Audience::Many(aud) => aud,
+    })
+}
+
+/// A JSON Web Key Set, as published at [`SLACK_JWKS_URL`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+/// A public key from a [`Jwks`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Jwk {
+    /// Key type; only `RSA` keys are used
+    pub kty: String,
+    /// Key ID, matched against the token's `kid` header
+    pub kid: Option<String>,
+    pub alg: Option<String>,
+    #[serde(rename = "use")]
+    pub key_use: Option<String>,
+    /// RSA modulus, base64url
+    pub n: Option<String>,
+    /// RSA public exponent, base64url
+    pub e: Option<String>,
+}
+
+impl Jwks {
+    /// Find the key for a token's `kid`; a token without one matches a lone key
+    fn find(&self, kid: Option<&str>) -> Option<&Jwk> {
+        match kid {
+            Some(kid) => self.keys.iter().find(|k| k.kid.as_deref() == Some(kid)),
+            None if self.keys.len() == 1 => self.keys.first(),
+            None => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+    kid: Option<String>,
+}
+
+fn invalid(message: impl Into<String>) -> SlackError {
+    SlackError::AuthError {
+        message: format!("Invalid ID token: {}", message.into()),
+        method: None,
+        code: None,
+    }
+}
+
+fn decode_part(part: &str, what: &str) -> Result<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| invalid(format!("{} is not base64url", what)))
+}
+
+/// Verifies ID tokens issued to an app
+///
+/// Keys are fetched from [`SLACK_JWKS_URL`] on first use and cached; a token
+/// signed with an unknown key triggers a refetch, to pick up key rotation.
+/// Fetches, including failed ones, happen at most once a minute; in between,
+/// the last failure is returned again. Use [`jwks`](Self::jwks) to supply the
+/// keys instead.
+#[derive(Debug, Clone)]
+pub struct IdTokenVerifier {
+    client_id: String,
+    issuer: String,
+    jwks_url: Option<String>,
+    leeway: Duration,
+    http: reqwest::Client,
+    keys: Arc<RwLock<KeyCache>>,
+}
+
+/// Cached signing keys and when they were last fetched
+#[derive(Debug, Default)]
+struct KeyCache {
+    jwks: Option<Arc<Jwks>>,
+    /// When the last fetch started, whether it succeeded or not
+    fetched_at: Option<Instant>,
+    /// Why the last fetch failed, if it did
+    last_error: Option<String>,
+}
+
+impl KeyCache {
+    /// Claim the next fetch, or fail if the last one was too recent
+    ///
+    /// While throttled, the last fetch's error is returned again.
+    fn claim_fetch(&mut self, now: Instant, kid: Option<&str>) -> Result<()> {
+        let recent = self
+            .fetched_at
+            .is_some_and(|at| now.duration_since(at) < MIN_REFETCH_INTERVAL);
+        if recent {
+            return Err(match &self.last_error {
+                Some(error) => {
+                    SlackError::Other(format!("Failed to fetch signing keys: {}", error))
+                }
+                None => invalid(format!("no key with ID {:?}", kid)),
+            });
+        }
+        self.fetched_at = Some(now);
+        Ok(())
+    }
+}
+
+impl IdTokenVerifier {
+    /// Verify tokens issued to the app with this client ID
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            issuer: SLACK_ISSUER.to_string(),
+            jwks_url: Some(SLACK_JWKS_URL.to_string()),
+            leeway: DEFAULT_LEEWAY,
+            http: reqwest::Client::new(),
+            keys: Arc::new(RwLock::new(KeyCache::default())),
+        }
+    }
+
+    /// Use these keys and never fetch any
+    pub fn jwks(mut self, jwks: Jwks) -> Self {
+        self.jwks_url = None;
+        self.keys = Arc::new(RwLock::new(KeyCache {
+            jwks: Some(Arc::new(jwks)),
+            ..KeyCache::default()
+        }));
+        self
+    }
+
+    /// Fetch keys from this URL instead of [`SLACK_JWKS_URL`]
+    pub fn jwks_url(mut self, url: impl Into<String>) -> Self {
+        self.jwks_url = Some(url.into());
+        self
+    }
+
+    /// Expected `iss` claim (default: [`SLACK_ISSUER`])
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = issuer.into();
+        self
+    }
+
+    /// Allowance for clock differences (default: 60 seconds)
+    pub fn leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// HTTP client used to fetch keys, e.g. one with a proxy
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Verify an ID token and return its claims
+    ///
+    /// Checks the RS256 signature, the issuer, that the audience includes the
+    /// client ID, that the token has not expired and, if `nonce` is given,
+    /// that it matches. Fails with [`SlackError::AuthError`] if any check
+    /// fails.
+    pub async fn verify(&self, id_token: &str, nonce: Option<&str>) -> Result<IdTokenClaims> {
+        let mut parts = id_token.split('.');
+        let (Some(header), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid("expected three dot-separated parts"));
+        };
+
+        let header: Header = serde_json::from_slice(&decode_part(header, "header")?)
+            .map_err(|e| invalid(format!("bad header: {}", e)))?;
+        if header.alg != "RS256" {
+            return Err(invalid(format!("unsupported algorithm {}", header.alg)));
+        }
+
+        let key = self.key(header.kid.as_deref()).await?;
+        let (Some(n), Some(e)) = (&key.n, &key.e) else {
+            return Err(invalid("signing key is not an RSA key"));
+        };
+        let public_key = RsaPublicKeyComponents {
+            n: decode_part(n, "key modulus")?,
+            e: decode_part(e, "key exponent")?,
+        };
+        let signed = &id_token[..header_and_payload_len(id_token)];
+        public_key
+            .verify(
+                &RSA_PKCS1_2048_8192_SHA256,
+                signed.as_bytes(),
+                &decode_part(signature, "signature")?,
+            )
+            .map_err(|_| invalid("signature does not match"))?;
+
+        let claims: IdTokenClaims = serde_json::from_slice(&decode_part(payload, "payload")?)
+            .map_err(|e| invalid(format!("bad claims: {}", e)))?;
+        self.check_claims(&claims, nonce)?;
+
+        Ok(claims)
+    }
+
+    fn check_claims(&self, claims: &IdTokenClaims, nonce: Option<&str>) -> Result<()> {
+        if claims.iss != self.issuer {
+            return Err(invalid(format!("issuer is {}", claims.iss)));
+        }
+        if !claims.aud.contains(&self.client_id) {
+            return Err(invalid("issued to another client"));
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let leeway = self.leeway.as_secs();
+        if claims.exp.saturating_add(leeway) <= now {
+            return Err(invalid("expired"));
+        }
+        if claims.iat > now.saturating_add(leeway) {
+            return Err(invalid("issued in the future"));
+        }
+
+        if let Some(nonce) = nonce {
+            if claims.nonce.as_deref() != Some(nonce) {
+                return Err(invalid("nonce does not match"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find the signing key, refetching once if it is unknown
+    async fn key(&self, kid: Option<&str>) -> Result<Jwk> {
+        let cached = self.keys.read().unwrap().jwks.clone();
+        if let Some(key) = cached.as_ref().and_then(|jwks| jwks.find(kid)) {
+            return Ok(key.clone());
+        }
+
+        let Some(url) = &self.jwks_url else {
+            return Err(invalid(format!("no key with ID {:?}", kid)));
+        };
+        self.keys
+            .write()
+            .unwrap()
+            .claim_fetch(Instant::now(), kid)?;
+        let jwks = match self.fetch_keys(url).await {
+            Ok(jwks) => jwks,
+            Err(e) => {
+                self.keys.write().unwrap().last_error = Some(e.to_string());
+                return Err(e);
+            }
+        };
+        tracing::debug!("Fetched {} signing keys from {}", jwks.keys.len(), url);
+
+        let key = jwks.find(kid).cloned();
+        let mut cache = self.keys.write().unwrap();
+        cache.jwks = Some(Arc::new(jwks));
+        cache.last_error = None;
+        key.ok_or_else(|| invalid(format!("no key with ID {:?}", kid)))
+    }
+
+    async fn fetch_keys(&self, url: &str) -> Result<Jwks> {
+        Ok(self
+            .http
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+/// Length of the signed `header.payload` part of a JWT
+fn header_and_payload_len(token: &str) -> usize {
+    token.rfind('.').unwrap_or(token.len())
+}
+
+/// Builds the URL that starts Sign in with Slack
+#[derive(Debug, Clone)]
+pub struct AuthorizeRequest {
+    client_id: String,
+    redirect_uri: String,
+    scopes: Vec<String>,
+    team: Option<String>,
+    state: Option<String>,
+    nonce: Option<String>,
+}
+
+/// A built authorize URL with the `state` and `nonce` it carries
+///
+/// Keep both until the callback: check `state` against the redirect, and
+/// pass `nonce` to [`IdTokenVerifier::verify`].
+#[derive(Debug, Clone)]
+pub struct Authorization {
+    pub url: String,
+    pub state: String,
+    pub nonce: String,
+}
+
+impl AuthorizeRequest {
+    /// Start building an authorize URL; scopes default to `openid`, `email` and `profile`
+    pub fn new(client_id: impl Into<String>, redirect_uri: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            redirect_uri: redirect_uri.into(),
+            scopes: vec!["openid".into(), "email".into(), "profile".into()],
+            team: None,
+            state: None,
+            nonce: None,
+        }
+    }
+
+    /// Scopes to request
+    pub fn scopes<I, S>(mut self, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Skip the workspace picker and sign in to this team
+    pub fn team(mut self, team_id: impl Into<String>) -> Self {
+        self.team = Some(team_id.into());
+        self
+    }
+
+    /// Use this state instead of a random one
+    pub fn state(mut self, state: impl Into<String>) -> Self {
+        self.state = Some(state.into());
+        self
+    }
+
+    /// Use this nonce instead of a random one
+    pub fn nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+
+    /// Build the URL, generating a random `state` and `nonce` if not set
+    pub fn build(self) -> Authorization {
+        let random = || Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+        let state = self.state.unwrap_or_else(random);
+        let nonce = self.nonce.unwrap_or_else(random);
+
+        let mut url = url::Url::parse(AUTHORIZE_URL).expect("authorize URL is valid");
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("response_type", "code");
+            query.append_pair("scope", &self.scopes.join(" "));
+            query.append_pair("client_id", &self.client_id);
+            query.append_pair("redirect_uri", &self.redirect_uri);
+            query.append_pair("state", &state);
+            query.append_pair("nonce", &nonce);
+            if let Some(team) = &self.team {
+                query.append_pair("team", team);
+            }
+        }
+
+        Authorization {
+            url: url.into(),
+            state,
+            nonce,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+    use serde_json::{json, Value};
+
+    const KEY: &[u8] = include_bytes!("../tests/fixtures/oidc_rsa_key.der");
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn jwks() -> Jwks {
+        let key = RsaKeyPair::from_der(KEY).unwrap();
+        let public = RsaPublicKeyComponents::<Vec<u8>>::from(key.public());
+        Jwks {
+            keys: vec![Jwk {
+                kty: "RSA".to_string(),
+                kid: Some("key-1".to_string()),
+                alg: Some("RS256".to_string()),
+                key_use: Some("sig".to_string()),
+                n: Some(URL_SAFE_NO_PAD.encode(public.n)),
+                e: Some(URL_SAFE_NO_PAD.encode(public.e)),
+            }],
+        }
+    }
+
+    fn sign(claims: &Value) -> String {
+        let header = json!({ "alg": "RS256", "kid": "key-1", "typ": "JWT" });
+        let signed = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+
+        let key = RsaKeyPair::from_der(KEY).unwrap();
+        let mut signature = vec![0; key.public().modulus_len()];
+        key.sign(
+            &RSA_PKCS1_SHA256,
+            &SystemRandom::new(),
+            signed.as_bytes(),
+            &mut signature,
+        )
+        .unwrap();
+        format!("{}.{}", signed, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    fn payload() -> Value {
+        json!({
+            "iss": "https://slack.com",
+            "sub": "U123",
+            "aud": "client-id",
+            "exp": now() + 3600,
+            "iat": now(),
+            "auth_time": now(),
+            "nonce": "nonce-1",
+            "https://slack.com/user_id": "U123",
+            "https://slack.com/team_id": "T123",
+            "email": "user@example.com",
+            "email_verified": true,
+            "name": "Test User",
+        })
+    }
+
+    #[tokio::test]
+    async fn test_verify_id_token() {
+        let verifier = IdTokenVerifier::new("client-id").jwks(jwks());
+
+        let claims = verifier
+            .verify(&sign(&payload()), Some("nonce-1"))
+            .await
+            .unwrap();
+        assert_eq!(claims.user_id, "U123");
+        assert_eq!(claims.team_id, "T123");
+        assert_eq!(claims.aud, ["client-id"]);
+        assert_eq!(claims.email.as_deref(), Some("user@example.com"));
+        assert_eq!(claims.name.as_deref(), Some("Test User"));
+
+        let rejected = |token: String, nonce: Option<&'static str>| {
+            let verifier = verifier.clone();
+            async move { verifier.verify(&token, nonce).await.unwrap_err() }
+        };
+
+        // Tampered payload
+        let token = sign(&payload());
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let mut tampered = payload();
+        tampered["https://slack.com/user_id"] = json!("U999");
+        let encoded = URL_SAFE_NO_PAD.encode(tampered.to_string());
+        parts[1] = &encoded;
+        let err = rejected(parts.join("."), None).await;
+        assert!(err.to_string().contains("signature"), "{}", err);
+
+        let err = rejected(sign(&payload()), Some("nonce-2")).await;
+        assert!(err.to_string().contains("nonce"), "{}", err);
+
+        let mut expired = payload();
+        expired["exp"] = json!(now() - 120);
+        let err = rejected(sign(&expired), None).await;
+        assert!(err.to_string().contains("expired"), "{}", err);
+
+        let mut other_app = payload();
+        other_app["aud"] = json!(["other-client"]);
+        let err = rejected(sign(&other_app), None).await;
+        assert!(err.to_string().contains("another client"), "{}", err);
+
+        let mut other_issuer = payload();
+        other_issuer["iss"] = json!("https://example.com");
+        let err = rejected(sign(&other_issuer), None).await;
+        assert!(err.to_string().contains("issuer"), "{}", err);
+
+        let err = rejected("not-a-jwt".to_string(), None).await;
+        assert!(matches!(err, SlackError::AuthError { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key_refetch_throttled() {
+        // Nothing listens here, so a fetch fails with an HTTP error
+        let verifier = IdTokenVerifier::new("client-id").jwks_url("http://127.0.0.1:9/keys");
+        *verifier.keys.write().unwrap() = KeyCache {
+            jwks: Some(Arc::new(jwks())),
+            fetched_at: Some(Instant::now()),
+            last_error: None,
+        };
+
+        let err = verifier.key(Some("key-2")).await.unwrap_err();
+        assert!(err.to_string().contains("no key with ID"), "{}", err);
+
+        verifier.keys.write().unwrap().fetched_at =
+            Some(Instant::now() - MIN_REFETCH_INTERVAL - Duration::from_secs(1));
+        let err = verifier.key(Some("key-2")).await.unwrap_err();
+        assert!(matches!(err, SlackError::HttpError(_)), "{}", err);
+        assert!(verifier.key(Some("key-1")).await.is_ok());
+
+        // A failed fetch is throttled too, and its error is returned again
+        let verifier = IdTokenVerifier::new("client-id").jwks_url("http://127.0.0.1:9/keys");
+        let err = verifier.key(Some("key-1")).await.unwrap_err();
+        assert!(matches!(err, SlackError::HttpError(_)), "{}", err);
+        let err = verifier.key(Some("key-1")).await.unwrap_err();
+        assert!(
+            err.to_string().contains("Failed to fetch signing keys"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_authorize_url() {
+        let authorization = AuthorizeRequest::new("client-id", "https://example.com/cb")
+            .team("T123")
+            .nonce("nonce-1")
+            .build();
+        assert_eq!(authorization.nonce, "nonce-1");
+        assert_eq!(authorization.state.len(), 32);
+
+        let url = url::Url::parse(&authorization.url).unwrap();
+        let query: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["scope"], "openid email profile");
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["redirect_uri"], "https://example.com/cb");
+        assert_eq!(query["state"], authorization.state);
+        assert_eq!(query["nonce"], "nonce-1");
+        assert_eq!(query["team"], "T123");
+    }
+}
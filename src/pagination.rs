@@ -0,0 +1,391 @@
+//! Cursor-based pagination
+//!
+//! Slack's list methods return a page of results plus a
+//! `response_metadata.next_cursor` to fetch the next one. [`Paginator`] runs
+//! that loop for you and yields the individual items as a
+//! [`Stream`](futures_util::Stream). Pages are requested lazily, so dropping
+//! the stream stops fetching, and every page goes through the client's
+//! request pipeline, including rate limit pacing.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use slacko::{AuthConfig, ConversationHistoryRequest, SlackClient};
+//!
+//! # async fn example() -> slacko::Result<()> {
+//! let client = SlackClient::new(AuthConfig::bot("xoxb-token"))?;
+//!
+//! let request = ConversationHistoryRequest {
+//!     channel: "C12345".to_string(),
+//!     limit: None,
+//!     cursor: None,
+//!     oldest: None,
+//!     latest: None,
+//!     inclusive: None,
+//! };
+//!
+//! let mut messages = client
+//!     .conversations()
+//!     .history_stream(request)
+//!     .page_size(200)
+//!     .max_items(1000);
+//!
+//! while let Some(message) = messages.next().await {
+//!     let message = message?;
+//!     if message.text == "stop" {
+//!         break;
+//!     }
+//! }
+//!
+//! let users = client.users().list_stream(Default::default()).collect_all().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::SlackClient;
+use crate::error::Result;
+use futures_util::stream::{self, BoxStream, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A request for a cursor-paginated method
+pub trait PaginatedRequest: Serialize + Send + Sync + 'static {
+    /// Set the cursor of the page to fetch
+    fn set_cursor(&mut self, cursor: Option<String>);
+
+    /// Set the maximum number of items per page
+    fn set_limit(&mut self, limit: u32);
+}
+
+/// A response page from a cursor-paginated method
+pub trait Paginated: DeserializeOwned + Send + 'static {
+    /// Type of the items in a page
+    type Item: Send + 'static;
+
+    /// Cursor of the next page, if any
+    fn next_cursor(&self) -> Option<&str>;
+
+    /// Items in this page
+    fn items(&self) -> &[Self::Item];
+
+    /// Consume the page and return its items
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+/// Implement [`PaginatedRequest`] and [`Paginated`] for a request/response
+/// pair with `cursor`/`limit` fields and a `response_metadata` field
+macro_rules! impl_paginated {
+    ($request:ty, $response:ty, $field:ident: $item:ty) => {
+        impl $crate::pagination::PaginatedRequest for $request {
+            fn set_cursor(&mut self, cursor: Option<String>) {
+                self.cursor = cursor;
+            }
+
+            fn set_limit(&mut self, limit: u32) {
+                self.limit = Some(limit);
+            }
+        }
+
+        impl $crate::pagination::Paginated for $response {
+            type Item = $item;
+
+            fn next_cursor(&self) -> Option<&str> {
+                self.response_metadata
+                    .as_ref()
+                    .and_then(|m| m.next_cursor.as_deref())
+            }
+
+            fn items(&self) -> &[$item] {
+                &self.$field
+            }
+
+            fn into_items(self) -> Vec<$item> {
+                self.$field
+            }
+        }
+    };
+}
+
+pub(crate) use impl_paginated;
+
+/// Stream over every item of a cursor-paginated method
+///
+/// Created by the `*_stream` methods on the API clients or by
+/// [`SlackClient::paginate`]. Yields items one by one, fetching the next page
+/// when the current one is exhausted. Stops after the last page, after
+/// [`max_items`](Paginator::max_items) items, or after the first error.
+pub struct Paginator<Q, R: Paginated> {
+    config: Option<Box<Config<Q, R>>>,
+    items: Option<BoxStream<'static, Result<R::Item>>>,
+    _response: PhantomData<fn() -> R>,
+}
+
+struct Config<Q, R> {
+    client: SlackClient,
+    method: String,
+    request: Q,
+    page_size: Option<u32>,
+    max_items: Option<usize>,
+    later_pages: Option<fn(&Q, &mut R)>,
+}
+
+impl<Q: PaginatedRequest, R: Paginated> Paginator<Q, R> {
+    pub(crate) fn new(client: SlackClient, method: impl Into<String>, request: Q) -> Self {
+        Self {
+            config: Some(Box::new(Config {
+                client,
+                method: method.into(),
+                request,
+                page_size: None,
+                max_items: None,
+                later_pages: None,
+            })),
+            items: None,
+            _response: PhantomData,
+        }
+    }
+
+    /// Set the number of items requested per page
+    ///
+    /// Overrides the `limit` of the request. Slack caps most methods at 200
+    /// or 1000 items per page.
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        if let Some(config) = self.config.as_mut() {
+            config.page_size = Some(page_size);
+        }
+        self
+    }
+
+    /// Stop after this many items
+    ///
+    /// The last page is requested with a smaller `limit` when a page size is
+    /// set, so no more items than needed are fetched.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        if let Some(config) = self.config.as_mut() {
+            config.max_items = Some(max_items);
+        }
+        self
+    }
+
+    /// Adjust every page after the first before its items are counted
+    ///
+    /// For methods that repeat items on each page, like the thread parent in
+    /// `conversations.replies`.
+    pub(crate) fn map_later_pages(mut self, f: fn(&Q, &mut R)) -> Self {
+        if let Some(config) = self.config.as_mut() {
+            config.later_pages = Some(f);
+        }
+        self
+    }
+
+    /// Stream whole pages instead of individual items
+    ///
+    /// Useful when the response carries more than the item list.
+    pub fn pages(mut self) -> impl Stream<Item = Result<R>> + Send {
+        match self.config.take() {
+            Some(config) => Self::fetch_pages(*config),
+            None => stream::empty().boxed(),
+        }
+    }
+
+    /// Fetch all remaining items into a `Vec`
+    pub async fn collect_all(self) -> Result<Vec<R::Item>> {
+        self.try_collect().await
+    }
+
+    fn fetch_pages(config: Config<Q, R>) -> BoxStream<'static, Result<R>> {
+        let Config {
+            client,
+            method,
+            mut request,
+            page_size,
+            max_items,
+            later_pages,
+        } = config;
+
+        // Cap each page at the number of items still wanted, if a page size
+        // is known
+        let limit = move |remaining: Option<usize>| match (page_size, remaining) {
+            (Some(size), Some(remaining)) => {
+                Some(size.min(u32::try_from(remaining).unwrap_or(u32::MAX)))
+            }
+            (size, _) => size,
+        };
+
+        if let Some(limit) = limit(max_items) {
+            request.set_limit(limit);
+        }
+
+        let state = Some((client, method, request, max_items, true));
+        stream::unfold(state, move |state| async move {
+            let (client, method, mut request, remaining, first) = state?;
+            if remaining == Some(0) {
+                return None;
+            }
+
+            let mut page: R = match client.post(&method, &request).await {
+                Ok(page) => page,
+                Err(e) => return Some((Err(e), None)),
+            };
+            if let Some(f) = later_pages.filter(|_| !first) {
+                f(&request, &mut page);
+            }
+
+            let remaining = remaining.map(|r| r.saturating_sub(page.items().len()));
+            let next = match page.next_cursor().filter(|cursor| !cursor.is_empty()) {
+                Some(cursor) => {
+                    request.set_cursor(Some(cursor.to_string()));
+                    if let Some(limit) = limit(remaining) {
+                        request.set_limit(limit);
+                    }
+                    Some((client, method, request, remaining, false))
+                }
+                None => None,
+            };
+
+            Some((Ok(page), next))
+        })
+        .boxed()
+    }
+}
+
+impl<Q: PaginatedRequest, R: Paginated> Stream for Paginator<Q, R> {
+    type Item = Result<R::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(config) = this.config.take() {
+            let max_items = config.max_items;
+            let items = Self::fetch_pages(*config).flat_map(|page| match page {
+                Ok(page) => stream::iter(page.into_items().into_iter().map(Ok).collect::<Vec<_>>()),
+                Err(e) => stream::iter(vec![Err(e)]),
+            });
+            this.items = Some(match max_items {
+                Some(max_items) => items.take(max_items).boxed(),
+                None => items.boxed(),
+            });
+        }
+
+        match this.items.as_mut() {
+            Some(items) => items.poll_next_unpin(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthConfig;
+    use crate::middleware::{ApiRequest, ApiResponse, Middleware};
+    use serde::Deserialize;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Serialize)]
+    struct NumbersRequest {
+        limit: Option<u32>,
+        cursor: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct NumbersResponse {
+        numbers: Vec<u32>,
+        response_metadata: Option<crate::types::ResponseMetadata>,
+    }
+
+    impl_paginated!(NumbersRequest, NumbersResponse, numbers: u32);
+
+    /// Cursor and limit of each request seen
+    type RequestLog = Arc<Mutex<Vec<(Option<String>, Option<u64>)>>>;
+
+    /// Serves 1..=10 in pages of `limit` (default 3), recording each request
+    struct Numbers(RequestLog);
+
+    #[async_trait::async_trait]
+    impl Middleware for Numbers {
+        async fn before_request(&self, request: &mut ApiRequest) -> Result<Option<ApiResponse>> {
+            let crate::middleware::RequestBody::Json(body) = &request.body else {
+                panic!("expected a JSON body");
+            };
+            let cursor = body["cursor"].as_str().map(str::to_string);
+            let limit = body["limit"].as_u64();
+            self.0.lock().unwrap().push((cursor.clone(), limit));
+
+            let start: u32 = cursor.as_deref().unwrap_or("1").parse().unwrap();
+            let end = (start + limit.unwrap_or(3) as u32).min(11);
+            let next = if end <= 10 {
+                end.to_string()
+            } else {
+                String::new()
+            };
+
+            Ok(Some(ApiResponse::json(&serde_json::json!({
+                "ok": true,
+                "numbers": (start..end).collect::<Vec<_>>(),
+                "response_metadata": { "next_cursor": next }
+            }))))
+        }
+    }
+
+    fn client() -> (SlackClient, RequestLog) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let client = SlackClient::new(AuthConfig::bot("xoxb-token"))
+            .unwrap()
+            .with_middleware(Numbers(log.clone()));
+        (client, log)
+    }
+
+    fn request() -> NumbersRequest {
+        NumbersRequest {
+            limit: None,
+            cursor: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collects_all_pages() {
+        let (client, log) = client();
+        let numbers = client
+            .paginate::<_, NumbersResponse>("numbers.list", request())
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert_eq!(numbers, (1..=10).collect::<Vec<_>>());
+        assert_eq!(log.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_page_size_and_max_items() {
+        let (client, log) = client();
+        let numbers = client
+            .paginate::<_, NumbersResponse>("numbers.list", request())
+            .page_size(4)
+            .max_items(6)
+            .collect_all()
+            .await
+            .unwrap();
+
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![(None, Some(4)), (Some("5".to_string()), Some(2))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_early_termination_stops_fetching() {
+        let (client, log) = client();
+        let mut stream = client.paginate::<_, NumbersResponse>("numbers.list", request());
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), 1);
+        drop(stream);
+
+        assert_eq!(log.lock().unwrap().len(), 1);
+    }
+}
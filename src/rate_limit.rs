@@ -0,0 +1,581 @@
+//! Rate limit handling for the Slack Web API
+//!
+//! Slack assigns every Web API method to a rate limit tier. This module keeps a
+//! built-in table of those tiers and uses token buckets to pace requests before
+//! they are sent, so bulk jobs stay under the limits instead of bouncing off
+//! HTTP 429 responses. When a 429 does happen, the `Retry-After` header is
+//! honored and the request is retried.
+//!
+//! Rate limiting is opt-in and runs as a [`Middleware`] in the request
+//! pipeline. Enable it with [`SlackClient::with_retry_policy`]:
+//!
+//! ```no_run
+//! use slacko::{AuthConfig, RetryPolicy, SlackClient};
+//!
+//! let client = SlackClient::new(AuthConfig::bot("xoxb-token"))
+//!     .unwrap()
+//!     .with_retry_policy(RetryPolicy::default().max_retries(5));
+//! ```
+//!
+//! [`SlackClient::with_retry_policy`]: crate::SlackClient::with_retry_policy
+
+use crate::error::Result;
+use crate::middleware::{ApiRequest, ApiResponse, Middleware, ResponseAction};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default delay used when a 429 response has no usable `Retry-After` header
+pub(crate) const DEFAULT_RETRY_AFTER_SECS: u64 = 60;
+
+/// Slack rate limit tiers
+///
+/// See <https://api.slack.com/apis/rate-limits> for the published limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitTier {
+    /// Tier 1: 1+ requests per minute
+    Tier1,
+    /// Tier 2: 20+ requests per minute
+    Tier2,
+    /// Tier 3: 50+ requests per minute
+    Tier3,
+    /// Tier 4: 100+ requests per minute
+    Tier4,
+    /// `chat.postMessage`: roughly one message per second per channel
+    PostMessage,
+}
+
+impl RateLimitTier {
+    /// Sustained requests per minute allowed by this tier
+    pub fn requests_per_minute(&self) -> u32 {
+        match self {
+            RateLimitTier::Tier1 => 1,
+            RateLimitTier::Tier2 => 20,
+            RateLimitTier::Tier3 => 50,
+            RateLimitTier::Tier4 => 100,
+            RateLimitTier::PostMessage => 60,
+        }
+    }
+
+    /// Number of requests that may be sent back-to-back before pacing kicks in
+    pub fn burst(&self) -> u32 {
+        match self {
+            RateLimitTier::Tier1 => 1,
+            RateLimitTier::Tier2 => 3,
+            RateLimitTier::Tier3 => 5,
+            RateLimitTier::Tier4 => 10,
+            RateLimitTier::PostMessage => 1,
+        }
+    }
+
+    /// Look up the tier for a Web API method
+    ///
+    /// Methods that are not in the built-in table fall back to Tier 3, which
+    /// is what Slack uses for most read methods.
+    pub fn for_method(method: &str) -> Self {
+        match method {
+            "chat.postMessage" => RateLimitTier::PostMessage,
+
+            // Tier 1
+            "rtm.connect" | "apps.connections.open" | "admin.teams.create" => RateLimitTier::Tier1,
+
+            // Tier 2
+            "conversations.list"
+            | "conversations.create"
+            | "conversations.archive"
+            | "conversations.unarchive"
+            | "conversations.rename"
+            | "conversations.setPurpose"
+            | "conversations.setTopic"
+            | "conversations.close"
+            | "users.list"
+            | "users.setPresence"
+            | "users.setPhoto"
+            | "users.deletePhoto"
+            | "reactions.remove"
+            | "reactions.list"
+            | "pins.add"
+            | "pins.remove"
+            | "pins.list"
+            | "stars.add"
+            | "stars.remove"
+            | "files.upload"
+            | "files.remote.add"
+            | "files.remote.info"
+            | "files.remote.list"
+            | "files.remote.remove"
+            | "files.remote.share"
+            | "files.remote.update"
+            | "search.all"
+            | "search.files"
+            | "search.messages"
+            | "team.accessLogs"
+            | "team.billableInfo"
+            | "team.integrationLogs"
+            | "emoji.list"
+            | "reminders.add"
+            | "reminders.complete"
+            | "reminders.delete"
+            | "reminders.info"
+            | "reminders.list"
+            | "dnd.teamInfo"
+            | "dnd.setSnooze"
+            | "dnd.endSnooze"
+            | "dnd.endDnd"
+            | "usergroups.create"
+            | "usergroups.disable"
+            | "usergroups.enable"
+            | "usergroups.list"
+            | "usergroups.update"
+            | "usergroups.users.list"
+            | "usergroups.users.update"
+            | "bookmarks.add"
+            | "bookmarks.edit"
+            | "bookmarks.list"
+            | "bookmarks.remove"
+            | "calls.add"
+            | "calls.end"
+            | "calls.info"
+            | "calls.update"
+            | "calls.participants.add"
+            | "calls.participants.remove"
+            | "admin.teams.list"
+            | "admin.users.invite"
+            | "admin.users.remove"
+            | "admin.users.setAdmin"
+            | "admin.apps.approve"
+            | "admin.apps.restrict"
+            | "admin.emoji.add"
+            | "admin.emoji.addAlias"
+            | "admin.emoji.list"
+            | "admin.emoji.remove"
+            | "admin.emoji.rename"
+            | "workflows.stepCompleted"
+            | "workflows.stepFailed"
+            | "workflows.updateStep" => RateLimitTier::Tier2,
+
+            // Tier 4
+            "api.test"
+            | "auth.test"
+            | "chat.postEphemeral"
+            | "chat.getPermalink"
+            | "conversations.members"
+            | "users.info"
+            | "users.identity"
+            | "users.profile.get"
+            | "files.info"
+            | "files.getUploadURLExternal"
+            | "files.completeUploadExternal"
+            | "views.open"
+            | "views.publish"
+            | "views.push"
+            | "views.update"
+            | "dialog.open"
+            | "oauth.v2.access"
+            | "oauth.v2.exchange" => RateLimitTier::Tier4,
+
+            // Everything else, including chat.update, chat.delete,
+            // conversations.history/replies/info and users.conversations
+            _ => RateLimitTier::Tier3,
+        }
+    }
+}
+
+/// Retry and pacing policy for rate-limited requests
+///
+/// # Example
+///
+/// ```
+/// use slacko::{RateLimitTier, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::default()
+///     .max_retries(5)
+///     .max_retry_after(Duration::from_secs(120))
+///     .tier("admin.analytics.getFile", RateLimitTier::Tier1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub(crate) max_retries: u32,
+    pub(crate) max_retry_after: Duration,
+    pub(crate) pace_requests: bool,
+    pub(crate) tier_overrides: HashMap<String, RateLimitTier>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            max_retry_after: Duration::from_secs(300),
+            pace_requests: true,
+            tier_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with default settings
+    ///
+    /// Retries up to 3 times, waits at most 5 minutes per retry and paces
+    /// requests using the built-in tier table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of retries after a 429 response
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Give up instead of retrying when Slack asks to wait longer than this
+    pub fn max_retry_after(mut self, max_retry_after: Duration) -> Self {
+        self.max_retry_after = max_retry_after;
+        self
+    }
+
+    /// Enable or disable proactive pacing with token buckets
+    ///
+    /// When disabled, requests are sent immediately and only 429 responses
+    /// cause a delay.
+    pub fn pace_requests(mut self, pace_requests: bool) -> Self {
+        self.pace_requests = pace_requests;
+        self
+    }
+
+    /// Override the tier used for a method
+    pub fn tier(mut self, method: impl Into<String>, tier: RateLimitTier) -> Self {
+        self.tier_overrides.insert(method.into(), tier);
+        self
+    }
+
+    /// Resolve the tier for a method, honoring overrides
+    pub(crate) fn tier_for(&self, method: &str) -> RateLimitTier {
+        self.tier_overrides
+            .get(method)
+            .copied()
+            .unwrap_or_else(|| RateLimitTier::for_method(method))
+    }
+}
+
+/// Token bucket for a single method (or method + channel)
+#[derive(Debug)]
+struct TokenBucket {
+    /// Available tokens; negative when requests are queued
+    tokens: f64,
+    capacity: f64,
+    /// Tokens added per second
+    refill_rate: f64,
+    last_refill: Instant,
+    /// No requests may be sent before this instant (set after a 429)
+    blocked_until: Option<Instant>,
+}
+
+impl TokenBucket {
+    fn new(tier: RateLimitTier, now: Instant) -> Self {
+        let capacity = tier.burst() as f64;
+        Self {
+            tokens: capacity,
+            capacity,
+            refill_rate: tier.requests_per_minute() as f64 / 60.0,
+            last_refill: now,
+            blocked_until: None,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Reserve one token and return how long the caller has to wait for it
+    fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= 1.0;
+
+        let mut wait = if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.refill_rate)
+        };
+
+        if let Some(until) = self.blocked_until {
+            wait = wait.max(until.saturating_duration_since(now));
+        }
+
+        wait
+    }
+}
+
+/// Shared rate limiter state attached to a [`SlackClient`](crate::SlackClient)
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    policy: RetryPolicy,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Bucket key for a request; `chat.postMessage` is limited per channel
+    fn bucket_key(&self, method: &str, channel: Option<&str>) -> String {
+        match (self.policy.tier_for(method), channel) {
+            (RateLimitTier::PostMessage, Some(channel)) => format!("{}:{}", method, channel),
+            _ => method.to_string(),
+        }
+    }
+
+    /// Compute the delay before a request may be sent, reserving its slot
+    fn reserve(&self, method: &str, channel: Option<&str>, now: Instant) -> Duration {
+        let tier = self.policy.tier_for(method);
+        let key = self.bucket_key(method, channel);
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+        buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(tier, now))
+            .reserve(now)
+    }
+
+    /// Wait until a request for `method` may be sent
+    async fn acquire(&self, method: &str, channel: Option<&str>) {
+        if !self.policy.pace_requests {
+            return;
+        }
+
+        let wait = self.reserve(method, channel, Instant::now());
+        if !wait.is_zero() {
+            tracing::debug!("Pacing {} for {:?}", method, wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Block a method's bucket after Slack returned 429 for it
+    fn penalize(&self, method: &str, channel: Option<&str>, retry_after: Duration) {
+        let tier = self.policy.tier_for(method);
+        let key = self.bucket_key(method, channel);
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+        let bucket = buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(tier, now));
+        bucket.tokens = bucket.tokens.min(0.0);
+        bucket.blocked_until = Some(now + retry_after);
+    }
+}
+
+/// Rate limit retries of one call, kept across attempts
+///
+/// Other middleware may retry the call too, so [`ApiRequest::attempt`] can't
+/// be used to enforce [`RetryPolicy::max_retries`].
+#[derive(Clone, Default)]
+struct Retries(Arc<RetryState>);
+
+#[derive(Default)]
+struct RetryState {
+    count: AtomicU32,
+    /// The next attempt follows a `Retry-After` wait
+    waited: AtomicBool,
+}
+
+#[async_trait]
+impl Middleware for RateLimiter {
+    async fn before_request(&self, request: &mut ApiRequest) -> Result<Option<ApiResponse>> {
+        let retries = request.extensions.get_or_insert_default::<Retries>();
+
+        // The `Retry-After` wait stands in for this attempt's slot; reserving
+        // one too would wait for the blocked bucket to refill on top of it
+        if !retries.0.waited.swap(false, Ordering::SeqCst) {
+            self.acquire(&request.method, request.param("channel"))
+                .await;
+        }
+        Ok(None)
+    }
+
+    async fn after_response(
+        &self,
+        request: &ApiRequest,
+        response: &mut ApiResponse,
+    ) -> Result<ResponseAction> {
+        if response.status != 429 {
+            return Ok(ResponseAction::Continue);
+        }
+
+        let retry_after = retry_after_secs(&response.headers);
+        let delay = Duration::from_secs(retry_after);
+
+        let retries = request
+            .extensions
+            .get::<Retries>()
+            .cloned()
+            .unwrap_or_default();
+        let count = retries.0.count.load(Ordering::SeqCst);
+        if count >= self.policy.max_retries || delay > self.policy.max_retry_after {
+            return Ok(ResponseAction::Continue);
+        }
+
+        tracing::warn!(
+            "Rate limited on {}, retrying in {}s (attempt {}/{})",
+            request.method,
+            retry_after,
+            count + 1,
+            self.policy.max_retries
+        );
+
+        let channel = request.param("channel");
+        self.penalize(&request.method, channel, delay);
+        tokio::time::sleep(delay).await;
+
+        retries.0.count.fetch_add(1, Ordering::SeqCst);
+        retries.0.waited.store(true, Ordering::SeqCst);
+        Ok(ResponseAction::Retry)
+    }
+}
+
+/// Parse the `Retry-After` header of a 429 response, in seconds
+pub(crate) fn retry_after_secs(headers: &reqwest::header::HeaderMap) -> u64 {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_RETRY_AFTER_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tier_lookup() {
+        assert_eq!(
+            RateLimitTier::for_method("chat.postMessage"),
+            RateLimitTier::PostMessage
+        );
+        assert_eq!(
+            RateLimitTier::for_method("conversations.list"),
+            RateLimitTier::Tier2
+        );
+        assert_eq!(
+            RateLimitTier::for_method("users.info"),
+            RateLimitTier::Tier4
+        );
+        assert_eq!(
+            RateLimitTier::for_method("rtm.connect"),
+            RateLimitTier::Tier1
+        );
+        assert_eq!(
+            RateLimitTier::for_method("some.unknown.method"),
+            RateLimitTier::Tier3
+        );
+    }
+
+    #[test]
+    fn test_tier_override() {
+        let policy = RetryPolicy::default().tier("users.info", RateLimitTier::Tier1);
+        assert_eq!(policy.tier_for("users.info"), RateLimitTier::Tier1);
+        assert_eq!(policy.tier_for("users.list"), RateLimitTier::Tier2);
+    }
+
+    #[test]
+    fn test_bucket_paces_after_burst() {
+        let limiter = RateLimiter::new(RetryPolicy::default());
+        let now = Instant::now();
+
+        // Tier 2 allows a burst of 3, then one request every 3 seconds
+        for _ in 0..3 {
+            assert_eq!(limiter.reserve("users.list", None, now), Duration::ZERO);
+        }
+        let wait = limiter.reserve("users.list", None, now);
+        assert!((wait.as_secs_f64() - 3.0).abs() < 0.01);
+
+        let wait = limiter.reserve("users.list", None, now);
+        assert!((wait.as_secs_f64() - 6.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_post_message_is_per_channel() {
+        let limiter = RateLimiter::new(RetryPolicy::default());
+        let now = Instant::now();
+
+        assert_eq!(
+            limiter.reserve("chat.postMessage", Some("C1"), now),
+            Duration::ZERO
+        );
+        assert_eq!(
+            limiter.reserve("chat.postMessage", Some("C2"), now),
+            Duration::ZERO
+        );
+        assert!(!limiter
+            .reserve("chat.postMessage", Some("C1"), now)
+            .is_zero());
+    }
+
+    #[test]
+    fn test_penalize_blocks_bucket() {
+        let limiter = RateLimiter::new(RetryPolicy::default());
+        limiter.penalize("users.info", None, Duration::from_secs(30));
+
+        let wait = limiter.reserve("users.info", None, Instant::now());
+        assert!(wait > Duration::from_secs(29));
+    }
+
+    #[tokio::test]
+    async fn test_retries_counted_separately() {
+        let limiter = RateLimiter::new(RetryPolicy::default().max_retries(1));
+        let mut request = ApiRequest {
+            method: "users.info".to_string(),
+            url: "https://slack.com/api/users.info".to_string(),
+            headers: Default::default(),
+            body: crate::middleware::RequestBody::query([("user", "U1")]),
+            // Retries made by other middleware don't count
+            attempt: 3,
+            extensions: Default::default(),
+        };
+        let mut limited = ApiResponse::json(&serde_json::json!({"ok": false}));
+        limited.status = 429;
+        limited.headers.insert(
+            reqwest::header::RETRY_AFTER,
+            reqwest::header::HeaderValue::from_static("0"),
+        );
+
+        limiter.before_request(&mut request).await.unwrap();
+        let action = limiter
+            .after_response(&request, &mut limited.clone())
+            .await
+            .unwrap();
+        assert_eq!(action, ResponseAction::Retry);
+
+        // The retry doesn't take a token from the bucket blocked by the 429
+        let tokens = |limiter: &RateLimiter| limiter.buckets.lock().unwrap()["users.info"].tokens;
+        let before = tokens(&limiter);
+        limiter.before_request(&mut request).await.unwrap();
+        assert!(tokens(&limiter) >= before);
+
+        let action = limiter
+            .after_response(&request, &mut limited)
+            .await
+            .unwrap();
+        assert_eq!(action, ResponseAction::Continue);
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after_secs(&headers), DEFAULT_RETRY_AFTER_SECS);
+
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            reqwest::header::HeaderValue::from_static("12"),
+        );
+        assert_eq!(retry_after_secs(&headers), 12);
+    }
+}
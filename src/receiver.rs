@@ -0,0 +1,377 @@
+//! HTTP receiver for the Events API, interactivity and slash commands
+//!
+//! Enabled with the `receiver` cargo feature. [`HttpReceiver`] serves one
+//! request URL for everything Slack delivers over HTTP: the
+//! `url_verification` challenge, Events API callbacks, interactive payloads
+//! (including `block_suggestion` options requests) and slash commands. Every
+//! request's signature is checked with a [`SignatureVerifier`].
+//!
+//! Payloads are handed to the same handler signature as
+//! [`SocketModeApi::start`], so an app can switch between Socket Mode and
+//! HTTP with [`Transport`] alone:
+//!
+//! - Events API callbacks are acknowledged right away and handled in the
+//!   background; the handler's return value is ignored
+//! - For interactive payloads and slash commands, the handler's return value
+//!   is sent as the response body, if it is ready within the ack timeout
+//!   (default: 2.5 seconds, within Slack's 3-second window); otherwise the
+//!   request is acknowledged with an empty body
+//!
+//! Redeliveries carry `X-Slack-Retry-Num` and `X-Slack-Retry-Reason`, which
+//! are passed on as [`SocketModeEvent::retry_attempt`] and
+//! [`SocketModeEvent::retry_reason`]; with
+//! [`ignore_retries`](HttpReceiver::ignore_retries) they are acknowledged
+//! without calling the handler.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use slacko::receiver::{HttpReceiver, Transport};
+//! use slacko::{AuthConfig, SlackClient};
+//!
+//! # async fn example() -> slacko::Result<()> {
+//! let client = SlackClient::new(AuthConfig::app("xapp-token").with_bot_token("xoxb-token"))?;
+//!
+//! let transport = if std::env::var("SLACK_SIGNING_SECRET").is_ok() {
+//!     Transport::Http {
+//!         receiver: HttpReceiver::new(std::env::var("SLACK_SIGNING_SECRET").unwrap()),
+//!         addr: "0.0.0.0:3000".parse().unwrap(),
+//!     }
+//! } else {
+//!     Transport::SocketMode
+//! };
+//!
+//! transport
+//!     .run(&client, |event| {
+//!         println!("Received: {:?}", event.payload);
+//!         None
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`SocketModeApi::start`]: crate::api::socket_mode::SocketModeApi::start
+
+use crate::api::socket_mode::{Acker, SocketModeEvent, SocketModeEventType, SocketModePayload};
+use crate::client::SlackClient;
+use crate::error::{Result, SlackError};
+use crate::server;
+use crate::signature::{HttpPayload, SignatureVerifier};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{HeaderMap, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+/// Default request path
+const DEFAULT_PATH: &str = "/slack/events";
+
+/// Default time to wait for a handler's response payload
+const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_millis(2500);
+
+/// Largest request body accepted; Slack's payloads are far smaller
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+type Handler = Arc<dyn Fn(SocketModeEvent) -> Option<Value> + Send + Sync>;
+
+/// How an app receives events
+#[derive(Debug)]
+pub enum Transport {
+    /// Socket Mode, with automatic reconnection; needs an app token
+    SocketMode,
+    /// HTTP request URL served on `addr`
+    Http {
+        receiver: HttpReceiver,
+        addr: SocketAddr,
+    },
+}
+
+impl Transport {
+    /// Receive events and pass them to `handler` until the transport stops
+    pub async fn run<F>(self, client: &SlackClient, handler: F) -> Result<()>
+    where
+        F: Fn(SocketModeEvent) -> Option<Value> + Send + Sync + 'static,
+    {
+        match self {
+            Self::SocketMode => client.socket_mode().start_with_reconnect(handler).await,
+            Self::Http { receiver, addr } => receiver.run(addr, handler).await,
+        }
+    }
+}
+
+/// HTTP server for Slack's request URLs
+#[derive(Debug, Clone)]
+pub struct HttpReceiver {
+    verifier: SignatureVerifier,
+    path: String,
+    ack_timeout: Duration,
+    ignore_retries: bool,
+}
+
+impl HttpReceiver {
+    /// Receive requests signed with this signing secret
+    pub fn new(signing_secret: impl AsRef<[u8]>) -> Self {
+        Self::with_verifier(SignatureVerifier::new(signing_secret))
+    }
+
+    /// Receive requests checked by `verifier`, e.g. one with several secrets
+    pub fn with_verifier(verifier: SignatureVerifier) -> Self {
+        Self {
+            verifier,
+            path: DEFAULT_PATH.to_string(),
+            ack_timeout: DEFAULT_ACK_TIMEOUT,
+            ignore_retries: false,
+        }
+    }
+
+    /// Path of the request URL (default: `/slack/events`)
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// How long to wait for a response payload before acknowledging without one
+    pub fn ack_timeout(mut self, ack_timeout: Duration) -> Self {
+        self.ack_timeout = ack_timeout;
+        self
+    }
+
+    /// Acknowledge redeliveries without calling the handler
+    pub fn ignore_retries(mut self, ignore_retries: bool) -> Self {
+        self.ignore_retries = ignore_retries;
+        self
+    }
+
+    /// Serve on `addr`; only returns if the address cannot be bound
+    pub async fn run<F>(self, addr: impl ToSocketAddrs, handler: F) -> Result<()>
+    where
+        F: Fn(SocketModeEvent) -> Option<Value> + Send + Sync + 'static,
+    {
+        let listener = bind(addr).await?;
+        accept(listener, self.shared(handler)).await;
+        Ok(())
+    }
+
+    /// Serve on `addr` on a background task
+    ///
+    /// The server runs until the returned [`ReceiverServer`] is dropped.
+    pub async fn serve<F>(self, addr: impl ToSocketAddrs, handler: F) -> Result<ReceiverServer>
+    where
+        F: Fn(SocketModeEvent) -> Option<Value> + Send + Sync + 'static,
+    {
+        let listener = bind(addr).await?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| SlackError::config_error(e.to_string()))?;
+        let server = tokio::spawn(accept(listener, self.shared(handler)));
+
+        Ok(ReceiverServer { local_addr, server })
+    }
+
+    fn shared<F>(self, handler: F) -> Arc<Shared>
+    where
+        F: Fn(SocketModeEvent) -> Option<Value> + Send + Sync + 'static,
+    {
+        Arc::new(Shared {
+            receiver: self,
+            handler: Arc::new(handler),
+            next_id: AtomicU64::new(1),
+        })
+    }
+}
+
+/// A running [`HttpReceiver`], stopped when dropped
+#[derive(Debug)]
+pub struct ReceiverServer {
+    local_addr: SocketAddr,
+    server: JoinHandle<()>,
+}
+
+impl ReceiverServer {
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for ReceiverServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+struct Shared {
+    receiver: HttpReceiver,
+    handler: Handler,
+    next_id: AtomicU64,
+}
+
+async fn bind(addr: impl ToSocketAddrs) -> Result<TcpListener> {
+    TcpListener::bind(addr)
+        .await
+        .map_err(|e| SlackError::config_error(format!("Failed to bind receiver: {}", e)))
+}
+
+async fn accept(listener: TcpListener, shared: Arc<Shared>) {
+    loop {
+        let stream = server::accept(&listener).await;
+
+        let shared = shared.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| handle(request, shared.clone()));
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    shared: Arc<Shared>,
+) -> std::result::Result<Response<Full<Bytes>>, Infallible> {
+    if request.uri().path() != shared.receiver.path {
+        return Ok(empty(StatusCode::NOT_FOUND));
+    }
+    if request.method() != hyper::Method::POST {
+        return Ok(empty(StatusCode::METHOD_NOT_ALLOWED));
+    }
+
+    let (parts, body) = request.into_parts();
+    let body = match Limited::new(body, MAX_BODY_BYTES).collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(e) if e.is::<LengthLimitError>() => {
+            return Ok(empty(StatusCode::PAYLOAD_TOO_LARGE));
+        }
+        Err(_) => return Ok(empty(StatusCode::BAD_REQUEST)),
+    };
+
+    let payload = match shared
+        .receiver
+        .verifier
+        .verify_and_parse(&parts.headers, &body)
+    {
+        Ok(payload) => payload,
+        Err(e @ SlackError::AuthError { .. }) => {
+            tracing::warn!("Rejected Slack request: {}", e);
+            return Ok(empty(StatusCode::UNAUTHORIZED));
+        }
+        Err(e) => {
+            tracing::warn!("Failed to parse Slack request: {}", e);
+            return Ok(empty(StatusCode::BAD_REQUEST));
+        }
+    };
+
+    Ok(dispatch(&shared, payload, &parts.headers).await)
+}
+
+/// Hand a verified payload to the handler and build the acknowledgment
+async fn dispatch(
+    shared: &Shared,
+    payload: HttpPayload,
+    headers: &HeaderMap,
+) -> Response<Full<Bytes>> {
+    let (envelope_type, accepts_response_payload, payload, envelope_id) = match payload {
+        HttpPayload::UrlVerification { challenge } => {
+            return text(StatusCode::OK, "text/plain", challenge);
+        }
+        HttpPayload::EventsApi(events) => {
+            let id = events.event_id.clone();
+            (
+                SocketModeEventType::EventsApi,
+                false,
+                SocketModePayload::EventsApi(events),
+                id,
+            )
+        }
+        HttpPayload::Interactive(interactive) => (
+            SocketModeEventType::Interactive,
+            true,
+            SocketModePayload::Interactive(interactive),
+            None,
+        ),
+        HttpPayload::SlashCommand(command) => (
+            SocketModeEventType::SlashCommands,
+            true,
+            SocketModePayload::SlashCommand(command),
+            None,
+        ),
+        HttpPayload::Raw(value) => {
+            let kind = value["type"].as_str().unwrap_or_default().to_string();
+            (
+                SocketModeEventType::Unknown(kind),
+                false,
+                SocketModePayload::Raw(value),
+                None,
+            )
+        }
+    };
+
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let retry_attempt = header("x-slack-retry-num").and_then(|n| n.parse().ok());
+    let retry_reason = header("x-slack-retry-reason").map(str::to_string);
+    if retry_attempt.is_some() && shared.receiver.ignore_retries {
+        tracing::debug!(
+            ?retry_attempt,
+            ?retry_reason,
+            "Ignoring redelivered request"
+        );
+        return empty(StatusCode::OK);
+    }
+
+    let event = SocketModeEvent {
+        envelope_id: envelope_id
+            .unwrap_or_else(|| format!("http-{}", shared.next_id.fetch_add(1, Ordering::Relaxed))),
+        envelope_type,
+        accepts_response_payload,
+        payload,
+        retry_attempt,
+        retry_reason,
+        acker: Acker::detached(),
+    };
+
+    // Handlers are synchronous and may block, so they run off the runtime
+    let handler = shared.handler.clone();
+    let task = tokio::task::spawn_blocking(move || handler(event));
+    if !accepts_response_payload {
+        return empty(StatusCode::OK);
+    }
+
+    match tokio::time::timeout(shared.receiver.ack_timeout, task).await {
+        Ok(Ok(Some(response))) => text(StatusCode::OK, "application/json", response.to_string()),
+        Ok(Ok(None)) => empty(StatusCode::OK),
+        Ok(Err(e)) => {
+            tracing::error!("Slack request handler panicked: {}", e);
+            empty(StatusCode::OK)
+        }
+        Err(_) => {
+            tracing::warn!("Handler missed the ack timeout; acknowledged without a response");
+            empty(StatusCode::OK)
+        }
+    }
+}
+
+fn empty(status: StatusCode) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .body(Full::new(Bytes::new()))
+        .expect("empty response is valid")
+}
+
+fn text(status: StatusCode, content_type: &str, body: String) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, content_type)
+        .body(Full::new(Bytes::from(body)))
+        .expect("text response is valid")
+}
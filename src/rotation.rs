@@ -0,0 +1,306 @@
+//! Automatic OAuth token rotation
+//!
+//! Apps with token rotation enabled get access tokens that expire after 12
+//! hours, plus a refresh token to get the next pair. [`TokenRotation`] keeps
+//! the client's token fresh: it refreshes shortly before the token expires,
+//! and refreshes and retries when a request fails with `token_expired`.
+//! Concurrent requests share a single refresh, and every new token pair is
+//! passed to a callback so it can be persisted.
+//!
+//! Rotation runs as a [`Middleware`] and is enabled with
+//! [`SlackClientBuilder::token_rotation`]:
+//!
+//! ```no_run
+//! use slacko::rotation::TokenRotation;
+//! use slacko::{AuthConfig, SlackClient};
+//! use std::time::Duration;
+//!
+//! let rotation = TokenRotation::new("client-id", "client-secret", "xoxe-1-refresh")
+//!     .expires_in(Duration::from_secs(43200))
+//!     .on_refresh(|tokens| {
+//!         // Store tokens.access_token and tokens.refresh_token somewhere safe
+//!         println!("Token rotated, expires at {:?}", tokens.expires_at);
+//!     });
+//!
+//! let client = SlackClient::builder(AuthConfig::bot("xoxe.xoxb-1-access"))
+//!     .token_rotation(rotation)
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
+//! [`SlackClientBuilder::token_rotation`]: crate::SlackClientBuilder::token_rotation
+
+use crate::client::SlackClient;
+use crate::credentials::SharedAuth;
+use crate::error::{Result, SlackError};
+use crate::middleware::{ApiRequest, ApiResponse, Middleware, ResponseAction};
+use async_trait::async_trait;
+use futures_util::future::BoxFuture;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Default time before expiry at which the token is refreshed
+const DEFAULT_REFRESH_BEFORE: Duration = Duration::from_secs(5 * 60);
+
+/// A token pair returned by a refresh
+#[derive(Debug, Clone)]
+pub struct RotatedTokens {
+    /// The new access token
+    pub access_token: String,
+    /// The refresh token to use for the next refresh
+    pub refresh_token: String,
+    /// When `access_token` expires, if Slack said
+    pub expires_at: Option<SystemTime>,
+}
+
+type RefreshCallback = Arc<dyn Fn(&RotatedTokens) + Send + Sync>;
+
+type AsyncRefreshCallback =
+    Arc<dyn Fn(RotatedTokens) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Token rotation settings
+///
+/// The client's [`AuthConfig`](crate::AuthConfig) holds the current access
+/// token; this holds what is needed to get the next one.
+#[derive(Clone)]
+pub struct TokenRotation {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    expires_at: Option<SystemTime>,
+    refresh_before: Duration,
+    on_refresh: Option<RefreshCallback>,
+    on_refresh_async: Option<AsyncRefreshCallback>,
+}
+
+impl fmt::Debug for TokenRotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenRotation")
+            .field("client_id", &self.client_id)
+            .field("expires_at", &self.expires_at)
+            .field("refresh_before", &self.refresh_before)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenRotation {
+    /// Rotate tokens with the given app credentials and refresh token
+    ///
+    /// Without an expiry time, the token is only refreshed after a request
+    /// fails with `token_expired`.
+    pub fn new(
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+        refresh_token: impl Into<String>,
+    ) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            refresh_token: refresh_token.into(),
+            expires_at: None,
+            refresh_before: DEFAULT_REFRESH_BEFORE,
+            on_refresh: None,
+            on_refresh_async: None,
+        }
+    }
+
+    /// Set the lifetime of the current access token, as in `expires_in`
+    pub fn expires_in(mut self, expires_in: Duration) -> Self {
+        self.expires_at = Some(SystemTime::now() + expires_in);
+        self
+    }
+
+    /// Set when the current access token expires, e.g. from stored tokens
+    pub fn expires_at(mut self, expires_at: SystemTime) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Refresh this long before the token expires (default: 5 minutes)
+    pub fn refresh_before(mut self, refresh_before: Duration) -> Self {
+        self.refresh_before = refresh_before;
+        self
+    }
+
+    /// Call `callback` with every new token pair
+    ///
+    /// The previous refresh token stops working after a refresh, so the new
+    /// pair must be stored to survive a restart.
+    pub fn on_refresh(mut self, callback: impl Fn(&RotatedTokens) + Send + Sync + 'static) -> Self {
+        self.on_refresh = Some(Arc::new(callback));
+        self
+    }
+
+    /// Await `callback` with every new token pair, e.g. to save it to a database
+    ///
+    /// Refreshes wait for the callback, so token pairs are stored in the order
+    /// they were issued. An error is returned from the request that triggered
+    /// the refresh; the client keeps using the new token.
+    pub fn on_refresh_async<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn(RotatedTokens) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        self.on_refresh_async = Some(Arc::new(move |tokens| Box::pin(callback(tokens))));
+        self
+    }
+
+    /// Create the middleware; `oauth` is used for the refresh calls
+    pub(crate) fn into_middleware(self, oauth: SlackClient, auth: SharedAuth) -> Rotator {
+        Rotator {
+            state: tokio::sync::Mutex::new(State {
+                refresh_token: self.refresh_token.clone(),
+                expires_at: self.expires_at,
+            }),
+            settings: self,
+            oauth,
+            auth,
+        }
+    }
+}
+
+/// Refresh token and expiry of the current access token
+struct State {
+    refresh_token: String,
+    expires_at: Option<SystemTime>,
+}
+
+/// Middleware that keeps the client's access token fresh
+pub(crate) struct Rotator {
+    settings: TokenRotation,
+    state: tokio::sync::Mutex<State>,
+    oauth: SlackClient,
+    auth: SharedAuth,
+}
+
+impl Rotator {
+    fn authorization(&self, method: &str) -> Option<HeaderValue> {
+        self.auth
+            .read()
+            .unwrap()
+            .build_headers_for(method)
+            .get(AUTHORIZATION)
+            .cloned()
+    }
+
+    fn is_due(&self, state: &State) -> bool {
+        state.expires_at.is_some_and(|expires_at| {
+            SystemTime::now() + self.settings.refresh_before >= expires_at
+        })
+    }
+
+    /// Refresh the token unless `needed` says another task already did
+    ///
+    /// The state lock is held across the refresh call, so concurrent
+    /// requests wait for one refresh instead of each starting their own.
+    async fn refresh(&self, needed: impl FnOnce(&State) -> bool) -> Result<()> {
+        let mut state = self.state.lock().await;
+        if !needed(&state) {
+            return Ok(());
+        }
+
+        let response = self
+            .oauth
+            .oauth()
+            .refresh(
+                &state.refresh_token,
+                &self.settings.client_id,
+                &self.settings.client_secret,
+            )
+            .await?;
+        let refresh_token = response.refresh_token.ok_or_else(|| {
+            SlackError::Other("oauth.v2.access returned no refresh token".to_string())
+        })?;
+
+        let tokens = RotatedTokens {
+            access_token: response.access_token,
+            refresh_token,
+            expires_at: response
+                .expires_in
+                .map(|secs| SystemTime::now() + Duration::from_secs(secs)),
+        };
+
+        self.auth
+            .write()
+            .unwrap()
+            .replace_token(tokens.access_token.clone());
+        state.refresh_token = tokens.refresh_token.clone();
+        state.expires_at = tokens.expires_at;
+        tracing::debug!("Rotated access token, expires at {:?}", tokens.expires_at);
+
+        if let Some(callback) = &self.settings.on_refresh {
+            callback(&tokens);
+        }
+        // Still under the state lock, so the next refresh waits for this one
+        if let Some(callback) = &self.settings.on_refresh_async {
+            callback(tokens).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Set once a call has been retried with a rotated token
+#[derive(Clone, Default)]
+struct Rotated(Arc<AtomicBool>);
+
+#[async_trait]
+impl Middleware for Rotator {
+    async fn before_request(&self, request: &mut ApiRequest) -> Result<Option<ApiResponse>> {
+        request.extensions.get_or_insert_default::<Rotated>();
+
+        let due = self.is_due(&*self.state.lock().await);
+
+        // The current token may still work, so a failed refresh is not fatal
+        // here; a `token_expired` response will trigger another attempt
+        if due {
+            if let Err(e) = self.refresh(|state| self.is_due(state)).await {
+                tracing::warn!(method = %request.method, "Failed to refresh access token: {}", e);
+            }
+        }
+
+        // The headers were built before this hook ran, possibly before a
+        // refresh made by another request
+        if let Some(authorization) = self.authorization(&request.method) {
+            request.headers.insert(AUTHORIZATION, authorization);
+        }
+
+        Ok(None)
+    }
+
+    async fn after_response(
+        &self,
+        request: &ApiRequest,
+        response: &mut ApiResponse,
+    ) -> Result<ResponseAction> {
+        let expired = response
+            .body_json()
+            .is_ok_and(|body| body["error"] == "token_expired");
+        if !expired {
+            return Ok(ResponseAction::Continue);
+        }
+
+        // A freshly rotated token that is rejected too won't get another one
+        let rotated = request.extensions.get::<Rotated>();
+        if rotated.is_some_and(|rotated| rotated.0.load(Ordering::SeqCst)) {
+            return Ok(ResponseAction::Continue);
+        }
+
+        let used = request.headers.get(AUTHORIZATION).cloned();
+        self.refresh(|_| self.authorization(&request.method) == used)
+            .await?;
+
+        if self.authorization(&request.method) != used {
+            tracing::debug!(method = %request.method, "Retrying with rotated access token");
+            if let Some(rotated) = rotated {
+                rotated.0.store(true, Ordering::SeqCst);
+            }
+            Ok(ResponseAction::Retry)
+        } else {
+            Ok(ResponseAction::Continue)
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! Pieces shared by the built-in HTTP servers
+
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+
+/// Pause after a failed `accept`, so errors like running out of file
+/// descriptors don't spin the loop
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Accept the next connection, backing off after errors
+pub(crate) async fn accept(listener: &TcpListener) -> TcpStream {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => return stream,
+            Err(e) => {
+                tracing::warn!("Failed to accept connection: {}", e);
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Request signature verification for HTTP-delivered payloads
+//!
+//! Slack signs every request it sends to an app's Events API, interactivity
+//! and slash command URLs: `X-Slack-Signature` is an HMAC-SHA256 of
+//! `v0:{timestamp}:{body}` keyed with the app's signing secret, and
+//! `X-Slack-Request-Timestamp` is when it was sent. [`SignatureVerifier`]
+//! checks both without depending on any web framework, then
+//! [`HttpPayload::parse`] turns the verified body into the same payload types
+//! Socket Mode delivers.
+//!
+//! ```no_run
+//! use slacko::signature::{HttpPayload, SignatureVerifier};
+//!
+//! # fn handle(headers: &reqwest::header::HeaderMap, body: &[u8]) -> slacko::Result<()> {
+//! let verifier = SignatureVerifier::new("signing-secret");
+//!
+//! match verifier.verify_and_parse(headers, body)? {
+//!     HttpPayload::UrlVerification { challenge } => { /* respond with the challenge */ }
+//!     HttpPayload::EventsApi(event) => println!("Event: {:?}", event.event),
+//!     HttpPayload::Interactive(interaction) => println!("Action: {}", interaction.interaction_type()),
+//!     HttpPayload::SlashCommand(command) => println!("Command: {}", command.command),
+//!     HttpPayload::Raw(value) => println!("Other: {}", value),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::socket_mode::{EventsApiPayload, InteractivePayload, SlashCommandPayload};
+use crate::error::{Result, SlackError};
+use reqwest::header::HeaderMap;
+use ring::hmac;
+use serde_json::{Map, Value};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Header carrying the request signature
+pub const SIGNATURE_HEADER: &str = "x-slack-signature";
+
+/// Header carrying the request timestamp
+pub const TIMESTAMP_HEADER: &str = "x-slack-request-timestamp";
+
+/// Default maximum age of a request
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
+fn rejected(message: impl Into<String>) -> SlackError {
+    SlackError::AuthError {
+        message: format!("Invalid request signature: {}", message.into()),
+        method: None,
+        code: None,
+    }
+}
+
+/// Verifies `X-Slack-Signature` on incoming requests
+///
+/// Requests older than five minutes are rejected to prevent replays. While
+/// rotating the signing secret, add the old and new secrets so requests
+/// signed with either are accepted.
+#[derive(Clone)]
+pub struct SignatureVerifier {
+    keys: Vec<hmac::Key>,
+    max_age: Duration,
+}
+
+impl std::fmt::Debug for SignatureVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignatureVerifier")
+            .field("secrets", &self.keys.len())
+            .field("max_age", &self.max_age)
+            .finish()
+    }
+}
+
+impl SignatureVerifier {
+    /// Verify requests signed with this signing secret
+    pub fn new(signing_secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            keys: vec![hmac::Key::new(hmac::HMAC_SHA256, signing_secret.as_ref())],
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+
+    /// Also accept requests signed with this secret, e.g. during rotation
+    pub fn add_secret(mut self, signing_secret: impl AsRef<[u8]>) -> Self {
+        self.keys
+            .push(hmac::Key::new(hmac::HMAC_SHA256, signing_secret.as_ref()));
+        self
+    }
+
+    /// Reject requests whose timestamp is further than this from now (default: 5 minutes)
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Verify a request from its timestamp and signature header values
+    ///
+    /// Fails with [`SlackError::AuthError`] if the timestamp is too old or
+    /// too far in the future, or if the signature matches none of the
+    /// secrets. Signatures are compared in constant time.
+    pub fn verify(&self, timestamp: &str, signature: &str, body: &[u8]) -> Result<()> {
+        let sent: i64 = timestamp
+            .trim()
+            .parse()
+            .map_err(|_| rejected(format!("bad timestamp {:?}", timestamp)))?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        if now.abs_diff(sent) > self.max_age.as_secs() {
+            return Err(rejected("timestamp is too old or in the future"));
+        }
+
+        let tag = signature
+            .trim()
+            .strip_prefix("v0=")
+            .and_then(decode_hex)
+            .ok_or_else(|| rejected("expected v0= and a hex digest"))?;
+
+        let message = signed_message(timestamp.trim(), body);
+        if self
+            .keys
+            .iter()
+            .any(|key| hmac::verify(key, &message, &tag).is_ok())
+        {
+            Ok(())
+        } else {
+            Err(rejected("signature does not match"))
+        }
+    }
+
+    /// Verify a request from its headers
+    pub fn verify_headers(&self, headers: &HeaderMap, body: &[u8]) -> Result<()> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| rejected(format!("missing {} header", name)))
+        };
+        self.verify(header(TIMESTAMP_HEADER)?, header(SIGNATURE_HEADER)?, body)
+    }
+
+    /// Verify a request from its headers and parse the body
+    pub fn verify_and_parse(&self, headers: &HeaderMap, body: &[u8]) -> Result<HttpPayload> {
+        self.verify_headers(headers, body)?;
+        HttpPayload::parse(body)
+    }
+
+    /// Compute the `X-Slack-Signature` value for a body, e.g. for tests
+    ///
+    /// Uses the first secret.
+    pub fn sign(&self, timestamp: &str, body: &[u8]) -> String {
+        let tag = hmac::sign(&self.keys[0], &signed_message(timestamp, body));
+        let hex: String = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+        format!("v0={}", hex)
+    }
+}
+
+fn signed_message(timestamp: &str, body: &[u8]) -> Vec<u8> {
+    let mut message = format!("v0:{}:", timestamp).into_bytes();
+    message.extend_from_slice(body);
+    message
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A payload delivered to an app's request URL
+#[derive(Debug, Clone)]
+pub enum HttpPayload {
+    /// The challenge Slack sends when the Events API URL is set; respond
+    /// with `challenge` as the body
+    UrlVerification { challenge: String },
+    /// Events API callback
+    EventsApi(EventsApiPayload),
+    /// Interactive component payload, including `block_suggestion`
+    Interactive(InteractivePayload),
+    /// Slash command payload
+    SlashCommand(SlashCommandPayload),
+    /// Any other JSON payload, e.g. `app_rate_limited`
+    Raw(Value),
+}
+
+impl HttpPayload {
+    /// Parse a request body
+    ///
+    /// Events API requests are JSON; interactivity requests are form-encoded
+    /// with the JSON in a `payload` field; slash commands are form-encoded
+    /// fields. Verify the signature first, as this trusts its input.
+    pub fn parse(body: &[u8]) -> Result<Self> {
+        let is_json = body
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|&b| b == b'{');
+        if is_json {
+            let value: Value = serde_json::from_slice(body)?;
+            return Ok(match value["type"].as_str() {
+                Some("url_verification") => Self::UrlVerification {
+                    challenge: value["challenge"].as_str().unwrap_or_default().to_string(),
+                },
+                Some("event_callback") => Self::EventsApi(serde_json::from_value(value)?),
+                _ => Self::Raw(value),
+            });
+        }
+
+        let fields: Map<String, Value> = url::form_urlencoded::parse(body)
+            .map(|(key, value)| (key.into_owned(), Value::String(value.into_owned())))
+            .collect();
+
+        if let Some(payload) = fields.get("payload").and_then(Value::as_str) {
+            Ok(Self::Interactive(serde_json::from_str(payload)?))
+        } else if fields.contains_key("command") {
+            Ok(Self::SlashCommand(serde_json::from_value(Value::Object(
+                fields,
+            ))?))
+        } else {
+            Err(SlackError::InvalidParameter(
+                "Request body is not a Slack payload".to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> String {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .to_string()
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        // Example from Slack's documentation
+        let body = b"token=xyzz0WbapA4vBCDEFasx0q6G&team_id=T1DC2JH3J&team_domain=testteamnow&channel_id=G8PSS9T3V&channel_name=foobar&user_id=U2CERLKJA&user_name=roadrunner&command=%2Fwebhook-collect&text=&response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2FT1DC2JH3J%2F397700885554%2F96rGlfmibIGlgcZRskXaIFfN&trigger_id=398738663015.47445629121.803a0bc887a14d10d2c447fce8b6703c";
+        let verifier = SignatureVerifier::new("8f742231b10e8888abcd99yyyzzz85a5")
+            .max_age(Duration::from_secs(u64::MAX / 2));
+        verifier
+            .verify(
+                "1531420618",
+                "v0=a2114d57b48eac39b9ad189dd8316235a7b4a8d21a10bd27519666489c69b503",
+                body,
+            )
+            .unwrap();
+
+        let payload = HttpPayload::parse(body).unwrap();
+        let HttpPayload::SlashCommand(command) = payload else {
+            panic!("expected a slash command, got {:?}", payload);
+        };
+        assert_eq!(command.command, "/webhook-collect");
+        assert_eq!(command.channel_id, "G8PSS9T3V");
+
+        // Tampered body, wrong secret, stale timestamp
+        let tampered = [&body[..], b"x"].concat();
+        assert!(verifier
+            .verify(
+                "1531420618",
+                "v0=a2114d57b48eac39b9ad189dd8316235a7b4a8d21a10bd27519666489c69b503",
+                &tampered
+            )
+            .is_err());
+
+        let old = SignatureVerifier::new("old-secret");
+        let rotating = SignatureVerifier::new("new-secret").add_secret("old-secret");
+        let ts = now();
+        let signature = old.sign(&ts, body);
+        rotating.verify(&ts, &signature, body).unwrap();
+        assert!(SignatureVerifier::new("new-secret")
+            .verify(&ts, &signature, body)
+            .is_err());
+
+        let err = old
+            .verify("1531420618", &old.sign("1531420618", body), body)
+            .unwrap_err();
+        assert!(matches!(err, SlackError::AuthError { .. }));
+        assert!(old.verify(&ts, "v1=abc", body).is_err());
+    }
+
+    #[test]
+    fn test_parse_payloads() {
+        let verifier = SignatureVerifier::new("secret");
+        let body = br#"{"type":"url_verification","challenge":"abc123","token":"x"}"#;
+        let ts = now();
+        let mut headers = HeaderMap::new();
+        headers.insert(TIMESTAMP_HEADER, ts.parse().unwrap());
+        headers.insert(SIGNATURE_HEADER, verifier.sign(&ts, body).parse().unwrap());
+        assert!(matches!(
+            verifier.verify_and_parse(&headers, body).unwrap(),
+            HttpPayload::UrlVerification { challenge } if challenge == "abc123"
+        ));
+        assert!(verifier.verify_and_parse(&headers, b"{}").is_err());
+
+        let body = br#"{"type":"event_callback","team_id":"T1","event_id":"Ev1","event":{"type":"app_mention","text":"hi","ts":"1.0","channel":"C1"}}"#;
+        let HttpPayload::EventsApi(event) = HttpPayload::parse(body).unwrap() else {
+            panic!("expected an event");
+        };
+        assert_eq!(event.event_id.as_deref(), Some("Ev1"));
+        assert!(matches!(event.event, Some(crate::Event::AppMention(m)) if m.text == "hi"));
+
+        let body = "payload=%7B%22type%22%3A%22block_actions%22%2C%22user%22%3A%7B%22id%22%3A%22U1%22%7D%7D";
+        let HttpPayload::Interactive(interaction) = HttpPayload::parse(body.as_bytes()).unwrap()
+        else {
+            panic!("expected an interaction");
+        };
+        assert_eq!(interaction.interaction_type(), "block_actions");
+        assert_eq!(interaction.user().unwrap().id, "U1");
+
+        assert!(HttpPayload::parse(b"foo=bar").is_err());
+    }
+}
@@ -0,0 +1,447 @@
+//! HTTP record/replay cassettes
+//!
+//! [`Recorder`] is a middleware that writes every Web API exchange to a JSON
+//! cassette file, and [`Replayer`] serves those exchanges back without
+//! touching the network. Record once against a real workspace, commit the
+//! cassette, and replay it in CI.
+//!
+//! Tokens and cookies are redacted before anything is written: the
+//! `Authorization` and `Cookie` request headers, `Set-Cookie` response
+//! headers, any value that looks like a Slack token (`xox…-`/`xapp-`), and
+//! the `token`, `access_token`, `refresh_token`, `client_secret` and
+//! `id_token` fields. More fields can be added with `redact_field`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use slacko::testing::{Recorder, Replayer};
+//! use slacko::{AuthConfig, SlackClient};
+//!
+//! # async fn example() -> slacko::Result<()> {
+//! // Record against Slack
+//! let client = SlackClient::new(AuthConfig::from_env()?)?
+//!     .with_middleware(Recorder::new("tests/cassettes/post.json").redact_field("email"));
+//! client.chat().post_message("#general", "Hello").await?;
+//!
+//! // Replay offline
+//! let client = SlackClient::new(AuthConfig::bot("xoxb-unused"))?
+//!     .with_middleware(Replayer::from_file("tests/cassettes/post.json")?.redact_field("email"));
+//! client.chat().post_message("#general", "Hello").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, SlackError};
+use crate::middleware::{
+    ApiRequest, ApiResponse, FilePart, Middleware, MultipartBody, RequestBody, ResponseAction,
+};
+use async_trait::async_trait;
+use base64::Engine;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Fields redacted in every cassette
+const DEFAULT_REDACTED_FIELDS: &[&str] = &[
+    "token",
+    "access_token",
+    "refresh_token",
+    "client_secret",
+    "id_token",
+];
+
+/// Headers whose values are never written to a cassette
+const REDACTED_HEADERS: &[&str] = &["authorization", "cookie", "set-cookie"];
+
+/// Contents of a cassette file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cassette {
+    interactions: Vec<Interaction>,
+}
+
+/// One request and the response it got
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+}
+
+/// Request or response body in a readable, comparable form
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedBody {
+    Json(Value),
+    Form(Vec<(String, String)>),
+    Query(Vec<(String, String)>),
+    Multipart {
+        fields: Vec<(String, String)>,
+        files: Vec<RecordedFile>,
+    },
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedFile {
+    field: String,
+    file_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+    /// Base64-encoded file content
+    data: String,
+}
+
+/// Which fields to blank out before writing or comparing
+#[derive(Debug, Clone)]
+struct Redactor {
+    fields: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self {
+            fields: DEFAULT_REDACTED_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Redactor {
+    fn add(&mut self, field: impl Into<String>) {
+        self.fields.push(field.into());
+    }
+
+    fn is_secret_field(&self, name: &str) -> bool {
+        self.fields.iter().any(|f| f == name)
+    }
+
+    fn pair(&self, (key, value): &(String, String)) -> (String, String) {
+        if self.is_secret_field(key) || looks_like_token(value) {
+            (key.clone(), REDACTED.to_string())
+        } else {
+            (key.clone(), value.clone())
+        }
+    }
+
+    fn pairs(&self, pairs: &[(String, String)]) -> Vec<(String, String)> {
+        pairs.iter().map(|pair| self.pair(pair)).collect()
+    }
+
+    fn json(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| {
+                        let value = if self.is_secret_field(key) && !value.is_null() {
+                            Value::String(REDACTED.to_string())
+                        } else {
+                            self.json(value)
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.json(v)).collect()),
+            Value::String(s) if looks_like_token(s) => Value::String(REDACTED.to_string()),
+            other => other.clone(),
+        }
+    }
+
+    fn headers(&self, headers: &HeaderMap) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                    REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.as_str().to_string(), value)
+            })
+            .collect()
+    }
+
+    fn request_body(&self, body: &RequestBody) -> RecordedBody {
+        match body {
+            RequestBody::Json(value) => RecordedBody::Json(self.json(value)),
+            RequestBody::Form(pairs) => RecordedBody::Form(self.pairs(pairs)),
+            RequestBody::Query(pairs) => RecordedBody::Query(self.pairs(pairs)),
+            RequestBody::Multipart(MultipartBody { fields, files }) => RecordedBody::Multipart {
+                fields: self.pairs(fields),
+                files: files.iter().map(RecordedFile::from).collect(),
+            },
+        }
+    }
+
+    fn response_body(&self, body: &[u8]) -> RecordedBody {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(value) => RecordedBody::Json(self.json(&value)),
+            Err(_) => RecordedBody::Text(String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+}
+
+impl From<&FilePart> for RecordedFile {
+    fn from(file: &FilePart) -> Self {
+        Self {
+            field: file.field.clone(),
+            file_name: file.file_name.clone(),
+            mime_type: file.mime_type.clone(),
+            data: base64::engine::general_purpose::STANDARD.encode(&file.data),
+        }
+    }
+}
+
+/// Whether a string looks like a Slack token or cookie value
+fn looks_like_token(value: &str) -> bool {
+    [
+        "xoxa-", "xoxb-", "xoxc-", "xoxd-", "xoxe-", "xoxp-", "xoxr-", "xoxs-", "xapp-",
+    ]
+    .iter()
+    .any(|prefix| value.starts_with(prefix))
+}
+
+/// Middleware that writes each Web API exchange to a cassette file
+///
+/// The file is rewritten after every response, so a cassette is complete
+/// even if the test panics halfway. Add the recorder last, so it sees
+/// requests as they are sent.
+pub struct Recorder {
+    path: PathBuf,
+    redactor: Redactor,
+    cassette: Mutex<Cassette>,
+}
+
+impl Recorder {
+    /// Record to `path`, replacing any existing cassette
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            redactor: Redactor::default(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// Also redact this field in request parameters and JSON bodies
+    pub fn redact_field(mut self, field: impl Into<String>) -> Self {
+        self.redactor.add(field);
+        self
+    }
+
+    fn save(&self, cassette: &Cassette) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| cassette_error(&self.path, e))?;
+        }
+        let json = serde_json::to_string_pretty(cassette)?;
+        std::fs::write(&self.path, json).map_err(|e| cassette_error(&self.path, e))
+    }
+}
+
+#[async_trait]
+impl Middleware for Recorder {
+    async fn after_response(
+        &self,
+        request: &ApiRequest,
+        response: &mut ApiResponse,
+    ) -> Result<ResponseAction> {
+        let interaction = Interaction {
+            request: RecordedRequest {
+                method: request.method.clone(),
+                headers: self.redactor.headers(&request.headers),
+                body: self.redactor.request_body(&request.body),
+            },
+            response: RecordedResponse {
+                status: response.status,
+                headers: self.redactor.headers(&response.headers),
+                body: self.redactor.response_body(&response.body),
+            },
+        };
+
+        let mut cassette = self.cassette.lock().unwrap();
+        cassette.interactions.push(interaction);
+        self.save(&cassette)?;
+
+        Ok(ResponseAction::Continue)
+    }
+}
+
+/// Middleware that answers requests from a cassette file
+///
+/// Each request is matched against the first unused interaction with the
+/// same method and (by default) the same redacted body. Requests with no
+/// match fail instead of reaching the network.
+pub struct Replayer {
+    path: PathBuf,
+    redactor: Redactor,
+    match_body: bool,
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+}
+
+impl Replayer {
+    /// Load a cassette written by [`Recorder`]
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let json = std::fs::read_to_string(&path).map_err(|e| cassette_error(&path, e))?;
+        let cassette: Cassette = serde_json::from_str(&json)?;
+
+        Ok(Self {
+            path,
+            redactor: Redactor::default(),
+            match_body: true,
+            interactions: Mutex::new(
+                cassette
+                    .interactions
+                    .into_iter()
+                    .map(|interaction| (interaction, false))
+                    .collect(),
+            ),
+        })
+    }
+
+    /// Redact this field before matching, as the recorder did
+    pub fn redact_field(mut self, field: impl Into<String>) -> Self {
+        self.redactor.add(field);
+        self
+    }
+
+    /// Match requests on method only, ignoring their parameters
+    ///
+    /// Useful when parameters contain timestamps or other values that change
+    /// between runs. Interactions are still served in recorded order.
+    pub fn match_body(mut self, match_body: bool) -> Self {
+        self.match_body = match_body;
+        self
+    }
+
+    /// Number of recorded interactions not yet replayed
+    pub fn remaining(&self) -> usize {
+        self.interactions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, used)| !used)
+            .count()
+    }
+}
+
+#[async_trait]
+impl Middleware for Replayer {
+    async fn before_request(&self, request: &mut ApiRequest) -> Result<Option<ApiResponse>> {
+        let body = self.redactor.request_body(&request.body);
+        let mut interactions = self.interactions.lock().unwrap();
+
+        let (interaction, used) = interactions
+            .iter_mut()
+            .find(|(interaction, used)| {
+                !used
+                    && interaction.request.method == request.method
+                    && (!self.match_body || interaction.request.body == body)
+            })
+            .ok_or_else(|| {
+                SlackError::Other(format!(
+                    "No interaction in cassette {} matches {} {:?}",
+                    self.path.display(),
+                    request.method,
+                    body
+                ))
+            })?;
+        *used = true;
+
+        Ok(Some(interaction.response.to_response()))
+    }
+}
+
+impl RecordedResponse {
+    fn to_response(&self) -> ApiResponse {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+
+        let body = match &self.body {
+            RecordedBody::Json(value) => serde_json::to_vec(value).unwrap_or_default(),
+            RecordedBody::Text(text) => text.clone().into_bytes(),
+            // Responses are always recorded as JSON or text
+            _ => Vec::new(),
+        };
+
+        ApiResponse {
+            status: self.status,
+            headers,
+            body,
+        }
+    }
+}
+
+fn cassette_error(path: &Path, error: std::io::Error) -> SlackError {
+    SlackError::Other(format!("Cassette {}: {}", path.display(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redaction() {
+        let mut redactor = Redactor::default();
+        redactor.add("email");
+
+        let redacted = redactor.json(&json!({
+            "ok": true,
+            "access_token": "xoxp-1-secret",
+            "authed_user": { "id": "U1", "email": "a@example.com" },
+            "items": ["xoxb-2-secret", "plain"],
+        }));
+        assert_eq!(
+            redacted,
+            json!({
+                "ok": true,
+                "access_token": REDACTED,
+                "authed_user": { "id": "U1", "email": REDACTED },
+                "items": [REDACTED, "plain"],
+            })
+        );
+
+        let pairs = redactor.pairs(&[
+            ("channel".to_string(), "C1".to_string()),
+            ("token".to_string(), "anything".to_string()),
+        ]);
+        assert_eq!(pairs[0].1, "C1");
+        assert_eq!(pairs[1].1, REDACTED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer xoxb-1"));
+        headers.insert("cookie", HeaderValue::from_static("d=xoxd-1"));
+        assert!(redactor
+            .headers(&headers)
+            .iter()
+            .all(|(_, value)| value == REDACTED));
+    }
+}
@@ -0,0 +1,826 @@
+//! In-memory workspace state and Web API method handlers for [`MockSlack`]
+//!
+//! [`MockSlack`]: super::MockSlack
+
+use crate::types::{Channel, File, Message, Reaction, User};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) const TEAM_ID: &str = "T0MOCK";
+pub(crate) const TEAM_NAME: &str = "Mock Workspace";
+pub(crate) const TEAM_DOMAIN: &str = "mock";
+pub(crate) const BOT_USER_ID: &str = "U0BOT";
+pub(crate) const BOT_USER_NAME: &str = "mockbot";
+pub(crate) const BOT_ID: &str = "B0BOT";
+pub(crate) const APP_ID: &str = "A0MOCK";
+
+/// Request parameters, merged from the query string and the JSON, form or
+/// multipart body
+#[derive(Debug, Default)]
+pub(crate) struct Params {
+    pub values: Map<String, Value>,
+    pub files: Vec<UploadedFile>,
+}
+
+/// A file part of a multipart request
+#[derive(Debug)]
+pub(crate) struct UploadedFile {
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
+impl Params {
+    fn str(&self, name: &str) -> Option<&str> {
+        self.values
+            .get(name)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+    }
+
+    fn string(&self, name: &str) -> Option<String> {
+        match self.values.get(name) {
+            Some(Value::String(s)) if !s.is_empty() => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            Some(Value::Bool(b)) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+
+    fn require(&self, name: &str) -> std::result::Result<&str, Value> {
+        self.str(name)
+            .ok_or_else(|| error_body("invalid_arguments"))
+    }
+
+    fn limit(&self, default: usize) -> usize {
+        self.string("limit")
+            .and_then(|l| l.parse().ok())
+            .filter(|&l| l > 0)
+            .unwrap_or(default)
+    }
+
+    fn cursor(&self) -> usize {
+        self.str("cursor").and_then(|c| c.parse().ok()).unwrap_or(0)
+    }
+}
+
+/// A message the workspace generated that connected WebSocket clients should see
+#[derive(Debug, Clone)]
+pub(crate) struct Notification {
+    pub event: Value,
+}
+
+#[derive(Debug, Clone)]
+struct MockChannel {
+    channel: Channel,
+    members: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct StoredFile {
+    file: File,
+    #[allow(dead_code)]
+    data: Vec<u8>,
+}
+
+/// Users, channels, messages, reactions and files of the mock workspace
+#[derive(Debug)]
+pub(crate) struct Workspace {
+    users: Vec<User>,
+    channels: Vec<MockChannel>,
+    messages: HashMap<String, Vec<Message>>,
+    files: Vec<StoredFile>,
+    last_ts: u64,
+    next_id: u64,
+    used_refresh_tokens: Vec<String>,
+    pub(crate) failures: HashMap<String, Vec<String>>,
+    pub(crate) notifications: Vec<Notification>,
+}
+
+impl Workspace {
+    pub(crate) fn new() -> Self {
+        let mut workspace = Self {
+            users: Vec::new(),
+            channels: Vec::new(),
+            messages: HashMap::new(),
+            files: Vec::new(),
+            last_ts: 0,
+            next_id: 1,
+            used_refresh_tokens: Vec::new(),
+            failures: HashMap::new(),
+            notifications: Vec::new(),
+        };
+
+        workspace.add_user(BOT_USER_ID, BOT_USER_NAME, true);
+        workspace.add_channel("C0GENERAL", "general", false);
+        workspace
+    }
+
+    pub(crate) fn add_user(&mut self, id: &str, name: &str, is_bot: bool) {
+        self.users.retain(|u| u.id != id);
+        self.users.push(User {
+            id: id.to_string(),
+            name: name.to_string(),
+            real_name: Some(name.to_string()),
+            profile: None,
+            is_bot: Some(is_bot),
+            is_admin: Some(false),
+            is_owner: Some(false),
+            deleted: Some(false),
+        });
+    }
+
+    pub(crate) fn add_channel(&mut self, id: &str, name: &str, is_private: bool) {
+        self.channels.retain(|c| c.channel.id != id);
+        self.channels.push(MockChannel {
+            channel: Channel {
+                id: id.to_string(),
+                name: Some(name.to_string()),
+                is_channel: Some(!is_private),
+                is_group: Some(is_private),
+                is_im: Some(false),
+                is_private: Some(is_private),
+                is_archived: Some(false),
+                is_member: None,
+                topic: None,
+                purpose: None,
+                created: Some(now_secs() as i64),
+                num_members: None,
+                user: None,
+            },
+            members: vec![BOT_USER_ID.to_string()],
+        });
+        self.messages.entry(id.to_string()).or_default();
+    }
+
+    pub(crate) fn channel_messages(&self, channel: &str) -> Vec<Message> {
+        self.find_channel(channel)
+            .and_then(|c| self.messages.get(&c.channel.id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn files(&self) -> Vec<File> {
+        self.files.iter().map(|f| f.file.clone()).collect()
+    }
+
+    /// Post a message as a user and notify connected clients
+    pub(crate) fn post_message(
+        &mut self,
+        channel: &str,
+        user: &str,
+        bot_id: Option<&str>,
+        text: &str,
+        thread_ts: Option<&str>,
+    ) -> std::result::Result<Message, Value> {
+        let channel_id = self
+            .find_channel(channel)
+            .ok_or_else(|| error_body("channel_not_found"))?
+            .channel
+            .id
+            .clone();
+
+        if let Some(parent) = thread_ts {
+            let messages = self.messages.entry(channel_id.clone()).or_default();
+            let parent = messages
+                .iter_mut()
+                .find(|m| m.ts == parent)
+                .ok_or_else(|| error_body("thread_not_found"))?;
+            parent.thread_ts = Some(parent.ts.clone());
+            parent.reply_count = Some(parent.reply_count.unwrap_or(0) + 1);
+        }
+
+        let ts = self.next_ts();
+        if let Some(parent) = thread_ts {
+            if let Some(parent) = self
+                .messages
+                .get_mut(&channel_id)
+                .and_then(|m| m.iter_mut().find(|m| m.ts == parent))
+            {
+                parent.latest_reply = Some(ts.clone());
+            }
+        }
+
+        let message = Message {
+            msg_type: "message".to_string(),
+            user: Some(user.to_string()),
+            bot_id: bot_id.map(str::to_string),
+            text: text.to_string(),
+            reply_count: None,
+            ts: ts.clone(),
+            thread_ts: thread_ts.map(str::to_string),
+            latest_reply: None,
+            channel: None,
+            attachments: None,
+            reactions: None,
+            files: None,
+            edited: None,
+        };
+
+        self.messages
+            .entry(channel_id.clone())
+            .or_default()
+            .push(message.clone());
+
+        let mut event = json!({
+            "type": "message",
+            "channel": channel_id,
+            "user": user,
+            "text": text,
+            "ts": ts,
+            "event_ts": ts,
+        });
+        if let Some(bot_id) = bot_id {
+            event["bot_id"] = json!(bot_id);
+        }
+        if let Some(thread_ts) = thread_ts {
+            event["thread_ts"] = json!(thread_ts);
+        }
+        self.notifications.push(Notification { event });
+
+        Ok(message)
+    }
+
+    /// Handle a Web API method call and return the JSON response body
+    pub(crate) fn handle(&mut self, method: &str, params: &Params) -> Value {
+        if let Some(error) = self
+            .failures
+            .get_mut(method)
+            .and_then(|queue| (!queue.is_empty()).then(|| queue.remove(0)))
+        {
+            return error_body(&error);
+        }
+
+        let result = match method {
+            "api.test" => Ok(json!({ "args": params.values })),
+            "auth.test" => Ok(json!({
+                "url": format!("https://{}.slack.com/", TEAM_DOMAIN),
+                "team": TEAM_NAME,
+                "user": BOT_USER_NAME,
+                "team_id": TEAM_ID,
+                "user_id": BOT_USER_ID,
+                "bot_id": BOT_ID,
+                "is_enterprise_install": false,
+            })),
+            "chat.postMessage" => self.chat_post_message(params),
+            "chat.update" => self.chat_update(params),
+            "chat.delete" => self.chat_delete(params),
+            "conversations.list" => self.conversations_list(params),
+            "conversations.info" => self.conversations_info(params),
+            "conversations.create" => self.conversations_create(params),
+            "conversations.join" => self.conversations_join(params),
+            "conversations.leave" => self.conversations_leave(params),
+            "conversations.archive" => self.conversations_set_archived(params, true),
+            "conversations.unarchive" => self.conversations_set_archived(params, false),
+            "conversations.members" => self.conversations_members(params),
+            "conversations.history" => self.conversations_history(params),
+            "conversations.replies" => self.conversations_replies(params),
+            "users.list" => self.users_list(params),
+            "users.info" => self.users_info(params),
+            "reactions.add" => self.reactions_add(params),
+            "reactions.remove" => self.reactions_remove(params),
+            "reactions.get" => self.reactions_get(params),
+            "files.upload" => self.files_upload(params),
+            "files.info" => self.files_info(params),
+            "files.list" => Ok(json!({ "files": self.files() })),
+            "files.delete" => self.files_delete(params),
+            "oauth.v2.access" => self.oauth_v2_access(params),
+            _ => Err(error_body("unknown_method")),
+        };
+
+        match result {
+            Ok(mut body) => {
+                body["ok"] = json!(true);
+                body
+            }
+            Err(error) => error,
+        }
+    }
+
+    // ========== chat.* ==========
+
+    fn chat_post_message(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let channel = params.require("channel")?;
+        let text = params.str("text").unwrap_or_default();
+        if text.is_empty() && !params.values.contains_key("blocks") {
+            return Err(error_body("no_text"));
+        }
+
+        let message = self.post_message(
+            channel,
+            BOT_USER_ID,
+            Some(BOT_ID),
+            text,
+            params.str("thread_ts"),
+        )?;
+        let channel_id = self.find_channel(channel).map(|c| c.channel.id.clone());
+
+        Ok(json!({ "channel": channel_id, "ts": message.ts, "message": message }))
+    }
+
+    fn chat_update(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let (channel_id, ts) = self.message_ref(params, "ts")?;
+        let text = params.str("text").unwrap_or_default().to_string();
+        let edited_ts = self.next_ts();
+
+        let message = self.find_message_mut(&channel_id, &ts)?;
+        message.text = text.clone();
+        message.edited = Some(crate::types::Edited {
+            user: Some(BOT_USER_ID.to_string()),
+            ts: Some(edited_ts),
+        });
+
+        Ok(json!({ "channel": channel_id, "ts": ts, "text": text }))
+    }
+
+    fn chat_delete(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let (channel_id, ts) = self.message_ref(params, "ts")?;
+        self.find_message_mut(&channel_id, &ts)?;
+
+        if let Some(messages) = self.messages.get_mut(&channel_id) {
+            messages.retain(|m| m.ts != ts);
+        }
+
+        Ok(json!({ "channel": channel_id, "ts": ts }))
+    }
+
+    // ========== conversations.* ==========
+
+    fn conversations_list(&self, params: &Params) -> std::result::Result<Value, Value> {
+        let exclude_archived = params.string("exclude_archived").as_deref() == Some("true");
+        let channels: Vec<Channel> = self
+            .channels
+            .iter()
+            .filter(|c| !exclude_archived || c.channel.is_archived != Some(true))
+            .map(|c| self.channel_view(c))
+            .collect();
+
+        let (page, next_cursor) = paginate(&channels, params.cursor(), params.limit(100));
+        Ok(json!({
+            "channels": page,
+            "response_metadata": { "next_cursor": next_cursor },
+        }))
+    }
+
+    fn conversations_info(&self, params: &Params) -> std::result::Result<Value, Value> {
+        let channel = self.require_channel(params)?;
+        Ok(json!({ "channel": self.channel_view(channel) }))
+    }
+
+    fn conversations_create(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let name = params.require("name")?.to_string();
+        if self.find_channel(&name).is_some() {
+            return Err(error_body("name_taken"));
+        }
+
+        let id = format!("C{:08}", self.next_id());
+        let is_private = params.string("is_private").as_deref() == Some("true");
+        self.add_channel(&id, &name, is_private);
+
+        let channel = self.find_channel(&id).expect("channel was just added");
+        Ok(json!({ "channel": self.channel_view(channel) }))
+    }
+
+    fn conversations_join(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let id = self.require_channel(params)?.channel.id.clone();
+        let channel = self.find_channel_mut(&id)?;
+
+        let already_in_channel = channel.members.iter().any(|m| m == BOT_USER_ID);
+        if !already_in_channel {
+            channel.members.push(BOT_USER_ID.to_string());
+        }
+
+        let channel = self.find_channel(&id).expect("channel exists");
+        let mut body = json!({ "channel": self.channel_view(channel) });
+        if already_in_channel {
+            body["warning"] = json!("already_in_channel");
+        }
+        Ok(body)
+    }
+
+    fn conversations_leave(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let id = self.require_channel(params)?.channel.id.clone();
+        let channel = self.find_channel_mut(&id)?;
+        if !channel.members.iter().any(|m| m == BOT_USER_ID) {
+            return Err(error_body("not_in_channel"));
+        }
+        channel.members.retain(|m| m != BOT_USER_ID);
+        Ok(json!({}))
+    }
+
+    fn conversations_set_archived(
+        &mut self,
+        params: &Params,
+        archived: bool,
+    ) -> std::result::Result<Value, Value> {
+        let id = self.require_channel(params)?.channel.id.clone();
+        let channel = self.find_channel_mut(&id)?;
+
+        match (channel.channel.is_archived == Some(true), archived) {
+            (true, true) => Err(error_body("already_archived")),
+            (false, false) => Err(error_body("not_archived")),
+            _ => {
+                channel.channel.is_archived = Some(archived);
+                Ok(json!({}))
+            }
+        }
+    }
+
+    fn conversations_members(&self, params: &Params) -> std::result::Result<Value, Value> {
+        let channel = self.require_channel(params)?;
+        let (page, next_cursor) = paginate(&channel.members, params.cursor(), params.limit(100));
+        Ok(json!({
+            "members": page,
+            "response_metadata": { "next_cursor": next_cursor },
+        }))
+    }
+
+    fn conversations_history(&self, params: &Params) -> std::result::Result<Value, Value> {
+        let channel = self.require_channel(params)?;
+        let oldest = params.string("oldest");
+        let latest = params.string("latest");
+
+        // Newest first, top-level messages and thread parents only
+        let messages: Vec<Message> = self
+            .messages
+            .get(&channel.channel.id)
+            .map(|m| m.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .rev()
+            .filter(|m| m.thread_ts.is_none() || m.thread_ts.as_deref() == Some(m.ts.as_str()))
+            .filter(|m| {
+                oldest
+                    .as_deref()
+                    .is_none_or(|o| ts_value(&m.ts) > ts_value(o))
+            })
+            .filter(|m| {
+                latest
+                    .as_deref()
+                    .is_none_or(|l| ts_value(&m.ts) < ts_value(l))
+            })
+            .cloned()
+            .collect();
+
+        let (page, next_cursor) = paginate(&messages, params.cursor(), params.limit(100));
+        Ok(json!({
+            "messages": page,
+            "has_more": !next_cursor.is_empty(),
+            "response_metadata": { "next_cursor": next_cursor },
+        }))
+    }
+
+    fn conversations_replies(&self, params: &Params) -> std::result::Result<Value, Value> {
+        let channel = self.require_channel(params)?;
+        let ts = params.require("ts")?;
+        let messages = self
+            .messages
+            .get(&channel.channel.id)
+            .map(|m| m.as_slice())
+            .unwrap_or_default();
+
+        let parent = messages
+            .iter()
+            .find(|m| m.ts == ts)
+            .ok_or_else(|| error_body("thread_not_found"))?;
+        let replies: Vec<Message> = messages
+            .iter()
+            .filter(|m| m.ts != ts && m.thread_ts.as_deref() == Some(ts))
+            .cloned()
+            .collect();
+
+        // Like Slack, the parent message leads every page
+        let (page, next_cursor) = paginate(&replies, params.cursor(), params.limit(100));
+        let mut page_messages = vec![parent.clone()];
+        page_messages.extend(page);
+
+        Ok(json!({
+            "messages": page_messages,
+            "has_more": !next_cursor.is_empty(),
+            "response_metadata": { "next_cursor": next_cursor },
+        }))
+    }
+
+    // ========== users.* ==========
+
+    fn users_list(&self, params: &Params) -> std::result::Result<Value, Value> {
+        let (page, next_cursor) = paginate(&self.users, params.cursor(), params.limit(100));
+        Ok(json!({
+            "members": page,
+            "response_metadata": { "next_cursor": next_cursor },
+        }))
+    }
+
+    fn users_info(&self, params: &Params) -> std::result::Result<Value, Value> {
+        let id = params.require("user")?;
+        let user = self
+            .users
+            .iter()
+            .find(|u| u.id == id)
+            .ok_or_else(|| error_body("user_not_found"))?;
+        Ok(json!({ "user": user }))
+    }
+
+    // ========== reactions.* ==========
+
+    fn reactions_add(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let (channel_id, ts) = self.message_ref(params, "timestamp")?;
+        let name = params.require("name")?.to_string();
+        let message = self.find_message_mut(&channel_id, &ts)?;
+        let reactions = message.reactions.get_or_insert_with(Vec::new);
+
+        match reactions.iter_mut().find(|r| r.name == name) {
+            Some(reaction) if reaction.users.iter().any(|u| u == BOT_USER_ID) => {
+                return Err(error_body("already_reacted"));
+            }
+            Some(reaction) => {
+                reaction.users.push(BOT_USER_ID.to_string());
+                reaction.count += 1;
+            }
+            None => reactions.push(Reaction {
+                name: name.clone(),
+                count: 1,
+                users: vec![BOT_USER_ID.to_string()],
+            }),
+        }
+
+        self.notifications.push(Notification {
+            event: json!({
+                "type": "reaction_added",
+                "user": BOT_USER_ID,
+                "reaction": name,
+                "item": { "type": "message", "channel": channel_id, "ts": ts },
+                "event_ts": ts,
+            }),
+        });
+
+        Ok(json!({}))
+    }
+
+    fn reactions_remove(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let (channel_id, ts) = self.message_ref(params, "timestamp")?;
+        let name = params.require("name")?.to_string();
+        let message = self.find_message_mut(&channel_id, &ts)?;
+        let reactions = message.reactions.get_or_insert_with(Vec::new);
+
+        let reaction = reactions
+            .iter_mut()
+            .find(|r| r.name == name && r.users.iter().any(|u| u == BOT_USER_ID))
+            .ok_or_else(|| error_body("no_reaction"))?;
+        reaction.users.retain(|u| u != BOT_USER_ID);
+        reaction.count -= 1;
+        reactions.retain(|r| r.count > 0);
+        if reactions.is_empty() {
+            message.reactions = None;
+        }
+
+        Ok(json!({}))
+    }
+
+    fn reactions_get(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let (channel_id, ts) = self.message_ref(params, "timestamp")?;
+        let message = self.find_message_mut(&channel_id, &ts)?.clone();
+        Ok(json!({ "type": "message", "channel": channel_id, "message": message }))
+    }
+
+    // ========== files.* ==========
+
+    fn files_upload(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let (file_name, mimetype, data) = match params.files.first() {
+            Some(upload) => (
+                params
+                    .string("filename")
+                    .unwrap_or_else(|| upload.file_name.clone()),
+                upload.content_type.clone(),
+                upload.data.clone(),
+            ),
+            None => match params.str("content") {
+                Some(content) => (
+                    params
+                        .string("filename")
+                        .unwrap_or_else(|| "file.txt".to_string()),
+                    Some("text/plain".to_string()),
+                    content.as_bytes().to_vec(),
+                ),
+                None => return Err(error_body("no_file_data")),
+            },
+        };
+
+        let id = format!("F{:08}", self.next_id());
+        let file = File {
+            id: id.clone(),
+            name: Some(file_name.clone()),
+            title: Some(params.string("title").unwrap_or_else(|| file_name.clone())),
+            mimetype,
+            filetype: params.string("filetype"),
+            size: Some(data.len() as u64),
+            url_private: Some(format!(
+                "https://files.slack.com/files-pri/{}/{}",
+                id, file_name
+            )),
+            url_private_download: None,
+            permalink: None,
+            user: Some(BOT_USER_ID.to_string()),
+            created: Some(now_secs() as i64),
+        };
+
+        let channels: Vec<String> = params
+            .str("channels")
+            .map(|c| c.split(',').map(|c| c.trim().to_string()).collect())
+            .unwrap_or_default();
+        for channel in channels.iter().filter(|c| !c.is_empty()) {
+            let comment = params.str("initial_comment").unwrap_or_default();
+            let message = self.post_message(
+                channel,
+                BOT_USER_ID,
+                Some(BOT_ID),
+                comment,
+                params.str("thread_ts"),
+            )?;
+            let channel_id = self.find_channel(channel).map(|c| c.channel.id.clone());
+            if let Some(message) = channel_id
+                .and_then(|c| self.messages.get_mut(&c))
+                .and_then(|m| m.iter_mut().find(|m| m.ts == message.ts))
+            {
+                message.files = Some(vec![file.clone()]);
+            }
+        }
+
+        self.files.push(StoredFile {
+            file: file.clone(),
+            data,
+        });
+
+        Ok(json!({ "file": file }))
+    }
+
+    fn files_info(&self, params: &Params) -> std::result::Result<Value, Value> {
+        let id = params.require("file")?;
+        let file = self
+            .files
+            .iter()
+            .find(|f| f.file.id == id)
+            .ok_or_else(|| error_body("file_not_found"))?;
+        Ok(json!({ "file": file.file }))
+    }
+
+    fn files_delete(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        let id = params.require("file")?.to_string();
+        if !self.files.iter().any(|f| f.file.id == id) {
+            return Err(error_body("file_not_found"));
+        }
+        self.files.retain(|f| f.file.id != id);
+        Ok(json!({}))
+    }
+
+    // ========== oauth.* ==========
+
+    fn oauth_v2_access(&mut self, params: &Params) -> std::result::Result<Value, Value> {
+        params.require("client_id")?;
+        params.require("client_secret")?;
+
+        match params.str("grant_type") {
+            Some("refresh_token") => {
+                // Refresh tokens are single use
+                let refresh_token = params.require("refresh_token")?.to_string();
+                if self.used_refresh_tokens.contains(&refresh_token) {
+                    return Err(error_body("invalid_refresh_token"));
+                }
+                self.used_refresh_tokens.push(refresh_token);
+
+                let n = self.next_id();
+                Ok(json!({
+                    "access_token": format!("xoxe.xoxb-mock-{}", n),
+                    "token_type": "bot",
+                    "scope": "chat:write,channels:read",
+                    "bot_user_id": BOT_USER_ID,
+                    "app_id": APP_ID,
+                    "team": { "id": TEAM_ID, "name": TEAM_NAME },
+                    "enterprise": null,
+                    "is_enterprise_install": false,
+                    "refresh_token": format!("xoxe-1-mock-{}", n),
+                    "expires_in": 43200,
+                }))
+            }
+            Some("authorization_code") | None => {
+                // Any code is accepted
+                params.require("code")?;
+
+                let n = self.next_id();
+                Ok(json!({
+                    "access_token": format!("xoxb-mock-{}", n),
+                    "token_type": "bot",
+                    "scope": "chat:write,channels:read",
+                    "bot_user_id": BOT_USER_ID,
+                    "app_id": APP_ID,
+                    "team": { "id": TEAM_ID, "name": TEAM_NAME },
+                    "enterprise": null,
+                    "is_enterprise_install": false,
+                    "authed_user": { "id": "U0INSTALLER" },
+                }))
+            }
+            _ => Err(error_body("invalid_grant_type")),
+        }
+    }
+
+    // ========== helpers ==========
+
+    fn find_channel(&self, channel: &str) -> Option<&MockChannel> {
+        let name = channel.trim_start_matches('#');
+        self.channels
+            .iter()
+            .find(|c| c.channel.id == channel || c.channel.name.as_deref() == Some(name))
+    }
+
+    fn find_channel_mut(&mut self, id: &str) -> std::result::Result<&mut MockChannel, Value> {
+        self.channels
+            .iter_mut()
+            .find(|c| c.channel.id == id)
+            .ok_or_else(|| error_body("channel_not_found"))
+    }
+
+    fn require_channel(&self, params: &Params) -> std::result::Result<&MockChannel, Value> {
+        let channel = params.require("channel")?;
+        self.find_channel(channel)
+            .ok_or_else(|| error_body("channel_not_found"))
+    }
+
+    /// Resolve the `channel` parameter and a message timestamp parameter
+    fn message_ref(
+        &self,
+        params: &Params,
+        ts_param: &str,
+    ) -> std::result::Result<(String, String), Value> {
+        let channel_id = self.require_channel(params)?.channel.id.clone();
+        let ts = params.require(ts_param)?.to_string();
+        Ok((channel_id, ts))
+    }
+
+    fn find_message_mut(
+        &mut self,
+        channel_id: &str,
+        ts: &str,
+    ) -> std::result::Result<&mut Message, Value> {
+        self.messages
+            .get_mut(channel_id)
+            .and_then(|m| m.iter_mut().find(|m| m.ts == ts))
+            .ok_or_else(|| error_body("message_not_found"))
+    }
+
+    fn channel_view(&self, channel: &MockChannel) -> Channel {
+        let mut view = channel.channel.clone();
+        view.is_member = Some(channel.members.iter().any(|m| m == BOT_USER_ID));
+        view.num_members = Some(channel.members.len() as u32);
+        view
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Unique, increasing message timestamp in Slack's `seconds.micros` format
+    fn next_ts(&mut self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or_default();
+        self.last_ts = now.max(self.last_ts + 1);
+        format!(
+            "{}.{:06}",
+            self.last_ts / 1_000_000,
+            self.last_ts % 1_000_000
+        )
+    }
+}
+
+/// Slack error response body
+pub(crate) fn error_body(error: &str) -> Value {
+    json!({ "ok": false, "error": error })
+}
+
+/// Return one page of `items` starting at `offset` and the cursor of the next
+/// page, or an empty cursor on the last page
+fn paginate<T: Clone>(items: &[T], offset: usize, limit: usize) -> (Vec<T>, String) {
+    let end = offset.saturating_add(limit).min(items.len());
+    let page = items.get(offset..end).unwrap_or_default().to_vec();
+    let next_cursor = if end < items.len() {
+        end.to_string()
+    } else {
+        String::new()
+    };
+    (page, next_cursor)
+}
+
+fn ts_value(ts: &str) -> f64 {
+    ts.parse().unwrap_or_default()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -10,14 +10,43 @@ pub struct SlackResponse<T> {
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub warning: Option<String>,
+    /// Scopes required by the method (sent with `missing_scope` errors)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub needed: Option<String>,
+    /// Scopes granted to the token (sent with `missing_scope` errors)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provided: Option<String>,
     #[serde(flatten)]
     pub data: Option<T>,
 }
 
-/// Pagination cursor for API responses
+/// Serde default for `ok` fields of response types
+///
+/// The `ok` field is consumed by [`SlackResponse`] before the data is parsed,
+/// so response types that expose it need a default.
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+/// Deserialize `null` as the type's default value
+pub(crate) fn null_as_default<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Pagination cursor and notices for API responses
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseMetadata {
     pub next_cursor: Option<String>,
+    /// Warning codes, e.g. `missing_charset` or `superfluous_charset`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Human-readable notices, e.g. deprecation messages
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<String>,
 }
 
 /// User information
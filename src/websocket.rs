@@ -0,0 +1,369 @@
+//! WebSocket connection helpers shared by RTM and Socket Mode
+//!
+//! Applies the client's network settings (user agent, custom headers, connect
+//! timeout and proxy) and, in stealth mode, the `d` cookie to WebSocket
+//! handshakes, so real-time connections take the same route as Web API
+//! requests.
+
+use crate::client::SlackClient;
+use crate::error::{Result, SlackError};
+use base64::Engine;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::Request;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// WebSocket stream type returned by [`connect`]
+pub(crate) type SlackWebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Open a WebSocket connection using the client's network settings
+pub(crate) async fn connect(client: &SlackClient, url: &str) -> Result<SlackWebSocket> {
+    let request = build_request(client, url)?;
+    let network = &client.network;
+
+    let connect = async {
+        match &network.proxy {
+            Some(proxy) => {
+                let stream = open_tunnel(proxy, &request).await?;
+                let (ws, _) =
+                    tokio_tungstenite::client_async_tls_with_config(request, stream, None, None)
+                        .await?;
+                Ok(ws)
+            }
+            None => {
+                let (ws, _) = tokio_tungstenite::connect_async(request).await?;
+                Ok(ws)
+            }
+        }
+    };
+
+    match network.connect_timeout {
+        Some(timeout) => tokio::time::timeout(timeout, connect)
+            .await
+            .map_err(|_| SlackError::websocket_error("Timed out connecting to WebSocket"))?,
+        None => connect.await,
+    }
+}
+
+/// Build the handshake request with the client's user agent, headers and
+/// stealth cookie
+fn build_request(client: &SlackClient, url: &str) -> Result<Request<()>> {
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| SlackError::websocket_error(format!("Invalid WebSocket URL: {}", e)))?;
+
+    let headers = request.headers_mut();
+    for (name, value) in client.websocket_headers() {
+        if let Some(name) = name {
+            headers.insert(name, value);
+        }
+    }
+
+    Ok(request)
+}
+
+/// Open a TCP stream to the WebSocket host through a proxy
+async fn open_tunnel(proxy: &str, request: &Request<()>) -> Result<TcpStream> {
+    let proxy_url = url::Url::parse(proxy)
+        .map_err(|e| SlackError::config_error(format!("Invalid proxy URL: {}", e)))?;
+
+    let target_host = request
+        .uri()
+        .host()
+        .ok_or_else(|| SlackError::websocket_error("WebSocket URL has no host"))?
+        .to_string();
+    let target_port = request
+        .uri()
+        .port_u16()
+        .unwrap_or(match request.uri().scheme_str() {
+            Some("ws") => 80,
+            _ => 443,
+        });
+
+    let proxy_host = proxy_url
+        .host_str()
+        .ok_or_else(|| SlackError::config_error("Proxy URL has no host"))?;
+
+    match proxy_url.scheme() {
+        "http" => {
+            let port = proxy_url.port().unwrap_or(80);
+            let stream = TcpStream::connect((proxy_host, port))
+                .await
+                .map_err(|e| proxy_error(format!("Failed to connect to proxy: {}", e)))?;
+            http_connect(stream, &proxy_url, &target_host, target_port).await
+        }
+        "socks5" | "socks5h" => {
+            let port = proxy_url.port().unwrap_or(1080);
+            let stream = TcpStream::connect((proxy_host, port))
+                .await
+                .map_err(|e| proxy_error(format!("Failed to connect to proxy: {}", e)))?;
+            socks5_connect(stream, &proxy_url, &target_host, target_port).await
+        }
+        other => Err(SlackError::config_error(format!(
+            "Unsupported proxy scheme for WebSocket connections: {}",
+            other
+        ))),
+    }
+}
+
+/// Establish a tunnel with an HTTP `CONNECT` request
+async fn http_connect(
+    mut stream: TcpStream,
+    proxy_url: &url::Url,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream> {
+    let mut connect = format!(
+        "CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n",
+        host = host,
+        port = port
+    );
+
+    if !proxy_url.username().is_empty() {
+        let credentials = format!(
+            "{}:{}",
+            percent_decode(proxy_url.username()),
+            percent_decode(proxy_url.password().unwrap_or(""))
+        );
+        connect.push_str(&format!(
+            "Proxy-Authorization: Basic {}\r\n",
+            base64::engine::general_purpose::STANDARD.encode(credentials)
+        ));
+    }
+    connect.push_str("\r\n");
+
+    stream
+        .write_all(connect.as_bytes())
+        .await
+        .map_err(|e| proxy_error(e.to_string()))?;
+
+    // Read the response headers byte by byte so no tunneled data is consumed
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > 8192 {
+            return Err(proxy_error("Proxy response headers too large"));
+        }
+        let n = stream
+            .read(&mut byte)
+            .await
+            .map_err(|e| proxy_error(e.to_string()))?;
+        if n == 0 {
+            return Err(proxy_error("Proxy closed connection during CONNECT"));
+        }
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(proxy_error(format!(
+            "Proxy CONNECT failed: {}",
+            status_line
+        )));
+    }
+
+    Ok(stream)
+}
+
+/// Establish a tunnel with the SOCKS5 protocol (RFC 1928 / RFC 1929)
+async fn socks5_connect(
+    mut stream: TcpStream,
+    proxy_url: &url::Url,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream> {
+    let io = |e: std::io::Error| proxy_error(e.to_string());
+    let has_credentials = !proxy_url.username().is_empty();
+
+    // Greeting: offer "no auth" and, if configured, username/password
+    let greeting: &[u8] = if has_credentials {
+        &[0x05, 0x02, 0x00, 0x02]
+    } else {
+        &[0x05, 0x01, 0x00]
+    };
+    stream.write_all(greeting).await.map_err(io)?;
+
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await.map_err(io)?;
+    match choice {
+        [0x05, 0x00] => {}
+        [0x05, 0x02] if has_credentials => {
+            let username = percent_decode(proxy_url.username());
+            let password = percent_decode(proxy_url.password().unwrap_or(""));
+            if username.len() > 255 || password.len() > 255 {
+                return Err(proxy_error("SOCKS5 credentials too long"));
+            }
+
+            let mut auth = vec![0x01, username.len() as u8];
+            auth.extend_from_slice(username.as_bytes());
+            auth.push(password.len() as u8);
+            auth.extend_from_slice(password.as_bytes());
+            stream.write_all(&auth).await.map_err(io)?;
+
+            let mut status = [0u8; 2];
+            stream.read_exact(&mut status).await.map_err(io)?;
+            if status[1] != 0x00 {
+                return Err(proxy_error("SOCKS5 authentication failed"));
+            }
+        }
+        _ => return Err(proxy_error("SOCKS5 proxy rejected authentication methods")),
+    }
+
+    if host.len() > 255 {
+        return Err(proxy_error("SOCKS5 target host name too long"));
+    }
+
+    // CONNECT request with a domain name address, resolved by the proxy
+    let mut connect = vec![0x05, 0x01, 0x00, 0x03, host.len() as u8];
+    connect.extend_from_slice(host.as_bytes());
+    connect.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&connect).await.map_err(io)?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await.map_err(io)?;
+    if reply[1] != 0x00 {
+        return Err(proxy_error(format!(
+            "SOCKS5 CONNECT failed with code {}",
+            reply[1]
+        )));
+    }
+
+    // Skip the bound address and port
+    let addr_len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await.map_err(io)?;
+            len[0] as usize
+        }
+        other => {
+            return Err(proxy_error(format!(
+                "SOCKS5 reply has unknown address type {}",
+                other
+            )))
+        }
+    };
+    let mut bound = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut bound).await.map_err(io)?;
+
+    Ok(stream)
+}
+
+fn proxy_error(msg: impl std::fmt::Display) -> SlackError {
+    SlackError::websocket_error(format!("Proxy error: {}", msg))
+}
+
+/// Decode percent-encoded credentials from a proxy URL
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthConfig;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_http_connect_tunnel() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let proxy = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            socket
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .await
+                .unwrap();
+            request
+        });
+
+        let proxy_url = url::Url::parse(&format!("http://user:p%40ss@{}", addr)).unwrap();
+        let stream = TcpStream::connect(addr).await.unwrap();
+        http_connect(stream, &proxy_url, "wss-primary.slack.com", 443)
+            .await
+            .unwrap();
+
+        let request = proxy.await.unwrap();
+        assert!(request.starts_with("CONNECT wss-primary.slack.com:443 HTTP/1.1\r\n"));
+        // "user:p@ss" in base64
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpwQHNz\r\n"));
+    }
+
+    #[test]
+    fn test_build_request_headers() {
+        let client = SlackClient::builder(AuthConfig::stealth("xoxc-1", "xoxd-2"))
+            .header("X-Trace", "abc")
+            .build()
+            .unwrap();
+        let request = build_request(&client, "wss://wss-primary.slack.com/?token=xoxc-1").unwrap();
+        let headers = request.headers();
+        assert_eq!(headers["cookie"], "d=xoxd-2");
+        assert_eq!(headers["x-trace"], "abc");
+        assert!(headers["user-agent"]
+            .to_str()
+            .unwrap()
+            .starts_with("slacko/"));
+        // The handshake's own headers are kept
+        assert!(headers.contains_key("sec-websocket-key"));
+    }
+
+    #[tokio::test]
+    async fn test_socks5_tunnel() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let proxy = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            socket.read_exact(&mut greeting).await.unwrap();
+            socket.write_all(&[0x05, 0x00]).await.unwrap();
+
+            let mut header = [0u8; 5];
+            socket.read_exact(&mut header).await.unwrap();
+            let mut target = vec![0u8; header[4] as usize + 2];
+            socket.read_exact(&mut target).await.unwrap();
+            socket
+                .write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0x1f, 0x90])
+                .await
+                .unwrap();
+            target
+        });
+
+        let proxy_url = url::Url::parse(&format!("socks5://{}", addr)).unwrap();
+        let stream = TcpStream::connect(addr).await.unwrap();
+        socks5_connect(stream, &proxy_url, "slack.com", 443)
+            .await
+            .unwrap();
+
+        let target = proxy.await.unwrap();
+        assert_eq!(&target[..9], b"slack.com");
+        assert_eq!(&target[9..], &443u16.to_be_bytes());
+    }
+}
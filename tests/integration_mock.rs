@@ -0,0 +1,1299 @@
+//! Offline end-to-end tests against the in-process mock server
+
+use futures_util::StreamExt;
+use slacko::api::conversations::{ConversationHistoryRequest, ConversationRepliesRequest};
+use slacko::api::socket_mode::{
+    AckMode, CancellationToken, ConnectionState, SocketModeEvent, SocketModeEventType,
+    SocketModePayload,
+};
+use slacko::api::users::UserInfoResponse;
+use slacko::install::{InstallFlow, MemoryInstallationStore};
+use slacko::middleware::{FilePart, MultipartBody, RequestBody};
+use slacko::receiver::HttpReceiver;
+use slacko::signature::SignatureVerifier;
+use slacko::testing::{MockSlack, Recorder, Replayer};
+use slacko::{
+    AuthConfig, CredentialProvider, Event, SlackClient, SlackError, SlackErrorCode, TokenRotation,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, watch};
+
+#[tokio::test]
+async fn test_mock_chat_flow() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let auth = client.auth().test().await.unwrap();
+    assert_eq!(auth.user_id, "U0BOT");
+
+    let posted = client
+        .chat()
+        .post_message("#general", "Hello, mock")
+        .await
+        .unwrap();
+    assert_eq!(posted.channel, "C0GENERAL");
+
+    client
+        .chat()
+        .update_message(&posted.channel, &posted.ts, "Hello, edited")
+        .await
+        .unwrap();
+
+    let history = client.conversations().history("C0GENERAL").await.unwrap();
+    assert_eq!(history.messages.len(), 1);
+    assert_eq!(history.messages[0].text, "Hello, edited");
+    assert!(history.messages[0].edited.is_some());
+
+    client
+        .reactions()
+        .add(&posted.channel, &posted.ts, "wave")
+        .await
+        .unwrap();
+    let reactions = client
+        .reactions()
+        .get(&posted.channel, &posted.ts)
+        .await
+        .unwrap();
+    assert_eq!(reactions.message.reactions.unwrap()[0].name, "wave");
+
+    client
+        .chat()
+        .delete_message(&posted.channel, &posted.ts)
+        .await
+        .unwrap();
+    assert!(slack.messages("C0GENERAL").is_empty());
+}
+
+#[tokio::test]
+async fn test_mock_threads_and_pagination() {
+    let slack = MockSlack::start().await.unwrap();
+    slack.add_user("U1", "alice");
+    slack.add_channel("C1", "random");
+    let client = slack.client();
+
+    for i in 0..25 {
+        slack
+            .post_as_user("C1", "U1", &format!("message {}", i))
+            .unwrap();
+    }
+    let parent = slack.post_as_user("C1", "U1", "parent").unwrap();
+    slack.reply_as_user("C1", &parent, "U1", "reply").unwrap();
+
+    let request = ConversationHistoryRequest {
+        channel: "C1".to_string(),
+        limit: None,
+        cursor: None,
+        oldest: None,
+        latest: None,
+        inclusive: None,
+    };
+    let messages = client
+        .conversations()
+        .history_stream(request)
+        .page_size(10)
+        .collect_all()
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 26);
+    assert_eq!(messages[0].text, "parent");
+    assert_eq!(messages[0].reply_count, Some(1));
+
+    let replies = client.conversations().replies("C1", &parent).await.unwrap();
+    assert_eq!(replies.messages.len(), 2);
+    assert_eq!(replies.messages[1].text, "reply");
+
+    // The parent leads every page, but the stream yields it once
+    for i in 0..4 {
+        slack
+            .reply_as_user("C1", &parent, "U1", &format!("reply {}", i))
+            .unwrap();
+    }
+    let request = ConversationRepliesRequest {
+        channel: "C1".to_string(),
+        ts: parent.clone(),
+        limit: None,
+        cursor: None,
+        latest: None,
+        oldest: None,
+        inclusive: false,
+    };
+    let thread = client
+        .conversations()
+        .replies_stream(request)
+        .page_size(2)
+        .collect_all()
+        .await
+        .unwrap();
+    let texts: Vec<_> = thread.iter().map(|m| m.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        ["parent", "reply", "reply 0", "reply 1", "reply 2", "reply 3"]
+    );
+
+    let history_calls = slack
+        .requests()
+        .iter()
+        .filter(|r| r.method == "conversations.history")
+        .count();
+    assert_eq!(history_calls, 3);
+}
+
+#[tokio::test]
+async fn test_mock_file_upload() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let uploaded = client
+        .files()
+        .upload(&["C0GENERAL"], b"hello file".to_vec(), "hello.txt")
+        .await
+        .unwrap();
+    assert_eq!(uploaded.file.name.as_deref(), Some("hello.txt"));
+    assert_eq!(uploaded.file.size, Some(10));
+
+    let info = client.files().info(&uploaded.file.id).await.unwrap();
+    assert_eq!(info.file.id, uploaded.file.id);
+
+    let messages = slack.messages("C0GENERAL");
+    assert_eq!(messages[0].files.as_ref().unwrap()[0].id, uploaded.file.id);
+}
+
+#[tokio::test]
+async fn test_mock_errors() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let err = client
+        .chat()
+        .post_message("C404", "nowhere")
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SlackErrorCode::ChannelNotFound));
+
+    slack.fail_next("auth.test", "invalid_auth");
+    let err = client.auth().test().await.unwrap_err();
+    assert!(matches!(err, SlackError::AuthError { .. }));
+    assert!(client.auth().test().await.is_ok());
+
+    let anonymous = SlackClient::builder(AuthConfig::bot(""))
+        .base_url(slack.url())
+        .build()
+        .unwrap();
+    let err = anonymous.auth().test().await.unwrap_err();
+    assert_eq!(err.code(), Some(&SlackErrorCode::NotAuthed));
+}
+
+#[tokio::test]
+async fn test_mock_socket_mode() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let listener = tokio::spawn(async move {
+        client
+            .socket_mode()
+            .start(move |event| {
+                let _ = tx.send(event);
+                None
+            })
+            .await
+    });
+
+    tokio::time::timeout(Duration::from_secs(5), slack.wait_for_socket_mode())
+        .await
+        .unwrap();
+    slack.post_as_user("C0GENERAL", "U0BOT", "ping").unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    match event.payload {
+        SocketModePayload::EventsApi(payload) => match payload.event {
+            Some(Event::Message(message)) => assert_eq!(message.text.as_deref(), Some("ping")),
+            other => panic!("Expected a message event, got {:?}", other),
+        },
+        other => panic!("Expected an events_api payload, got {:?}", other),
+    }
+
+    // The ack is sent right after the handler returns
+    for _ in 0..50 {
+        if !slack.acks().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(slack.acks()[0]["envelope_id"], event.envelope_id);
+
+    slack.close_connections();
+    let result = tokio::time::timeout(Duration::from_secs(5), listener)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_socket_mode_async_handlers() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let (release, released) = watch::channel(false);
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let counters = (in_flight.clone(), max_in_flight.clone());
+    let listener = tokio::spawn(async move {
+        client
+            .socket_mode()
+            .ack_mode(AckMode::AfterHandler)
+            .max_concurrency(2)
+            .start_async(move |event| {
+                let (in_flight, max_in_flight) = counters.clone();
+                let mut released = released.clone();
+                let tx = tx.clone();
+                async move {
+                    if let SocketModePayload::Interactive(_) = event.payload {
+                        return Some(serde_json::json!({ "response_action": "clear" }));
+                    }
+                    let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(running, Ordering::SeqCst);
+                    let _ = released.wait_for(|&r| r).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    let _ = tx.send(event.envelope_id);
+                    None
+                }
+            })
+            .await
+    });
+
+    tokio::time::timeout(Duration::from_secs(5), slack.wait_for_socket_mode())
+        .await
+        .unwrap();
+    for text in ["one", "two", "three", "four"] {
+        slack.post_as_user("C0GENERAL", "U0BOT", text).unwrap();
+    }
+
+    // Events are acked before their handlers finish; with two handlers
+    // blocked, the third is acked and the fourth is not read yet
+    for _ in 0..50 {
+        if slack.acks().len() >= 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(slack.acks().len(), 3);
+    assert_eq!(in_flight.load(Ordering::SeqCst), 2);
+
+    release.send(true).unwrap();
+    for _ in 0..4 {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+
+    // Interactions wait for the handler and carry its response
+    let envelope_id = slack.send_envelope(
+        "interactive",
+        serde_json::json!({ "type": "view_submission" }),
+    );
+    for _ in 0..50 {
+        if slack.acks().len() >= 5 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let ack = slack.acks().pop().unwrap();
+    assert_eq!(ack["envelope_id"], envelope_id);
+    assert_eq!(ack["payload"]["response_action"], "clear");
+
+    slack.close_connections();
+    let result = tokio::time::timeout(Duration::from_secs(5), listener)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_socket_mode_connection_pool() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let listener = tokio::spawn(async move {
+        client
+            .socket_mode()
+            .connections(2)
+            .start_with_reconnect(move |event| {
+                let _ = tx.send(event);
+                None
+            })
+            .await
+    });
+
+    for _ in 0..250 {
+        if slack.socket_mode_connections() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(slack.socket_mode_connections(), 2);
+
+    // The mock delivers each envelope on every connection; the pool handles
+    // it once but acks it on both
+    slack.post_as_user("C0GENERAL", "U0BOT", "before").unwrap();
+    let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    for _ in 0..50 {
+        if slack.acks().len() >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(rx.try_recv().is_err());
+    let acks = slack.acks();
+    assert_eq!(acks.len(), 2);
+    assert!(acks
+        .iter()
+        .all(|ack| ack["envelope_id"] == event.envelope_id));
+
+    // A disconnect warning opens replacements before the old connections close
+    tokio::time::timeout(Duration::from_secs(5), slack.refresh_socket_mode())
+        .await
+        .unwrap();
+    let disconnects: Vec<_> = std::iter::from_fn(|| rx.try_recv().ok())
+        .filter(|event| event.envelope_type == SocketModeEventType::Disconnect)
+        .collect();
+    assert_eq!(disconnects.len(), 2);
+    for _ in 0..250 {
+        if slack.socket_mode_connections() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(slack.socket_mode_connections(), 2);
+
+    slack.post_as_user("C0GENERAL", "U0BOT", "after").unwrap();
+    let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    match event.payload {
+        SocketModePayload::EventsApi(payload) => match payload.event {
+            Some(Event::Message(message)) => assert_eq!(message.text.as_deref(), Some("after")),
+            other => panic!("Expected a message event, got {:?}", other),
+        },
+        other => panic!("Expected an events_api payload, got {:?}", other),
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(rx.try_recv().is_err());
+
+    slack.close_connections();
+    let result = tokio::time::timeout(Duration::from_secs(5), listener)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_socket_mode_stream() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let shutdown = CancellationToken::new();
+    let mut events = client.socket_mode().shutdown(shutdown.clone()).connect();
+    tokio::time::timeout(Duration::from_secs(5), slack.wait_for_socket_mode())
+        .await
+        .unwrap();
+
+    // Events are not acked until the consumer acks them
+    slack.post_as_user("C0GENERAL", "U0BOT", "ping").unwrap();
+    let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap()
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(slack.acks().is_empty());
+    event.acker.ack().await.unwrap();
+    assert!(event.acker.is_acked());
+
+    let envelope_id = slack.send_envelope(
+        "interactive",
+        serde_json::json!({ "type": "view_submission" }),
+    );
+    let submission = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap()
+        .unwrap();
+    submission
+        .acker
+        .ack_with(serde_json::json!({ "response_action": "clear" }))
+        .await
+        .unwrap();
+    // Later acks of the same envelope are not sent
+    submission.acker.clone().ack().await.unwrap();
+
+    // After shutdown, envelopes already handed out can still be acked
+    slack.post_as_user("C0GENERAL", "U0BOT", "last").unwrap();
+    let last = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap()
+        .unwrap();
+    shutdown.cancel();
+    last.acker.ack().await.unwrap();
+    drop(last);
+    let end = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap();
+    assert!(end.is_none());
+
+    for _ in 0..50 {
+        if slack.socket_mode_connections() == 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(slack.socket_mode_connections(), 0);
+    let acks = slack.acks();
+    assert_eq!(acks.len(), 3);
+    assert_eq!(acks[0]["envelope_id"], event.envelope_id);
+    assert_eq!(acks[1]["envelope_id"], envelope_id);
+    assert_eq!(acks[1]["payload"]["response_action"], "clear");
+}
+
+#[tokio::test]
+async fn test_socket_mode_fan_out() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let fan_out = client.socket_mode().connect().fan_out(16);
+    let mut first = Box::pin(fan_out.subscribe());
+    let mut second = Box::pin(fan_out.subscribe());
+    tokio::time::timeout(Duration::from_secs(5), slack.wait_for_socket_mode())
+        .await
+        .unwrap();
+
+    slack.post_as_user("C0GENERAL", "U0BOT", "shared").unwrap();
+    let a = tokio::time::timeout(Duration::from_secs(5), first.next())
+        .await
+        .unwrap()
+        .unwrap();
+    let b = tokio::time::timeout(Duration::from_secs(5), second.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(a.envelope_id, b.envelope_id);
+
+    // Subscribers share the acker, so only one ack is sent
+    a.acker.ack().await.unwrap();
+    assert!(b.acker.is_acked());
+    b.acker.ack().await.unwrap();
+    for _ in 0..50 {
+        if !slack.acks().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(slack.acks().len(), 1);
+
+    drop((a, b));
+    fan_out.shutdown();
+    let ends = tokio::time::timeout(
+        Duration::from_secs(5),
+        futures_util::future::join(first.next(), second.next()),
+    )
+    .await
+    .unwrap();
+    assert!(ends.0.is_none() && ends.1.is_none());
+}
+
+#[tokio::test]
+async fn test_socket_mode_start_shutdown() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let shutdown = CancellationToken::new();
+    let api = client
+        .socket_mode()
+        .connections(2)
+        .shutdown(shutdown.clone());
+    let listener = tokio::spawn(async move { api.start_with_reconnect(|_| None).await });
+    for _ in 0..250 {
+        if slack.socket_mode_connections() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    shutdown.cancel();
+    let result = tokio::time::timeout(Duration::from_secs(5), listener)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_socket_mode_health() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client();
+
+    let api = client
+        .socket_mode()
+        .ping_interval(Duration::from_millis(100))
+        .ping_timeout(Duration::from_millis(200));
+    let mut health = api.health();
+    assert_eq!(health.borrow().state(), ConnectionState::Disconnected);
+    let listener = tokio::spawn(async move { api.start_with_reconnect(|_| None).await });
+
+    let wait = Duration::from_secs(5);
+    tokio::time::timeout(wait, health.wait_for(|h| h.connected == 1))
+        .await
+        .unwrap()
+        .unwrap();
+    slack.post_as_user("C0GENERAL", "U0BOT", "ping").unwrap();
+    tokio::time::timeout(wait, health.wait_for(|h| h.last_event_at.is_some()))
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Unanswered pings force a reconnect
+    slack.stall_connections(Duration::from_secs(5));
+    tokio::time::timeout(
+        wait,
+        health.wait_for(|h| h.state() == ConnectionState::Reconnecting),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let reconnected = tokio::time::timeout(wait, health.wait_for(|h| h.connected == 1))
+        .await
+        .unwrap()
+        .unwrap()
+        .clone();
+    assert_eq!(reconnected.reconnects, 1);
+    assert_eq!(reconnected.state(), ConnectionState::Connected);
+
+    listener.abort();
+
+    // Without pings, a connection that stays silent is replaced too
+    let api = slack
+        .client()
+        .socket_mode()
+        .ping_interval(Duration::from_secs(3600))
+        .stale_timeout(Duration::from_millis(200));
+    let mut health = api.health();
+    let listener = tokio::spawn(async move { api.start_with_reconnect(|_| None).await });
+    tokio::time::timeout(wait, health.wait_for(|h| h.reconnects >= 1))
+        .await
+        .unwrap()
+        .unwrap();
+    listener.abort();
+}
+
+#[tokio::test]
+async fn test_mock_rtm() {
+    let slack = MockSlack::start().await.unwrap();
+    slack.add_user("U1", "alice");
+    let client = slack.client();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let listener = tokio::spawn(async move {
+        client
+            .rtm()
+            .start(move |message| {
+                let _ = tx.send(message);
+            })
+            .await
+    });
+
+    tokio::time::timeout(Duration::from_secs(5), slack.wait_for_rtm())
+        .await
+        .unwrap();
+    slack.post_as_user("C0GENERAL", "U1", "hi there").unwrap();
+
+    let message = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(message.text.as_deref(), Some("hi there"));
+    assert_eq!(message.user.as_deref(), Some("U1"));
+
+    slack.close_connections();
+    let _ = tokio::time::timeout(Duration::from_secs(5), listener).await;
+
+    // Streams can be dropped early without draining the server
+    let mut users = slack.client().users().list_stream(Default::default());
+    assert!(users.next().await.unwrap().is_ok());
+}
+
+#[tokio::test]
+async fn test_rtm_typed_events() {
+    let slack = MockSlack::start().await.unwrap();
+    slack.add_user("U1", "alice");
+    let client = slack.client();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let listener = tokio::spawn(async move {
+        client
+            .rtm()
+            .start_events(move |event| {
+                let _ = tx.send(event);
+            })
+            .await
+    });
+
+    tokio::time::timeout(Duration::from_secs(5), slack.wait_for_rtm())
+        .await
+        .unwrap();
+    slack.post_as_user("C0GENERAL", "U1", "typed").unwrap();
+
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        if let Event::Message(message) = event {
+            assert_eq!(message.text.as_deref(), Some("typed"));
+            assert_eq!(message.channel.as_deref(), Some("C0GENERAL"));
+            assert!(message.subtype.is_none());
+            break;
+        }
+    }
+
+    slack.close_connections();
+    let _ = tokio::time::timeout(Duration::from_secs(5), listener).await;
+}
+
+#[tokio::test]
+async fn test_rtm_async_with_reconnect() {
+    let slack = MockSlack::start().await.unwrap();
+    slack.add_user("U1", "alice");
+    let shutdown = CancellationToken::new();
+    let rtm = slack
+        .client()
+        .rtm()
+        .max_concurrency(4)
+        .shutdown(shutdown.clone());
+    let sender = rtm.sender();
+    assert!(sender.typing("C0GENERAL").is_err());
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let listener = tokio::spawn(async move {
+        rtm.start_async_with_reconnect(move |event| {
+            let tx = tx.clone();
+            async move {
+                let _ = tx.send(event);
+            }
+        })
+        .await
+    });
+    async fn next_event(rx: &mut mpsc::UnboundedReceiver<Event>) -> Event {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+    assert!(matches!(next_event(&mut rx).await, Event::Hello));
+
+    // Outbound frames; pongs are matched to their pings, not passed on
+    let rtt = tokio::time::timeout(Duration::from_secs(5), sender.ping())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(rtt < Duration::from_secs(5));
+    sender.typing("C0GENERAL").unwrap();
+    sender.presence_sub(&["U1"]).unwrap();
+    for _ in 0..50 {
+        if slack.rtm_frames().len() >= 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let frames = slack.rtm_frames();
+    assert_eq!(frames[0]["type"], "ping");
+    assert_eq!(frames[1]["type"], "typing");
+    assert_eq!(frames[1]["channel"], "C0GENERAL");
+    assert_eq!(frames[2]["type"], "presence_sub");
+    assert_eq!(frames[2]["ids"], serde_json::json!(["U1"]));
+
+    // `goodbye` is passed on, then the client reconnects right away
+    slack.send_goodbye();
+    assert!(matches!(next_event(&mut rx).await, Event::Goodbye));
+    assert!(matches!(next_event(&mut rx).await, Event::Hello));
+    assert_eq!(
+        slack
+            .requests()
+            .iter()
+            .filter(|r| r.method == "rtm.connect")
+            .count(),
+        2
+    );
+
+    slack.post_as_user("C0GENERAL", "U1", "still here").unwrap();
+    match next_event(&mut rx).await {
+        Event::Message(message) => assert_eq!(message.text.as_deref(), Some("still here")),
+        other => panic!("Expected a message event, got {:?}", other),
+    }
+    sender.typing("C0GENERAL").unwrap();
+
+    // A server close is a dropped socket too: reconnect after the backoff
+    slack.close_connections();
+    assert!(matches!(next_event(&mut rx).await, Event::Hello));
+    assert_eq!(
+        slack
+            .requests()
+            .iter()
+            .filter(|r| r.method == "rtm.connect")
+            .count(),
+        3
+    );
+    assert!(!listener.is_finished());
+
+    // Cancelling the shutdown token ends the listener cleanly
+    shutdown.cancel();
+    let result = tokio::time::timeout(Duration::from_secs(5), listener)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(result.is_ok());
+    assert!(sender.typing("C0GENERAL").is_err());
+}
+
+#[tokio::test]
+async fn test_cassette_record_and_replay() {
+    let path = std::env::temp_dir().join(format!("slacko-cassette-{}.json", std::process::id()));
+
+    // Record one call per body encoding: JSON, GET query, multipart and form
+    let slack = MockSlack::start().await.unwrap();
+    slack.add_user("U1", "alice");
+    let client = slack.client().with_middleware(Recorder::new(&path));
+
+    let posted = client
+        .chat()
+        .post_message("#general", "recorded")
+        .await
+        .unwrap();
+    let user = client.users().info("U1").await.unwrap();
+    let uploaded = client
+        .files()
+        .upload(&["C0GENERAL"], b"cassette".to_vec(), "tape.txt")
+        .await
+        .unwrap();
+    let saved = client.reminders().list_saved().await.unwrap_err();
+    drop(slack);
+
+    let cassette = std::fs::read_to_string(&path).unwrap();
+    assert!(!cassette.contains("xoxb-"));
+    assert!(cassette.contains("[REDACTED]"));
+
+    // Replay with the server gone
+    let replayer = Replayer::from_file(&path).unwrap();
+    let client = SlackClient::builder(AuthConfig::bot("xoxb-other"))
+        .base_url("http://127.0.0.1:9")
+        .build()
+        .unwrap()
+        .with_middleware(replayer);
+
+    let replayed = client
+        .chat()
+        .post_message("#general", "recorded")
+        .await
+        .unwrap();
+    assert_eq!(replayed.ts, posted.ts);
+    assert_eq!(
+        client.users().info("U1").await.unwrap().user.name,
+        user.user.name
+    );
+    let replayed_upload = client
+        .files()
+        .upload(&["C0GENERAL"], b"cassette".to_vec(), "tape.txt")
+        .await
+        .unwrap();
+    assert_eq!(replayed_upload.file.id, uploaded.file.id);
+    let replayed_saved = client.reminders().list_saved().await.unwrap_err();
+    assert_eq!(replayed_saved.code(), saved.code());
+
+    // Requests that were not recorded fail instead of reaching the network
+    let err = client
+        .chat()
+        .post_message("#general", "not recorded")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("No interaction in cassette"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_raw_call() {
+    let slack = MockSlack::start().await.unwrap();
+    slack.add_user("U1", "alice");
+    let client = slack.client();
+
+    let posted = client
+        .call(
+            "chat.postMessage",
+            RequestBody::json(&serde_json::json!({ "channel": "C0GENERAL", "text": "json" }))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(posted["ok"], true);
+    assert_eq!(posted["message"]["text"], "json");
+
+    client
+        .call(
+            "chat.postMessage",
+            RequestBody::form([("channel", "C0GENERAL"), ("text", "form")]),
+        )
+        .await
+        .unwrap();
+
+    let user: UserInfoResponse = client
+        .call_as("users.info", RequestBody::query([("user", "U1")]))
+        .await
+        .unwrap();
+    assert_eq!(user.user.name, "alice");
+
+    let upload = MultipartBody::new()
+        .text("channels", "C0GENERAL")
+        .file(FilePart::new("file", "raw.txt", b"raw".to_vec()));
+    let uploaded = client
+        .call("files.upload", RequestBody::multipart(upload))
+        .await
+        .unwrap();
+    assert_eq!(uploaded["file"]["name"], "raw.txt");
+
+    let texts: Vec<_> = slack
+        .messages("C0GENERAL")
+        .into_iter()
+        .map(|m| m.text)
+        .collect();
+    assert!(texts.contains(&"json".to_string()));
+    assert!(texts.contains(&"form".to_string()));
+
+    let err = client
+        .call("users.info", RequestBody::query([("user", "U404")]))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SlackErrorCode::UserNotFound));
+    assert_eq!(err.method(), Some("users.info"));
+}
+
+#[tokio::test]
+async fn test_socket_mode_with_app_credentials() {
+    let slack = MockSlack::start().await.unwrap();
+    let client = slack.client_with_auth(AuthConfig::app("xapp-mock").with_bot_token("xoxb-mock"));
+
+    let replier = client.clone();
+    let listener = tokio::spawn(async move {
+        client
+            .socket_mode()
+            .start(move |event| {
+                let SocketModePayload::EventsApi(payload) = event.payload else {
+                    return None;
+                };
+                let Some(Event::Message(message)) = payload.event else {
+                    return None;
+                };
+                if message.text.as_deref() != Some("ping") {
+                    return None;
+                }
+                let replier = replier.clone();
+                tokio::spawn(async move {
+                    replier
+                        .chat()
+                        .post_message("C0GENERAL", "pong")
+                        .await
+                        .unwrap();
+                });
+                None
+            })
+            .await
+    });
+
+    tokio::time::timeout(Duration::from_secs(5), slack.wait_for_socket_mode())
+        .await
+        .unwrap();
+    slack.post_as_user("C0GENERAL", "U0BOT", "ping").unwrap();
+
+    for _ in 0..50 {
+        if slack.messages("C0GENERAL").len() >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let tokens: Vec<_> = slack
+        .requests()
+        .into_iter()
+        .map(|r| (r.method, r.token))
+        .collect();
+    assert!(tokens.contains(&(
+        "apps.connections.open".to_string(),
+        Some("xapp-mock".to_string())
+    )));
+    assert!(tokens.contains(&(
+        "chat.postMessage".to_string(),
+        Some("xoxb-mock".to_string())
+    )));
+
+    slack.close_connections();
+    let _ = tokio::time::timeout(Duration::from_secs(5), listener).await;
+}
+
+/// Hands out `xoxb-1`, `xoxb-2`, ... on each call
+struct RotatingProvider(std::sync::atomic::AtomicUsize);
+
+#[async_trait::async_trait]
+impl CredentialProvider for RotatingProvider {
+    async fn credentials(&self) -> slacko::Result<AuthConfig> {
+        let n = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        Ok(AuthConfig::bot(format!("xoxb-{}", n)))
+    }
+}
+
+#[tokio::test]
+async fn test_credentials_refresh_on_invalid_auth() {
+    let slack = MockSlack::start().await.unwrap();
+    let provider = RotatingProvider(Default::default());
+    let client = SlackClient::builder(provider.credentials().await.unwrap())
+        .base_url(slack.url())
+        .credential_provider(provider)
+        .build()
+        .unwrap();
+
+    slack.fail_next("auth.test", "token_expired");
+    client.auth().test().await.unwrap();
+
+    let tokens: Vec<_> = slack.requests().into_iter().map(|r| r.token).collect();
+    assert_eq!(
+        tokens,
+        vec![Some("xoxb-1".to_string()), Some("xoxb-2".to_string())]
+    );
+
+    // Clones share the refreshed credentials
+    client.clone().auth().test().await.unwrap();
+    assert_eq!(
+        slack.requests().last().unwrap().token.as_deref(),
+        Some("xoxb-2")
+    );
+
+    // Errors other than invalid credentials are not retried
+    slack.fail_next("auth.test", "account_inactive");
+    assert!(client.auth().test().await.is_err());
+    assert_eq!(slack.requests().len(), 4);
+
+    // Refreshed credentials that are rejected too are retried only once
+    slack.fail_next("auth.test", "invalid_auth");
+    slack.fail_next("auth.test", "invalid_auth");
+    slack.fail_next("auth.test", "invalid_auth");
+    assert!(client.auth().test().await.is_err());
+    assert_eq!(slack.requests().len(), 6);
+}
+
+#[tokio::test]
+async fn test_token_rotation() {
+    let slack = MockSlack::start().await.unwrap();
+    let rotated = Arc::new(Mutex::new(Vec::new()));
+    let saved = rotated.clone();
+    let rotation = TokenRotation::new("client-id", "client-secret", "xoxe-1-initial")
+        .expires_in(Duration::from_secs(60))
+        .on_refresh(move |tokens| saved.lock().unwrap().push(tokens.clone()));
+    let client = SlackClient::builder(AuthConfig::bot("xoxe.xoxb-initial"))
+        .base_url(slack.url())
+        .token_rotation(rotation)
+        .build()
+        .unwrap();
+
+    // The token is within the refresh window, so concurrent calls share one refresh
+    let calls: Vec<_> = (0..5)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.auth().test().await })
+        })
+        .collect();
+    for call in calls {
+        call.await.unwrap().unwrap();
+    }
+
+    let refreshes = |slack: &MockSlack| {
+        slack
+            .requests()
+            .into_iter()
+            .filter(|r| r.method == "oauth.v2.access")
+            .count()
+    };
+    assert_eq!(refreshes(&slack), 1);
+    // Refreshes don't send the expiring access token
+    assert!(slack
+        .requests()
+        .iter()
+        .filter(|r| r.method == "oauth.v2.access")
+        .all(|r| r.token.as_deref().is_none_or(str::is_empty)));
+    let first = rotated.lock().unwrap()[0].clone();
+    assert!(first.expires_at.is_some());
+    assert!(slack
+        .requests()
+        .iter()
+        .filter(|r| r.method == "auth.test")
+        .all(|r| r.token.as_deref() == Some(first.access_token.as_str())));
+
+    // A token_expired response triggers a refresh and a retry
+    slack.fail_next("auth.test", "token_expired");
+    client.auth().test().await.unwrap();
+    assert_eq!(refreshes(&slack), 2);
+
+    let second = {
+        let rotated = rotated.lock().unwrap();
+        assert_eq!(rotated.len(), 2);
+        rotated[1].clone()
+    };
+    let last = slack.requests().pop().unwrap();
+    assert_eq!(last.token.as_deref(), Some(second.access_token.as_str()));
+    let refresh = slack
+        .requests()
+        .into_iter()
+        .rfind(|r| r.method == "oauth.v2.access")
+        .unwrap();
+    assert_eq!(refresh.params["refresh_token"], first.refresh_token);
+
+    // A rotated token that is rejected too is not rotated again for the same call
+    for _ in 0..2 {
+        slack.fail_next("auth.test", "token_expired");
+    }
+    assert!(client.auth().test().await.is_err());
+    assert_eq!(refreshes(&slack), 3);
+}
+
+#[tokio::test]
+async fn test_token_rotation_async_callback() {
+    let slack = MockSlack::start().await.unwrap();
+    let saved = Arc::new(Mutex::new(Vec::new()));
+    let store = saved.clone();
+    let rotation = TokenRotation::new("client-id", "client-secret", "xoxe-1-initial")
+        .on_refresh_async(move |tokens| {
+            let store = store.clone();
+            async move {
+                store.lock().unwrap().push(tokens.refresh_token);
+                if store.lock().unwrap().len() > 2 {
+                    return Err(SlackError::Other("store is full".to_string()));
+                }
+                Ok(())
+            }
+        });
+    let client = SlackClient::builder(AuthConfig::bot("xoxe.xoxb-initial"))
+        .base_url(slack.url())
+        .token_rotation(rotation)
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        slack.fail_next("auth.test", "token_expired");
+        client.auth().test().await.unwrap();
+    }
+
+    // Each save finished before the next refresh used its refresh token
+    let used: Vec<_> = slack
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "oauth.v2.access")
+        .skip(1)
+        .map(|r| r.params["refresh_token"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(saved.lock().unwrap()[..1], used[..]);
+
+    // A failed save is returned from the call that triggered the refresh
+    slack.fail_next("auth.test", "token_expired");
+    let err = client.auth().test().await.unwrap_err();
+    assert!(err.to_string().contains("store is full"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_install_flow() {
+    let slack = MockSlack::start().await.unwrap();
+    let flow = InstallFlow::new("client-id", "client-secret", MemoryInstallationStore::new())
+        .scopes(["chat:write", "channels:read"])
+        .user_scopes(["search:read"])
+        .api_url(slack.url());
+    let server = flow.serve("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", server.local_addr());
+    let http = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    // The install page redirects to Slack with a fresh state, also set in a cookie
+    let response = http
+        .get(format!("{}/slack/install", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 302);
+    let location = url::Url::parse(response.headers()["location"].to_str().unwrap()).unwrap();
+    let query: std::collections::HashMap<_, _> = location.query_pairs().into_owned().collect();
+    assert_eq!(query["client_id"], "client-id");
+    assert_eq!(query["scope"], "chat:write,channels:read");
+    assert_eq!(query["user_scope"], "search:read");
+    let state = &query["state"];
+    let set_cookie = response.headers()["set-cookie"].to_str().unwrap();
+    assert!(set_cookie.starts_with(&format!("slack_oauth_state={};", state)));
+    assert!(set_cookie.contains("HttpOnly"));
+
+    let redirect = |state: &str, cookie: Option<&str>| {
+        let mut request = http.get(format!(
+            "{}/slack/oauth_redirect?code=abc&state={}",
+            base, state
+        ));
+        if let Some(cookie) = cookie {
+            request = request.header("Cookie", format!("other=1; slack_oauth_state={}", cookie));
+        }
+        request.send()
+    };
+
+    // An unknown state, or a state without the browser's cookie, is rejected
+    // before the code is exchanged
+    let response = redirect("forged", Some("forged")).await.unwrap();
+    assert_eq!(response.status(), 400);
+    let response = redirect(state, None).await.unwrap();
+    assert_eq!(response.status(), 400);
+    let response = redirect(state, Some("other-browser")).await.unwrap();
+    assert_eq!(response.status(), 400);
+    assert!(slack.requests().is_empty());
+
+    let response = redirect(state, Some(state)).await.unwrap();
+    assert_eq!(response.status(), 200);
+    let set_cookie = response.headers()["set-cookie"].to_str().unwrap();
+    assert!(set_cookie.starts_with("slack_oauth_state=;"));
+    assert!(set_cookie.contains("Max-Age=0"));
+    assert!(response.text().await.unwrap().contains("Mock Workspace"));
+
+    // Each state works once
+    let response = redirect(state, Some(state)).await.unwrap();
+    assert_eq!(response.status(), 400);
+
+    let installation = flow
+        .store()
+        .find(None, Some("T0MOCK"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(installation.user_id.as_deref(), Some("U0INSTALLER"));
+    let bot_token = installation.bot_token.unwrap();
+
+    let client = flow.client_for(None, Some("T0MOCK")).await.unwrap();
+    client.auth().test().await.unwrap();
+    assert_eq!(
+        slack.requests().pop().unwrap().token.as_deref(),
+        Some(bot_token.as_str())
+    );
+
+    let err = flow.client_for(None, Some("T0OTHER")).await.err().unwrap();
+    assert!(err.is_not_found());
+}
+
+#[tokio::test]
+async fn test_http_receiver() {
+    let verifier = SignatureVerifier::new("signing-secret");
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let server = HttpReceiver::with_verifier(verifier.clone())
+        .ignore_retries(true)
+        .serve("127.0.0.1:0", move |event: SocketModeEvent| {
+            let response = match &event.payload {
+                SocketModePayload::SlashCommand(command) => {
+                    Some(serde_json::json!({ "text": format!("Got {}", command.command) }))
+                }
+                _ => None,
+            };
+            tx.send(event).unwrap();
+            response
+        })
+        .await
+        .unwrap();
+    let url = format!("http://{}/slack/events", server.local_addr());
+    let http = reqwest::Client::new();
+
+    let post = |body: &'static str, signature: Option<String>, retry: Option<&'static str>| {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .to_string();
+        let signature = signature.unwrap_or_else(|| verifier.sign(&timestamp, body.as_bytes()));
+        let mut request = http
+            .post(&url)
+            .header("X-Slack-Request-Timestamp", timestamp)
+            .header("X-Slack-Signature", signature)
+            .body(body);
+        if let Some(retry) = retry {
+            request = request
+                .header("X-Slack-Retry-Num", retry)
+                .header("X-Slack-Retry-Reason", "http_timeout");
+        }
+        request.send()
+    };
+
+    let response = post(
+        r#"{"type":"url_verification","challenge":"challenge-1"}"#,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), "challenge-1");
+
+    let forged = post(r#"{"type":"event_callback"}"#, Some("v0=00".into()), None)
+        .await
+        .unwrap();
+    assert_eq!(forged.status(), 401);
+
+    // Oversized bodies are rejected before the signature is checked
+    let response = http
+        .post(&url)
+        .body(vec![b'x'; 2 * 1024 * 1024])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 413);
+
+    let event =
+        r#"{"type":"event_callback","event_id":"Ev1","event":{"type":"app_mention","text":"hi"}}"#;
+    let response = post(event, None, None).await.unwrap();
+    assert_eq!(response.status(), 200);
+    let received = rx.recv().await.unwrap();
+    assert_eq!(received.envelope_type, SocketModeEventType::EventsApi);
+    assert_eq!(received.envelope_id, "Ev1");
+    assert!(matches!(received.payload, SocketModePayload::EventsApi(_)));
+
+    // Redeliveries are acknowledged without reaching the handler
+    let response = post(event, None, Some("1")).await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = post(
+        "command=%2Fweather&text=oslo&response_url=https%3A%2F%2Fhooks.slack.com%2Fx&user_id=U1&channel_id=C1",
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["text"], "Got /weather");
+    let received = rx.recv().await.unwrap();
+    assert_eq!(received.envelope_type, SocketModeEventType::SlashCommands);
+    assert!(received.retry_attempt.is_none());
+    assert!(rx.try_recv().is_err());
+}
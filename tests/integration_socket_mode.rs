@@ -169,13 +169,16 @@ async fn test_socket_mode_interactive_envelope() {
 
     // Parse the payload
     let payload: InteractivePayload = serde_json::from_value(envelope.payload.unwrap()).unwrap();
-    assert_eq!(payload.interaction_type, "block_actions");
-    assert!(payload.user.is_some());
-    assert_eq!(payload.user.as_ref().unwrap().id, "U12345");
-    assert!(payload.channel.is_some());
+    assert_eq!(payload.interaction_type(), "block_actions");
+    assert_eq!(payload.user().unwrap().id, "U12345");
+    assert_eq!(payload.trigger_id(), Some("123.456.abc"));
+    let InteractivePayload::BlockActions(payload) = payload else {
+        panic!("expected block_actions");
+    };
     assert_eq!(payload.channel.as_ref().unwrap().id, "C12345");
-    assert_eq!(payload.trigger_id, Some("123.456.abc".to_string()));
-    assert!(!payload.actions.is_empty());
+    assert_eq!(payload.actions.len(), 1);
+    assert_eq!(payload.actions[0].action_id, "button_click");
+    assert_eq!(payload.actions[0].value.value(), Some("clicked"));
 
     println!("✓ Socket Mode interactive envelope parses correctly");
 }
@@ -305,9 +308,16 @@ async fn test_socket_mode_view_submission_envelope() {
     assert_eq!(envelope.envelope_type, "interactive");
 
     let payload: InteractivePayload = serde_json::from_value(envelope.payload.unwrap()).unwrap();
-    assert_eq!(payload.interaction_type, "view_submission");
-    assert!(payload.view.is_some());
-    assert!(payload.trigger_id.is_some());
+    assert_eq!(payload.interaction_type(), "view_submission");
+    assert!(payload.trigger_id().is_some());
+    let InteractivePayload::ViewSubmission(payload) = payload else {
+        panic!("expected view_submission");
+    };
+    assert_eq!(payload.view.callback_id, "my_modal");
+    assert_eq!(
+        payload.view.state.get("block1", "input1").unwrap().value(),
+        Some("user input")
+    );
 
     println!("✓ Socket Mode view_submission envelope parses correctly");
 }